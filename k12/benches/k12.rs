@@ -0,0 +1,69 @@
+//! Throughput and latency of one-shot hashing.
+//!
+//! Only uses [`k12::hash_into`], so this also builds with
+//! `--no-default-features`.
+
+use criterion::{criterion_group, criterion_main, BenchmarkId, Criterion, Throughput};
+
+/// Input sizes around the chunk size of 8192 bytes and beyond.
+const INPUT_SIZES: [usize; 7] = [0, 64, 1024, 8192, 8193, 64 * 1024, 1024 * 1024];
+
+/// Output sizes.
+const OUTPUT_SIZES: [usize; 3] = [32, 64, 1024];
+
+fn input(c: &mut Criterion) {
+    let data = vec![0u8; INPUT_SIZES[INPUT_SIZES.len() - 1]];
+    let mut output = [0u8; 32];
+    let mut group = c.benchmark_group("input");
+    for &size in &INPUT_SIZES {
+        group.throughput(Throughput::Bytes(size as u64));
+        group.bench_with_input(
+            BenchmarkId::from_parameter(size),
+            &data[..size],
+            |b, data| {
+                b.iter(|| k12::hash_into(data, b"", &mut output));
+            },
+        );
+    }
+    group.finish();
+}
+
+fn output(c: &mut Criterion) {
+    let mut output = vec![0u8; OUTPUT_SIZES[OUTPUT_SIZES.len() - 1]];
+    let mut group = c.benchmark_group("output");
+    for &size in &OUTPUT_SIZES {
+        group.throughput(Throughput::Bytes(size as u64));
+        group.bench_with_input(BenchmarkId::from_parameter(size), &size, |b, &size| {
+            b.iter(|| k12::hash_into(b"", b"", &mut output[..size]));
+        });
+    }
+    group.finish();
+}
+
+fn customization(c: &mut Criterion) {
+    let data = [0u8; 1024];
+    let mut output = [0u8; 32];
+    let mut group = c.benchmark_group("customization");
+    group.throughput(Throughput::Bytes(data.len() as u64));
+    for &customization in &[&b""[..], b"customization string of 32 bytes"] {
+        group.bench_with_input(
+            BenchmarkId::from_parameter(customization.len()),
+            customization,
+            |b, customization| {
+                b.iter(|| k12::hash_into(&data, customization, &mut output));
+            },
+        );
+    }
+    group.finish();
+}
+
+fn latency(c: &mut Criterion) {
+    let data = [0u8; 32];
+    let mut output = [0u8; 32];
+    c.bench_function("latency", |b| {
+        b.iter(|| k12::hash_into(&data, b"", &mut output));
+    });
+}
+
+criterion_group!(benches, input, output, customization, latency);
+criterion_main!(benches);
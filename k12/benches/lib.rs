@@ -3,10 +3,76 @@
 
 extern crate test;
 
-use digest::Update;
+use digest::ExtendableOutput;
+use k12::KangarooTwelve;
 use test::Bencher;
 
 digest::bench!(bench1_10, k12::KangarooTwelve, 10);
 digest::bench!(bench2_100, k12::KangarooTwelve, 100);
 digest::bench!(bench3_1000, k12::KangarooTwelve, 1000);
 digest::bench!(bench4_10000, k12::KangarooTwelve, 10000);
+digest::bench!(bench5_100000, k12::KangarooTwelve, 100000);
+
+#[bench]
+fn bench6_new_each(b: &mut Bencher) {
+    let data = [0u8; 64];
+    let mut output = [0u8; 32];
+    b.iter(|| {
+        KangarooTwelve::new()
+            .chain(data)
+            .finalize_xof()
+            .read(&mut output);
+    });
+    b.bytes = data.len() as u64;
+}
+
+#[bench]
+fn bench7_reset_each(b: &mut Bencher) {
+    let data = [0u8; 64];
+    let mut output = [0u8; 32];
+    let mut hasher = KangarooTwelve::new();
+    b.iter(|| {
+        KangarooTwelve::update(&mut hasher, data);
+        hasher.finalize_xof_reset().read(&mut output);
+    });
+    b.bytes = data.len() as u64;
+}
+
+#[cfg(feature = "rayon")]
+static LARGE: [u8; 1 << 20] = [0u8; 1 << 20];
+
+#[cfg(feature = "rayon")]
+fn bench_parallelism(b: &mut Bencher, parallelism: usize) {
+    let mut output = [0u8; 32];
+    b.iter(|| {
+        KangarooTwelve::new()
+            .with_parallelism(parallelism)
+            .chain(&LARGE[..])
+            .finalize_into(&mut output);
+    });
+    b.bytes = LARGE.len() as u64;
+}
+
+#[bench]
+#[cfg(feature = "rayon")]
+fn bench8_parallelism_1(b: &mut Bencher) {
+    bench_parallelism(b, 1);
+}
+
+#[bench]
+#[cfg(feature = "rayon")]
+fn bench8_parallelism_2(b: &mut Bencher) {
+    bench_parallelism(b, 2);
+}
+
+#[bench]
+#[cfg(feature = "rayon")]
+fn bench8_parallelism_4(b: &mut Bencher) {
+    bench_parallelism(b, 4);
+}
+
+#[bench]
+#[cfg(feature = "rayon")]
+fn bench8_parallelism_max(b: &mut Bencher) {
+    bench_parallelism(b, usize::MAX);
+}
@@ -0,0 +1,77 @@
+//! Throughput of SHAKE and SHA-3 compared with the `sha3` crate.
+
+use criterion::{criterion_group, criterion_main, BenchmarkId, Criterion, Throughput};
+use sha3::digest::{Digest, ExtendableOutput, Update, XofReader};
+
+/// Input sizes from a single block to many.
+const INPUT_SIZES: [usize; 4] = [64, 1024, 16 * 1024, 1024 * 1024];
+
+fn shake128(c: &mut Criterion) {
+    let data = vec![0u8; INPUT_SIZES[INPUT_SIZES.len() - 1]];
+    let mut output = [0u8; 32];
+    let mut group = c.benchmark_group("shake128");
+    for &size in &INPUT_SIZES {
+        group.throughput(Throughput::Bytes(size as u64));
+        group.bench_with_input(BenchmarkId::new("k12", size), &data[..size], |b, data| {
+            b.iter(|| {
+                k12::Shake128::new()
+                    .chain(data)
+                    .finalize_xof()
+                    .read(&mut output)
+            });
+        });
+        group.bench_with_input(BenchmarkId::new("sha3", size), &data[..size], |b, data| {
+            b.iter(|| {
+                sha3::Shake128::default()
+                    .chain(data)
+                    .finalize_xof()
+                    .read(&mut output)
+            });
+        });
+    }
+    group.finish();
+}
+
+fn shake256(c: &mut Criterion) {
+    let data = vec![0u8; INPUT_SIZES[INPUT_SIZES.len() - 1]];
+    let mut output = [0u8; 32];
+    let mut group = c.benchmark_group("shake256");
+    for &size in &INPUT_SIZES {
+        group.throughput(Throughput::Bytes(size as u64));
+        group.bench_with_input(BenchmarkId::new("k12", size), &data[..size], |b, data| {
+            b.iter(|| {
+                k12::Shake256::new()
+                    .chain(data)
+                    .finalize_xof()
+                    .read(&mut output)
+            });
+        });
+        group.bench_with_input(BenchmarkId::new("sha3", size), &data[..size], |b, data| {
+            b.iter(|| {
+                sha3::Shake256::default()
+                    .chain(data)
+                    .finalize_xof()
+                    .read(&mut output)
+            });
+        });
+    }
+    group.finish();
+}
+
+fn sha3_256(c: &mut Criterion) {
+    let data = vec![0u8; INPUT_SIZES[INPUT_SIZES.len() - 1]];
+    let mut group = c.benchmark_group("sha3_256");
+    for &size in &INPUT_SIZES {
+        group.throughput(Throughput::Bytes(size as u64));
+        group.bench_with_input(BenchmarkId::new("k12", size), &data[..size], |b, data| {
+            b.iter(|| k12::Sha3_256::new().chain(data).finalize());
+        });
+        group.bench_with_input(BenchmarkId::new("sha3", size), &data[..size], |b, data| {
+            b.iter(|| sha3::Sha3_256::digest(data));
+        });
+    }
+    group.finish();
+}
+
+criterion_group!(benches, shake128, shake256, sha3_256);
+criterion_main!(benches);
@@ -0,0 +1,28 @@
+//! Check the integer encodings of arbitrary values against their
+//! definitions.
+#![no_main]
+
+use k12::fuzzing::{left_encode, right_encode};
+use libfuzzer_sys::fuzz_target;
+
+/// Value of big-endian `bytes`, which must not have leading zeroes.
+fn decode(bytes: &[u8]) -> usize {
+    assert!(bytes.first() != Some(&0), "leading zero in {:?}", bytes);
+    bytes.iter().fold(0, |x, &b| (x << 8) | usize::from(b))
+}
+
+fuzz_target!(|x: usize| {
+    let right = right_encode(x);
+    let (&len, bytes) = right.split_last().unwrap();
+    assert_eq!(usize::from(len), bytes.len());
+    assert_eq!(decode(bytes), x);
+
+    let left = left_encode(x);
+    let (&len, bytes) = left.split_first().unwrap();
+    assert_eq!(usize::from(len), bytes.len());
+    if x == 0 {
+        assert_eq!(bytes, [0]);
+    } else {
+        assert_eq!(decode(bytes), x);
+    }
+});
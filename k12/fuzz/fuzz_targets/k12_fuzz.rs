@@ -0,0 +1,43 @@
+//! Hash arbitrary inputs, checking that incremental and one-shot hashing
+//! agree.
+#![no_main]
+
+use arbitrary::Arbitrary;
+use k12::KangarooTwelve;
+use libfuzzer_sys::fuzz_target;
+
+#[derive(Arbitrary, Debug)]
+struct Input<'a> {
+    data: &'a [u8],
+    customization: &'a [u8],
+    /// Bounded to avoid running out of memory
+    output_len: u16,
+    /// Position at which the data is split into two updates
+    split: usize,
+}
+
+fuzz_target!(|input: Input<'_>| {
+    let output_len = usize::from(input.output_len);
+    let expected = match k12::hash(input.data, input.customization, output_len) {
+        Ok(output) => output,
+        Err(k12::Error::ZeroOutputLength) if output_len == 0 => return,
+        Err(e) => panic!("unexpected error: {}", e),
+    };
+    assert_eq!(expected.len(), output_len);
+
+    let (a, b) = input.data.split_at(input.split % (input.data.len() + 1));
+    let mut output = vec![0u8; output_len];
+    KangarooTwelve::new_with_customization(input.customization)
+        .chain(a)
+        .chain(b)
+        .finalize_into(&mut output);
+    assert_eq!(output, expected);
+
+    let mut reader = KangarooTwelve::new_with_customization(input.customization)
+        .chain(input.data)
+        .finalize_xof();
+    let (first, second) = output.split_at_mut(output_len / 2);
+    reader.read(first);
+    reader.read(second);
+    assert_eq!(output, expected);
+});
@@ -0,0 +1,54 @@
+#ifndef K12_H
+#define K12_H
+
+/* Generated with cbindgen from src/ffi.rs. Do not edit. */
+
+#include <stddef.h>
+#include <stdint.h>
+
+// State of a KangarooTwelve computation.
+//
+// Only ever handled through a pointer returned by [`k12_new`], so its
+// layout is not part of the interface.
+typedef struct K12State K12State;
+
+// Allocate a new state, to be released with [`k12_free`].
+K12State *k12_new(void);
+
+// Input `len` bytes at `data` into the hash function.
+//
+// Returns 0 on success, or -1 if `state` is null or `data` is null while
+// `len` is not zero.
+//
+// # Safety
+//
+// `state` must be null or returned by [`k12_new`] and not yet freed, and
+// `data` must be null or valid for reads of `len` bytes.
+int k12_update(K12State *state, const uint8_t *data, size_t len);
+
+// Write `output_len` bytes of output for the input so far and the
+// customization string of `cust_len` bytes at `customization` to `output`.
+//
+// The state is left unchanged, so that more input can be added. Returns 0
+// on success, or -1 if `state` is null or another pointer is null while its
+// length is not zero.
+//
+// # Safety
+//
+// `state` must be null or returned by [`k12_new`] and not yet freed,
+// `customization` must be null or valid for reads of `cust_len` bytes, and
+// `output` must be null or valid for writes of `output_len` bytes.
+int k12_finalize(const K12State *state,
+                 const uint8_t *customization,
+                 size_t cust_len,
+                 uint8_t *output,
+                 size_t output_len);
+
+// Release a state returned by [`k12_new`]. Does nothing if `state` is null.
+//
+// # Safety
+//
+// `state` must be null or returned by [`k12_new`] and not yet freed.
+void k12_free(K12State *state);
+
+#endif /* K12_H */
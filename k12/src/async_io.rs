@@ -0,0 +1,89 @@
+//! Adapters between the hashers and asynchronous readers and writers.
+
+use crate::{KangarooTwelve, Squeezer};
+use core::{
+    fmt,
+    pin::Pin,
+    task::{Context, Poll},
+};
+use std::io;
+use tokio::io::{AsyncRead, AsyncWrite, ReadBuf};
+
+/// Wrapper around [`KangarooTwelve`] implementing [`AsyncWrite`], so that
+/// input can be hashed straight from an asynchronous reader with
+/// [`tokio::io::copy`].
+///
+/// Absorbing input never blocks, so writes are always ready and never fail.
+#[derive(Clone, Default)]
+pub struct AsyncK12Absorber {
+    inner: KangarooTwelve,
+}
+
+impl AsyncK12Absorber {
+    /// Absorb the written bytes into `inner`.
+    pub fn new(inner: KangarooTwelve) -> Self {
+        Self { inner }
+    }
+
+    /// Reference to the underlying hasher.
+    pub fn get_ref(&self) -> &KangarooTwelve {
+        &self.inner
+    }
+
+    /// Mutable reference to the underlying hasher.
+    pub fn get_mut(&mut self) -> &mut KangarooTwelve {
+        &mut self.inner
+    }
+
+    /// Retrieve the underlying hasher.
+    pub fn into_inner(self) -> KangarooTwelve {
+        self.inner
+    }
+}
+
+impl From<KangarooTwelve> for AsyncK12Absorber {
+    fn from(inner: KangarooTwelve) -> Self {
+        Self::new(inner)
+    }
+}
+
+impl AsyncWrite for AsyncK12Absorber {
+    fn poll_write(
+        mut self: Pin<&mut Self>,
+        _cx: &mut Context<'_>,
+        buf: &[u8],
+    ) -> Poll<io::Result<usize>> {
+        self.inner.update(buf);
+        Poll::Ready(Ok(buf.len()))
+    }
+
+    fn poll_flush(self: Pin<&mut Self>, _cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+        Poll::Ready(Ok(()))
+    }
+
+    fn poll_shutdown(self: Pin<&mut Self>, _cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+        Poll::Ready(Ok(()))
+    }
+}
+
+/// Output never runs out, so reads always fill the whole buffer and are
+/// always ready; use [`tokio::io::AsyncReadExt::take`] to limit the output
+/// when copying it into a writer.
+impl AsyncRead for Squeezer {
+    fn poll_read(
+        mut self: Pin<&mut Self>,
+        _cx: &mut Context<'_>,
+        buf: &mut ReadBuf<'_>,
+    ) -> Poll<io::Result<()>> {
+        let n = buf.remaining();
+        self.read(buf.initialize_unfilled());
+        buf.advance(n);
+        Poll::Ready(Ok(()))
+    }
+}
+
+impl fmt::Debug for AsyncK12Absorber {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("AsyncK12Absorber { ... }")
+    }
+}
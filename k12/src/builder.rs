@@ -0,0 +1,96 @@
+//! Builder for preconfigured [`KangarooTwelve`] instances.
+
+use crate::{
+    tree::{self, K12Params, K12Tree},
+    Error, KangarooTwelve,
+};
+use alloc::vec::Vec;
+use core::fmt;
+
+/// Configuration of [`KangarooTwelve`] instances, which can build any number
+/// of identical hashers.
+///
+/// ```
+/// use k12::KangarooTwelveBuilder;
+///
+/// let builder = KangarooTwelveBuilder::new()
+///     .block_size(4096)?
+///     .customization(b"MyApp v1");
+///
+/// for message in [&b"first"[..], b"second"] {
+///     let output = builder.build().chain(message).finalize_fixed::<32>();
+///     # let _ = output;
+/// }
+/// # Ok::<(), k12::Error>(())
+/// ```
+#[derive(Clone)]
+pub struct KangarooTwelveBuilder {
+    /// Parameters of the tree
+    params: K12Params,
+
+    /// Maximum number of groups of leaves hashed in parallel
+    parallelism: usize,
+
+    /// Customization string to apply
+    customization: Vec<u8>,
+}
+
+impl KangarooTwelveBuilder {
+    /// Create a builder for the default configuration.
+    pub fn new() -> Self {
+        Self {
+            params: tree::K12,
+            parallelism: usize::MAX,
+            customization: Vec::new(),
+        }
+    }
+
+    /// Cut the input into blocks of `block_size` bytes.
+    ///
+    /// Returns [`Error::InvalidBlockSize`] if `block_size` is smaller than the
+    /// rate of 168 bytes. See [`KangarooTwelve::with_block_size`] for the
+    /// implications of a non-default block size.
+    pub fn block_size(mut self, block_size: usize) -> Result<Self, Error> {
+        self.params = self
+            .params
+            .with_chunk_size(block_size)
+            .ok_or(Error::InvalidBlockSize)?;
+        Ok(self)
+    }
+
+    /// Hash at most `parallelism` groups of leaves in parallel with the
+    /// `rayon` feature.
+    ///
+    /// See [`KangarooTwelve::set_parallelism`].
+    pub fn parallelism(mut self, parallelism: usize) -> Self {
+        self.parallelism = parallelism;
+        self
+    }
+
+    /// Use the given customization string.
+    pub fn customization(mut self, customization: impl AsRef<[u8]>) -> Self {
+        self.customization = customization.as_ref().into();
+        self
+    }
+
+    /// Create a new [`KangarooTwelve`] instance with this configuration.
+    pub fn build(&self) -> KangarooTwelve {
+        KangarooTwelve {
+            tree: K12Tree::new(self.params),
+            customization: self.customization.clone(),
+        }
+        .with_parallelism(self.parallelism)
+    }
+}
+
+impl Default for KangarooTwelveBuilder {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl fmt::Debug for KangarooTwelveBuilder {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("KangarooTwelveBuilder { ... }")
+    }
+}
@@ -0,0 +1,212 @@
+//! KangarooTwelve as `const fn`s, for computing hashes of short inputs at
+//! compile time.
+//!
+//! `&mut` references and iterators are not available in `const fn` on the
+//! supported compilers, so states are passed by value and loops are `while`
+//! loops. This is much slower than the runtime path and only meant for
+//! constants.
+
+use crate::lanes::{PI, RC, RHO};
+
+/// Rate of TurboSHAKE128 in bytes.
+const RATE: usize = 1344 / 8;
+
+/// Largest input string `S = M || C || right_encode(|C|)` which fits in the
+/// final node alone.
+const MAX_INPUT: usize = crate::tree::CHUNK_SIZE;
+
+/// Apply one round of Keccak-f[1600] with the round constant `rc`.
+const fn round(mut a: [u64; 25], rc: u64) -> [u64; 25] {
+    // θ
+    let mut c = [0u64; 5];
+    let mut x = 0;
+    while x < 5 {
+        c[x] = a[x] ^ a[x + 5] ^ a[x + 10] ^ a[x + 15] ^ a[x + 20];
+        x += 1;
+    }
+    x = 0;
+    while x < 5 {
+        let d = c[(x + 4) % 5] ^ c[(x + 1) % 5].rotate_left(1);
+        let mut y = 0;
+        while y < 25 {
+            a[x + y] ^= d;
+            y += 5;
+        }
+        x += 1;
+    }
+
+    // ρ and π
+    let mut last = a[1];
+    let mut t = 0;
+    while t < 24 {
+        let next = a[PI[t]];
+        a[PI[t]] = last.rotate_left(RHO[t]);
+        last = next;
+        t += 1;
+    }
+
+    // χ
+    let mut y = 0;
+    while y < 25 {
+        let row = [a[y], a[y + 1], a[y + 2], a[y + 3], a[y + 4]];
+        x = 0;
+        while x < 5 {
+            a[x + y] = row[x] ^ (!row[(x + 1) % 5] & row[(x + 2) % 5]);
+            x += 1;
+        }
+        y += 5;
+    }
+
+    // ι
+    a[0] ^= rc;
+    a
+}
+
+/// Apply the last `rounds` rounds of Keccak-f[1600] to the lanes.
+const fn permute(mut lanes: [u64; 25], rounds: usize) -> [u64; 25] {
+    let mut i = RC.len() - rounds;
+    while i < RC.len() {
+        lanes = round(lanes, RC[i]);
+        i += 1;
+    }
+    lanes
+}
+
+/// XOR `byte` into byte `pos` of the lanes.
+const fn xor_byte(mut lanes: [u64; 25], pos: usize, byte: u8) -> [u64; 25] {
+    lanes[pos / 8] ^= (byte as u64) << (8 * (pos % 8));
+    lanes
+}
+
+/// Byte `pos` of the lanes.
+const fn get_byte(lanes: &[u64; 25], pos: usize) -> u8 {
+    (lanes[pos / 8] >> (8 * (pos % 8))) as u8
+}
+
+/// TurboSHAKE128 sponge absorbing one byte at a time.
+struct Sponge {
+    /// Keccak sponge state
+    lanes: [u64; 25],
+
+    /// Number of bytes absorbed into the current block
+    pos: usize,
+}
+
+impl Sponge {
+    const fn new() -> Self {
+        Self {
+            lanes: [0; 25],
+            pos: 0,
+        }
+    }
+
+    const fn absorb_byte(mut self, byte: u8) -> Self {
+        self.lanes = xor_byte(self.lanes, self.pos, byte);
+        self.pos += 1;
+        if self.pos == RATE {
+            self.lanes = permute(self.lanes, 12);
+            self.pos = 0;
+        }
+        self
+    }
+
+    const fn absorb(mut self, input: &[u8]) -> Self {
+        let mut i = 0;
+        while i < input.len() {
+            self = self.absorb_byte(input[i]);
+            i += 1;
+        }
+        self
+    }
+
+    const fn finalize<const N: usize>(mut self, domain: u8) -> [u8; N] {
+        self.lanes = xor_byte(self.lanes, self.pos, domain);
+        self.lanes = xor_byte(self.lanes, RATE - 1, 0x80);
+        self.lanes = permute(self.lanes, 12);
+
+        let mut output = [0u8; N];
+        let (mut i, mut pos) = (0, 0);
+        while i < N {
+            if pos == RATE {
+                self.lanes = permute(self.lanes, 12);
+                pos = 0;
+            }
+            output[i] = get_byte(&self.lanes, pos);
+            pos += 1;
+            i += 1;
+        }
+        output
+    }
+}
+
+/// Apply the Keccak-p[1600, `rounds`] permutation to `state` in a constant
+/// context, returning the permuted state.
+///
+/// This is [`keccak_p_1600`](crate::keccak_p_1600) for constants; the bytes
+/// of `state` are in the same order.
+///
+/// # Panics
+///
+/// If `rounds` is greater than 24.
+pub const fn keccak_p_1600_const(state: [u8; 200], rounds: usize) -> [u8; 200] {
+    assert!(rounds <= 24, "Keccak-f[1600] has only 24 rounds");
+    let mut lanes = [0u64; 25];
+    let mut pos = 0;
+    while pos < 200 {
+        lanes = xor_byte(lanes, pos, state[pos]);
+        pos += 1;
+    }
+    lanes = permute(lanes, rounds);
+
+    let mut output = [0u8; 200];
+    pos = 0;
+    while pos < 200 {
+        output[pos] = get_byte(&lanes, pos);
+        pos += 1;
+    }
+    output
+}
+
+/// Compute `N` bytes of KangarooTwelve output for `data` with the given
+/// `customization` string in a constant context:
+///
+/// ```
+/// const FOO_HASH: [u8; 32] = k12::hash_const(b"foo", b"");
+///
+/// assert_eq!(FOO_HASH, *k12::hash_fixed::<32>(b"foo", b""));
+/// ```
+///
+/// The output is the same as that of [`hash_fixed`](crate::hash_fixed).
+///
+/// # Panics
+///
+/// If `data`, `customization` and the encoding of its length are together
+/// longer than a chunk of 8192 bytes, so that the input does not fit in the
+/// final node. In a constant this is a compile-time error.
+pub const fn hash_const<const N: usize>(data: &[u8], customization: &[u8]) -> [u8; N] {
+    // `right_encode(|C|)`
+    let mut encoded = [0u8; 9];
+    let (mut len, mut x) = (0, customization.len());
+    while x > 0 {
+        len += 1;
+        x >>= 8;
+    }
+    let mut i = 0;
+    while i < len {
+        encoded[i] = (customization.len() >> (8 * (len - 1 - i))) as u8;
+        i += 1;
+    }
+    encoded[len] = len as u8;
+
+    assert!(
+        data.len() <= MAX_INPUT && customization.len() + len < MAX_INPUT - data.len(),
+        "input too long for hash_const"
+    );
+    let mut sponge = Sponge::new().absorb(data).absorb(customization);
+    i = 0;
+    while i <= len {
+        sponge = sponge.absorb_byte(encoded[i]);
+        i += 1;
+    }
+    sponge.finalize(0x07)
+}
@@ -0,0 +1,78 @@
+//! Typed customization strings.
+
+/// Customization string for a fixed application context, defined once as a
+/// constant:
+///
+/// ```
+/// # #[cfg(feature = "alloc")] {
+/// use k12::{Domain, KangarooTwelve};
+///
+/// const KDF_DOMAIN: Domain = Domain::new("MyApp v1 KDF");
+///
+/// let key = KangarooTwelve::new()
+///     .chain(b"input keying material")
+///     .finalize_with_domain(KDF_DOMAIN, 32)?;
+/// # }
+/// # Ok::<(), k12::Error>(())
+/// ```
+///
+/// The bytes of the string are used exactly like a customization string, so
+/// a [`Domain`] produces the same output as passing those bytes to
+/// [`KangarooTwelve::new_with_customization`][crate::KangarooTwelve::new_with_customization].
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct Domain(&'static str);
+
+impl Domain {
+    /// Create a [`Domain`] for the given context string.
+    pub const fn new(s: &'static str) -> Self {
+        Self(s)
+    }
+
+    /// Context string.
+    pub const fn as_str(&self) -> &'static str {
+        self.0
+    }
+
+    /// Bytes of the context string.
+    pub const fn as_bytes(&self) -> &'static [u8] {
+        self.0.as_bytes()
+    }
+}
+
+/// Values which can be used as the customization string of
+/// [`KangarooTwelve::finalize_with`][crate::KangarooTwelve::finalize_with]:
+/// a [`Domain`], or plain bytes and strings.
+pub trait IntoCustomization {
+    /// Bytes of the customization string.
+    fn customization_bytes(&self) -> &[u8];
+}
+
+impl IntoCustomization for Domain {
+    fn customization_bytes(&self) -> &[u8] {
+        self.as_bytes()
+    }
+}
+
+impl IntoCustomization for [u8] {
+    fn customization_bytes(&self) -> &[u8] {
+        self
+    }
+}
+
+impl<const N: usize> IntoCustomization for [u8; N] {
+    fn customization_bytes(&self) -> &[u8] {
+        self
+    }
+}
+
+impl IntoCustomization for str {
+    fn customization_bytes(&self) -> &[u8] {
+        self.as_bytes()
+    }
+}
+
+impl<T: IntoCustomization + ?Sized> IntoCustomization for &T {
+    fn customization_bytes(&self) -> &[u8] {
+        (**self).customization_bytes()
+    }
+}
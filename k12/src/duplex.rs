@@ -0,0 +1,198 @@
+//! Duplex construction over the Keccak-p[1600, 12] permutation.
+
+use crate::{
+    lanes::{absorb_bytes, squeeze_bytes},
+    platform::keccak_p,
+};
+use core::{cmp::min, fmt};
+#[cfg(feature = "zeroize")]
+use zeroize::{Zeroize, ZeroizeOnDrop};
+
+/// Rate of the duplex in bytes, as for TurboSHAKE128.
+const RATE: usize = 1344 / 8;
+
+/// Number of rounds of the Keccak-p[1600] permutation.
+const ROUNDS: usize = 12;
+
+/// Domain separation byte padding the input before output is squeezed.
+const DOMAIN: u8 = 0x01;
+
+/// Whether the duplex last absorbed input or squeezed output.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+enum Phase {
+    Absorbing,
+    Squeezing,
+}
+
+/// Duplex object over the Keccak-p[1600, 12] permutation with a capacity of
+/// 256 bits, for building authenticated encryption and other protocols on
+/// the permutation of KangarooTwelve.
+///
+/// Input and output can be interleaved freely:
+///
+/// - Input is padded with the domain separation byte `0x01` when output is
+///   requested next, so absorbing a message and squeezing output from a new
+///   duplex gives the same output as TurboSHAKE128 with `D = 0x01`.
+/// - Absorbing after squeezing permutes the state first, discarding the
+///   rest of the block.
+///
+/// As in a sponge, the data of consecutive calls of the same kind is not
+/// delimited: absorbing `"ab"` and then `"c"` is the same as absorbing
+/// `"abc"`. Protocols need to encode the lengths of their fields.
+#[derive(Clone)]
+pub struct Duplex {
+    /// Keccak state
+    state: [u64; 25],
+
+    /// Number of bytes of the current block absorbed or squeezed
+    pos: usize,
+
+    /// Whether the current block is being absorbed or squeezed
+    phase: Phase,
+}
+
+impl Duplex {
+    /// Create a new [`Duplex`] with an all-zero state.
+    pub fn new() -> Self {
+        Self {
+            state: [0u64; 25],
+            pos: 0,
+            phase: Phase::Absorbing,
+        }
+    }
+
+    /// Absorb `data` into the state.
+    pub fn absorb(&mut self, mut data: &[u8]) {
+        if self.phase == Phase::Squeezing {
+            self.permute();
+            self.phase = Phase::Absorbing;
+        }
+
+        while !data.is_empty() {
+            if self.pos == RATE {
+                self.permute();
+            }
+
+            let n = min(RATE - self.pos, data.len());
+            absorb_bytes(&mut self.state, self.pos, &data[..n]);
+            self.pos += n;
+            data = &data[n..];
+        }
+    }
+
+    /// Fill `output` with the next bytes of output.
+    pub fn squeeze(&mut self, output: &mut [u8]) {
+        self.start_squeezing();
+
+        let mut offset = 0;
+        while offset < output.len() {
+            if self.pos == RATE {
+                self.permute();
+            }
+
+            let n = min(RATE - self.pos, output.len() - offset);
+            squeeze_bytes(&self.state, self.pos, &mut output[offset..offset + n]);
+            self.pos += n;
+            offset += n;
+        }
+    }
+
+    /// Squeeze `input.len()` bytes of output, XOR them with `input` into
+    /// `output`, and absorb `input` in their place.
+    ///
+    /// This encrypts the plaintext `input` into `output` while absorbing it,
+    /// as in the Cyclist mode of Xoodyak. It is undone by
+    /// [`squeeze_and_absorb`](Self::squeeze_and_absorb), which leaves the
+    /// duplex in the same state.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `input` and `output` have different lengths.
+    pub fn absorb_and_squeeze(&mut self, input: &[u8], output: &mut [u8]) {
+        self.crypt(input, output, false);
+    }
+
+    /// Squeeze `input.len()` bytes of output, XOR them with `input` into
+    /// `output`, and absorb `output` in their place.
+    ///
+    /// This decrypts the ciphertext `input` produced by
+    /// [`absorb_and_squeeze`](Self::absorb_and_squeeze) into `output`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `input` and `output` have different lengths.
+    pub fn squeeze_and_absorb(&mut self, input: &[u8], output: &mut [u8]) {
+        self.crypt(input, output, true);
+    }
+
+    /// XOR the output with `input` into `output`, absorbing `output` if
+    /// `absorb_output` is set and `input` otherwise.
+    fn crypt(&mut self, input: &[u8], output: &mut [u8], absorb_output: bool) {
+        assert_eq!(input.len(), output.len(), "length mismatch");
+        self.start_squeezing();
+
+        let mut block = [0u8; RATE];
+        let mut offset = 0;
+        while offset < input.len() {
+            if self.pos == RATE {
+                self.permute();
+            }
+
+            let n = min(RATE - self.pos, input.len() - offset);
+            let (input, output) = (&input[offset..offset + n], &mut output[offset..offset + n]);
+            squeeze_bytes(&self.state, self.pos, &mut block[..n]);
+            for ((out, &inp), &key) in output.iter_mut().zip(input).zip(&block[..n]) {
+                *out = inp ^ key;
+            }
+            // Either way the plaintext is absorbed, so that the state becomes
+            // the ciphertext
+            let absorbed = if absorb_output { &*output } else { input };
+            absorb_bytes(&mut self.state, self.pos, absorbed);
+            self.pos += n;
+            offset += n;
+        }
+        #[cfg(feature = "zeroize")]
+        block.zeroize();
+    }
+
+    /// Pad the input absorbed so far if the duplex is absorbing.
+    fn start_squeezing(&mut self) {
+        if self.phase == Phase::Absorbing {
+            if self.pos == RATE {
+                self.permute();
+            }
+            absorb_bytes(&mut self.state, self.pos, &[DOMAIN]);
+            absorb_bytes(&mut self.state, RATE - 1, &[0x80]);
+            self.permute();
+            self.phase = Phase::Squeezing;
+        }
+    }
+
+    /// Apply the permutation and start a new block.
+    fn permute(&mut self) {
+        keccak_p(&mut self.state, ROUNDS);
+        self.pos = 0;
+    }
+}
+
+impl Default for Duplex {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl fmt::Debug for Duplex {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("Duplex { ... }")
+    }
+}
+
+#[cfg(feature = "zeroize")]
+impl Drop for Duplex {
+    fn drop(&mut self) {
+        self.state.zeroize();
+    }
+}
+
+#[cfg(feature = "zeroize")]
+impl ZeroizeOnDrop for Duplex {}
@@ -0,0 +1,348 @@
+//! Integer encodings used by the tree hashing mode and the constructions of
+//! [NIST SP 800-185].
+//!
+//! [NIST SP 800-185]: https://doi.org/10.6028/NIST.SP.800-185
+
+#[cfg(feature = "alloc")]
+use alloc::{vec, vec::Vec};
+use core::mem;
+#[cfg(all(feature = "alloc", feature = "zeroize"))]
+use zeroize::Zeroize;
+
+/// Maximum length of the encoding of a `u128` value.
+const ENCODED_SIZE: usize = mem::size_of::<u128>() + 1;
+
+/// Big-endian bytes of `x` without leading zeroes, starting at `offset` in
+/// the result, and their number.
+const fn be_bytes(x: u128, offset: usize) -> ([u8; ENCODED_SIZE], usize) {
+    let mut len = 0;
+    while len < ENCODED_SIZE - 1 && (x >> (8 * len)) != 0 {
+        len += 1;
+    }
+
+    let mut bytes = [0u8; ENCODED_SIZE];
+    let mut i = 0;
+    while i < len {
+        bytes[offset + i] = (x >> (8 * (len - 1 - i))) as u8;
+        i += 1;
+    }
+    (bytes, len)
+}
+
+/// Output of [`right_encode`] and [`right_encode_u128`].
+#[derive(Clone, Copy, Debug)]
+pub(crate) struct RightEncoded([u8; ENCODED_SIZE], usize);
+
+impl RightEncoded {
+    /// Encoded bytes.
+    pub(crate) fn as_bytes(&self) -> &[u8] {
+        &self.0[..self.1]
+    }
+}
+
+/// Encode `x` as its big-endian bytes without leading zeroes, followed by
+/// the number of those bytes.
+///
+/// As specified for KangarooTwelve, `right_encode(0)` is the single byte
+/// `0x00`, whereas SP 800-185 encodes zero as `0x00 0x01`.
+pub(crate) const fn right_encode(x: usize) -> RightEncoded {
+    right_encode_u128(x as u128)
+}
+
+/// [`right_encode`] for values that do not fit in a `usize`, such as byte
+/// lengths above 4 GiB on 32-bit targets.
+pub(crate) const fn right_encode_u128(x: u128) -> RightEncoded {
+    let (mut bytes, len) = be_bytes(x, 0);
+    bytes[len] = len as u8;
+    RightEncoded(bytes, len + 1)
+}
+
+/// Output of [`left_encode`].
+#[cfg_attr(not(feature = "alloc"), allow(dead_code))]
+#[derive(Clone, Copy, Debug)]
+pub(crate) struct LeftEncoded([u8; ENCODED_SIZE], usize);
+
+#[cfg_attr(not(feature = "alloc"), allow(dead_code))]
+impl LeftEncoded {
+    /// Encoded bytes.
+    pub(crate) fn as_bytes(&self) -> &[u8] {
+        &self.0[..self.1]
+    }
+}
+
+/// Encode `x` as the number of its big-endian bytes, followed by those bytes
+/// without leading zeroes, as specified by `left_encode` in SP 800-185,
+/// Section 2.3.1.
+///
+/// `left_encode(0)` is `0x01 0x00`.
+#[cfg_attr(not(feature = "alloc"), allow(dead_code))]
+pub(crate) const fn left_encode(x: usize) -> LeftEncoded {
+    left_encode_u128(x as u128)
+}
+
+/// [`left_encode`] for values that do not fit in a `usize`, such as bit
+/// lengths of strings above 512 MiB on 32-bit targets.
+#[cfg_attr(not(feature = "alloc"), allow(dead_code))]
+pub(crate) const fn left_encode_u128(x: u128) -> LeftEncoded {
+    let (mut bytes, len) = be_bytes(x, 1);
+    if len == 0 {
+        // `bytes[1]` is already zero
+        bytes[0] = 1;
+        return LeftEncoded(bytes, 2);
+    }
+    bytes[0] = len as u8;
+    LeftEncoded(bytes, len + 1)
+}
+
+/// Size of the inline buffer of [`Encoded`], which is the rate of
+/// KangarooTwelve so that a padded block fits.
+#[cfg(feature = "alloc")]
+const INLINE_SIZE: usize = 1344 / 8;
+
+/// Output of [`encode_string`] and [`bytepad`], kept inline unless it is
+/// longer than [`INLINE_SIZE`].
+#[cfg(feature = "alloc")]
+#[derive(Clone)]
+pub(crate) enum Encoded {
+    Inline([u8; INLINE_SIZE], usize),
+    Heap(Vec<u8>),
+}
+
+#[cfg(feature = "alloc")]
+impl Encoded {
+    /// All-zero output of `len` bytes.
+    fn zeroed(len: usize) -> Self {
+        if len <= INLINE_SIZE {
+            Self::Inline([0u8; INLINE_SIZE], len)
+        } else {
+            Self::Heap(vec![0u8; len])
+        }
+    }
+
+    fn as_mut_slice(&mut self) -> &mut [u8] {
+        match self {
+            Self::Inline(bytes, len) => &mut bytes[..*len],
+            Self::Heap(bytes) => bytes,
+        }
+    }
+}
+
+#[cfg(feature = "alloc")]
+impl AsRef<[u8]> for Encoded {
+    fn as_ref(&self) -> &[u8] {
+        match self {
+            Self::Inline(bytes, len) => &bytes[..*len],
+            Self::Heap(bytes) => bytes,
+        }
+    }
+}
+
+#[cfg(all(feature = "alloc", feature = "zeroize"))]
+impl Drop for Encoded {
+    fn drop(&mut self) {
+        self.as_mut_slice().zeroize();
+    }
+}
+
+/// Encode the string `s` as `left_encode(8 * |s|) || s`, as specified by
+/// `encode_string` in SP 800-185, Section 2.3.2.
+///
+/// # Panics
+///
+/// If the bit length of `s` does not fit in a `usize`.
+#[cfg(feature = "alloc")]
+pub(crate) fn encode_string(s: impl AsRef<[u8]>) -> Encoded {
+    let s = s.as_ref();
+    let bits = s.len().checked_mul(8).expect("string too long to encode");
+    let prefix = left_encode(bits);
+    let prefix = prefix.as_bytes();
+
+    let mut encoded = Encoded::zeroed(prefix.len() + s.len());
+    let (head, tail) = encoded.as_mut_slice().split_at_mut(prefix.len());
+    head.copy_from_slice(prefix);
+    tail.copy_from_slice(s);
+    encoded
+}
+
+/// Prepend `left_encode(w)` to `x` and pad the result with zeroes to a
+/// multiple of `w` bytes, as specified by `bytepad` in SP 800-185,
+/// Section 2.3.3.
+///
+/// # Panics
+///
+/// If `w` is zero.
+#[cfg(feature = "alloc")]
+pub(crate) fn bytepad(x: &[u8], w: usize) -> Encoded {
+    assert!(w > 0, "bytepad width must be positive");
+    let prefix = left_encode(w);
+    let prefix = prefix.as_bytes();
+    let len = prefix.len() + x.len();
+
+    let mut padded = Encoded::zeroed(len + (w - len % w) % w);
+    let bytes = padded.as_mut_slice();
+    bytes[..prefix.len()].copy_from_slice(prefix);
+    bytes[prefix.len()..len].copy_from_slice(x);
+    padded
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    #[cfg(feature = "alloc")]
+    use crate::sponge::{KeccakP1600, Sponge};
+    #[cfg(feature = "alloc")]
+    use hex_literal::hex;
+
+    #[test]
+    fn right_encode_values() {
+        // Usable in constant contexts
+        const ZERO: RightEncoded = right_encode(0);
+
+        assert_eq!(ZERO.as_bytes(), [0x00]);
+        assert_eq!(right_encode(1).as_bytes(), [0x01, 0x01]);
+        assert_eq!(right_encode(255).as_bytes(), [0xFF, 0x01]);
+        assert_eq!(right_encode(256).as_bytes(), [0x01, 0x00, 0x02]);
+        assert_eq!(right_encode(0x0102_0304).as_bytes(), [1, 2, 3, 4, 4]);
+
+        let max = right_encode(usize::MAX);
+        let (last, bytes) = max.as_bytes().split_last().unwrap();
+        assert_eq!(usize::from(*last), mem::size_of::<usize>());
+        assert!(bytes.iter().all(|&b| b == 0xFF));
+    }
+
+    #[test]
+    fn right_encode_u128_values() {
+        assert_eq!(right_encode_u128(0).as_bytes(), [0x00]);
+        assert_eq!(right_encode_u128(1 << 32).as_bytes(), [1, 0, 0, 0, 0, 5]);
+        assert_eq!(
+            right_encode_u128((1 << 64) - 1).as_bytes(),
+            [0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 8]
+        );
+        assert_eq!(
+            right_encode_u128(1 << 64).as_bytes(),
+            [1, 0, 0, 0, 0, 0, 0, 0, 0, 9]
+        );
+        assert_eq!(
+            right_encode_u128(1 << 127).as_bytes(),
+            [0x80, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 16]
+        );
+        assert_eq!(
+            right_encode_u128(u128::MAX).as_bytes(),
+            [
+                0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF,
+                0xFF, 0xFF, 16
+            ]
+        );
+
+        for &x in &[0, 1, 255, 256, 0x0102_0304, usize::MAX] {
+            assert_eq!(
+                right_encode_u128(x as u128).as_bytes(),
+                right_encode(x).as_bytes()
+            );
+        }
+    }
+
+    #[test]
+    fn left_encode_values() {
+        // Usable in constant contexts
+        const ZERO: LeftEncoded = left_encode(0);
+
+        assert_eq!(ZERO.as_bytes(), [0x01, 0x00]);
+        assert_eq!(left_encode(1).as_bytes(), [0x01, 0x01]);
+        assert_eq!(left_encode(255).as_bytes(), [0x01, 0xFF]);
+        assert_eq!(left_encode(256).as_bytes(), [0x02, 0x01, 0x00]);
+        assert_eq!(left_encode(0x0102_0304).as_bytes(), [4, 1, 2, 3, 4]);
+
+        let max = left_encode(usize::MAX);
+        let (first, bytes) = max.as_bytes().split_first().unwrap();
+        assert_eq!(usize::from(*first), mem::size_of::<usize>());
+        assert!(bytes.iter().all(|&b| b == 0xFF));
+
+        assert_eq!(
+            left_encode_u128(1 << 127).as_bytes(),
+            [16, 0x80, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0]
+        );
+        assert_eq!(left_encode_u128(0).as_bytes(), ZERO.as_bytes());
+    }
+
+    #[test]
+    #[cfg(feature = "alloc")]
+    fn encode_string_values() {
+        assert_eq!(encode_string(b"").as_ref(), [0x01, 0x00]);
+        assert_eq!(encode_string(b"KMAC").as_ref(), b"\x01\x20KMAC");
+
+        // Longer than the inline buffer
+        let s = [0xA5; 300];
+        let encoded = encode_string(s);
+        assert!(matches!(encoded, Encoded::Heap(_)));
+        assert_eq!(encoded.as_ref()[..3], [0x02, 0x09, 0x60]);
+        assert_eq!(encoded.as_ref()[3..], s);
+    }
+
+    #[test]
+    #[cfg(feature = "alloc")]
+    fn bytepad_values() {
+        assert_eq!(bytepad(b"", 4).as_ref(), [0x01, 0x04, 0x00, 0x00]);
+        assert_eq!(bytepad(b"ab", 4).as_ref(), [0x01, 0x04, b'a', b'b']);
+        assert_eq!(bytepad(b"abc", 4).as_ref(), b"\x01\x04abc\0\0\0");
+
+        let padded = bytepad(b"", 168);
+        assert_eq!(padded.as_ref().len(), 168);
+        assert_eq!(padded.as_ref()[..2], [0x01, 0xA8]);
+        assert!(padded.as_ref()[2..].iter().all(|&b| b == 0));
+
+        assert_eq!(bytepad(&[0xFF; 200], 168).as_ref().len(), 336);
+    }
+
+    /// cSHAKE128 as specified in SP 800-185, Section 3.3, returning 256 bits.
+    #[cfg(feature = "alloc")]
+    fn cshake128(x: &[&[u8]], n: &[u8], s: &[u8]) -> [u8; 32] {
+        let prefix = [encode_string(n).as_ref(), encode_string(s).as_ref()].concat();
+        let mut sponge = Sponge::<KeccakP1600<24>, 168, 32>::new();
+        sponge.absorb(bytepad(&prefix, 168).as_ref());
+        for x in x {
+            sponge.absorb(x);
+        }
+        let mut output = [0u8; 32];
+        sponge.finalize(0x04).read(&mut output);
+        output
+    }
+
+    /// KMAC128 as specified in SP 800-185, Section 4.3, returning 256 bits.
+    #[cfg(feature = "alloc")]
+    fn kmac128(key: &[u8], x: &[u8], s: &[u8]) -> [u8; 32] {
+        let key = bytepad(encode_string(key).as_ref(), 168);
+        let l = right_encode(256);
+        cshake128(&[key.as_ref(), x, l.as_bytes()], b"KMAC", s)
+    }
+
+    /// Samples from the NIST cSHAKE and KMAC example files.
+    #[test]
+    #[cfg(feature = "alloc")]
+    fn sp800_185_samples() {
+        let data: Vec<u8> = (0..200).collect();
+        let key: Vec<u8> = (0x40..0x60).collect();
+
+        assert_eq!(
+            cshake128(&[&data[..4]], b"", b"Email Signature"),
+            hex!("C1C36925B6409A04F1B504FCBCA9D82B4017277CB5ED2B2065FC1D3814D5AAF5")
+        );
+        assert_eq!(
+            cshake128(&[&data], b"", b"Email Signature"),
+            hex!("C5221D50E4F822D96A2E8881A961420F294B7B24FE3D2094BAED2C6524CC166B")
+        );
+
+        assert_eq!(
+            kmac128(&key, &data[..4], b""),
+            hex!("E5780B0D3EA6F7D3A429C5706AA43A00FADBD7D49628839E3187243F456EE14E")
+        );
+        assert_eq!(
+            kmac128(&key, &data[..4], b"My Tagged Application"),
+            hex!("3B1FBA963CD8B0B59E8C1A6D71888B7143651AF8BA0A7070C0979E2811324AA5")
+        );
+        assert_eq!(
+            kmac128(&key, &data, b"My Tagged Application"),
+            hex!("1F5B4E6CCA02209E0DCB5CA635B89A15E271ECC760071DFD805FAA38F9729230")
+        );
+    }
+}
@@ -0,0 +1,53 @@
+//! Error types.
+
+use core::fmt;
+
+/// Errors returned by this crate.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+#[non_exhaustive]
+pub enum Error {
+    /// Input does not fit in the fixed-size buffer of the hasher.
+    CapacityExceeded,
+
+    /// Domain separation byte outside of the range `0x01..=0x7F`.
+    InvalidDomainSeparator,
+
+    /// Block size smaller than the rate of the sponge.
+    InvalidBlockSize,
+
+    /// Character in a hexadecimal string which is neither a hexadecimal
+    /// digit nor a separator.
+    InvalidHexCharacter,
+
+    /// Hexadecimal string encoding the wrong number of bytes.
+    InvalidOutputLength,
+
+    /// Request for zero bytes of output.
+    ZeroOutputLength,
+
+    /// Allocation of the output failed.
+    AllocationFailed,
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Error::CapacityExceeded => f.write_str("input exceeds buffer capacity"),
+            Error::InvalidDomainSeparator => {
+                f.write_str("domain separation byte must be in the range 0x01..=0x7F")
+            }
+            Error::InvalidBlockSize => {
+                f.write_str("block size must be at least the rate of the sponge")
+            }
+            Error::InvalidHexCharacter => f.write_str("invalid character in hexadecimal string"),
+            Error::InvalidOutputLength => {
+                f.write_str("hexadecimal string encodes the wrong number of bytes")
+            }
+            Error::ZeroOutputLength => f.write_str("output length must not be zero"),
+            Error::AllocationFailed => f.write_str("failed to allocate the output"),
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for Error {}
@@ -0,0 +1,111 @@
+//! C interface to [`KangarooTwelve`].
+//!
+//! The header `include/k12.h` is generated from this module with
+//! [cbindgen] using `cbindgen.toml`.
+//!
+//! [cbindgen]: https://github.com/mozilla/cbindgen
+
+#![allow(unsafe_code)]
+
+use crate::KangarooTwelve;
+use core::slice;
+use std::{boxed::Box, os::raw::c_int};
+
+/// Status returned on success.
+const OK: c_int = 0;
+
+/// Status returned when a required pointer is null.
+const NULL_POINTER: c_int = -1;
+
+/// State of a KangarooTwelve computation.
+///
+/// Only ever handled through a pointer returned by [`k12_new`], so its
+/// layout is not part of the interface.
+pub struct K12State(KangarooTwelve);
+
+/// Bytes at `ptr`, which may only be null if `len` is zero.
+unsafe fn bytes<'a>(ptr: *const u8, len: usize) -> Option<&'a [u8]> {
+    match (ptr.is_null(), len) {
+        (true, 0) => Some(&[]),
+        (true, _) => None,
+        (false, _) => Some(slice::from_raw_parts(ptr, len)),
+    }
+}
+
+/// Allocate a new state, to be released with [`k12_free`].
+#[no_mangle]
+pub extern "C" fn k12_new() -> *mut K12State {
+    Box::into_raw(Box::new(K12State(KangarooTwelve::new())))
+}
+
+/// Input `len` bytes at `data` into the hash function.
+///
+/// Returns 0 on success, or -1 if `state` is null or `data` is null while
+/// `len` is not zero.
+///
+/// # Safety
+///
+/// `state` must be null or returned by [`k12_new`] and not yet freed, and
+/// `data` must be null or valid for reads of `len` bytes.
+#[no_mangle]
+pub unsafe extern "C" fn k12_update(state: *mut K12State, data: *const u8, len: usize) -> c_int {
+    let (state, data) = match (state.as_mut(), bytes(data, len)) {
+        (Some(state), Some(data)) => (state, data),
+        _ => return NULL_POINTER,
+    };
+    state.0.update(data);
+    OK
+}
+
+/// Write `output_len` bytes of output for the input so far and the
+/// customization string of `cust_len` bytes at `customization` to `output`.
+///
+/// The state is left unchanged, so that more input can be added. Returns 0
+/// on success, or -1 if `state` is null or another pointer is null while its
+/// length is not zero.
+///
+/// # Safety
+///
+/// `state` must be null or returned by [`k12_new`] and not yet freed,
+/// `customization` must be null or valid for reads of `cust_len` bytes, and
+/// `output` must be null or valid for writes of `output_len` bytes.
+#[no_mangle]
+pub unsafe extern "C" fn k12_finalize(
+    state: *const K12State,
+    customization: *const u8,
+    cust_len: usize,
+    output: *mut u8,
+    output_len: usize,
+) -> c_int {
+    let (state, customization) = match (state.as_ref(), bytes(customization, cust_len)) {
+        (Some(state), Some(customization)) => (state, customization),
+        _ => return NULL_POINTER,
+    };
+    if output.is_null() && output_len != 0 {
+        return NULL_POINTER;
+    }
+
+    let output: &mut [u8] = match output_len {
+        0 => &mut [],
+        _ => slice::from_raw_parts_mut(output, output_len),
+    };
+    state
+        .0
+        .tree
+        .clone()
+        .finalize_with_customization(customization)
+        .read(output);
+    OK
+}
+
+/// Release a state returned by [`k12_new`]. Does nothing if `state` is null.
+///
+/// # Safety
+///
+/// `state` must be null or returned by [`k12_new`] and not yet freed.
+#[no_mangle]
+pub unsafe extern "C" fn k12_free(state: *mut K12State) {
+    if !state.is_null() {
+        drop(Box::from_raw(state));
+    }
+}
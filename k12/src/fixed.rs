@@ -0,0 +1,79 @@
+//! KangarooTwelve with a fixed output size.
+
+use crate::{K12Output, KangarooTwelve};
+#[cfg(feature = "digest")]
+use digest::{
+    consts::{U168, U32, U64},
+    generic_array::GenericArray,
+    BlockInput, FixedOutputDirty, Reset, Update,
+};
+#[cfg(feature = "zeroize")]
+use zeroize::ZeroizeOnDrop;
+
+macro_rules! impl_fixed {
+    ($name:ident, $len:expr, $output_size:ident, $doc:expr) => {
+        #[allow(non_camel_case_types)]
+        #[derive(Clone, Debug, Default)]
+        #[doc = $doc]
+        pub struct $name(KangarooTwelve);
+
+        impl $name {
+            #[doc = concat!("Create a new [`", stringify!($name), "`] instance.")]
+            pub fn new() -> Self {
+                Self::default()
+            }
+
+            /// Input data into the hash function.
+            pub fn update(&mut self, bytes: impl AsRef<[u8]>) {
+                self.0.update(bytes);
+            }
+
+            /// Input data into the hash function, returning the updated hasher.
+            pub fn chain(mut self, bytes: impl AsRef<[u8]>) -> Self {
+                self.update(bytes);
+                self
+            }
+
+            /// Retrieve the hash and consume the hasher.
+            pub fn finalize(self) -> K12Output<$len> {
+                self.0.finalize_fixed()
+            }
+        }
+
+        /// The block size is the rate of the sponge, as for SHA-3, which is
+        /// what HMAC pads the key to.
+        #[cfg(feature = "digest")]
+        impl BlockInput for $name {
+            type BlockSize = U168;
+        }
+
+        #[cfg(feature = "digest")]
+        impl Update for $name {
+            fn update(&mut self, bytes: impl AsRef<[u8]>) {
+                $name::update(self, bytes);
+            }
+        }
+
+        #[cfg(feature = "digest")]
+        impl FixedOutputDirty for $name {
+            type OutputSize = $output_size;
+
+            fn finalize_into_dirty(&mut self, out: &mut GenericArray<u8, Self::OutputSize>) {
+                self.0.squeezer().read(out);
+            }
+        }
+
+        #[cfg(feature = "digest")]
+        impl Reset for $name {
+            fn reset(&mut self) {
+                self.0.reset();
+            }
+        }
+
+        #[cfg(feature = "zeroize")]
+        impl ZeroizeOnDrop for $name {}
+    };
+}
+
+impl_fixed!(K12_256, 32, U32, "KangarooTwelve with a 256-bit output.");
+impl_fixed!(K12_512, 64, U64, "KangarooTwelve with a 512-bit output.");
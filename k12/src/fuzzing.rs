@@ -0,0 +1,15 @@
+//! Internals exposed to the fuzz targets in `fuzz/` when built with
+//! `--cfg fuzzing`, as `cargo fuzz` does. Not part of the public API.
+
+use crate::encoding;
+use alloc::vec::Vec;
+
+/// Bytes of `right_encode(x)`.
+pub fn right_encode(x: usize) -> Vec<u8> {
+    encoding::right_encode(x).as_bytes().into()
+}
+
+/// Bytes of `left_encode(x)`.
+pub fn left_encode(x: usize) -> Vec<u8> {
+    encoding::left_encode(x).as_bytes().into()
+}
@@ -0,0 +1,45 @@
+//! Two-phase key derivation in the style of HKDF (RFC 5869) built on
+//! KangarooTwelve alone.
+
+use crate::{encoding::encode_string, Domain, Error, K12Output, KangarooTwelve};
+use alloc::vec::Vec;
+
+/// Customization string of [`k12_extract`].
+const EXTRACT: Domain = Domain::new("K12-KDF-Extract");
+
+/// Customization string of [`k12_expand`].
+const EXPAND: Domain = Domain::new("K12-KDF-Expand");
+
+/// Extract a pseudo-random key of 32 bytes from the input keying material
+/// `ikm`, like HKDF-Extract.
+///
+/// The PRK is the KangarooTwelve output for `encode_string(salt) || ikm`
+/// with the customization string `"K12-KDF-Extract"`. The salt is encoded
+/// with its length, so it cannot be confused with the start of `ikm`. It
+/// may be empty, but a random salt strengthens the extraction as it does
+/// for HKDF.
+pub fn k12_extract(salt: &[u8], ikm: &[u8]) -> K12Output<32> {
+    KangarooTwelve::new_with_customization(EXTRACT.as_bytes())
+        .chain(encode_string(salt))
+        .chain(ikm)
+        .finalize_fixed()
+}
+
+/// Expand the pseudo-random key `prk` into `output_len` bytes of keying
+/// material for the context `info`, like HKDF-Expand.
+///
+/// The output is the KangarooTwelve output for `encode_string(prk) || info`
+/// with the customization string `"K12-KDF-Expand"`. As with HKDF-Expand,
+/// a shorter output is a prefix of a longer one for the same `prk` and
+/// `info`, so keys for different purposes should differ in `info`.
+///
+/// `prk` should be the output of [`k12_extract`], or another uniformly
+/// random key of at least 16 bytes. Unlike HKDF-Expand the output length is
+/// not limited. Returns [`Error::ZeroOutputLength`] if `output_len` is zero,
+/// or [`Error::AllocationFailed`] if the output cannot be allocated.
+pub fn k12_expand(prk: &[u8], info: &[u8], output_len: usize) -> Result<Vec<u8>, Error> {
+    KangarooTwelve::new()
+        .chain(encode_string(prk))
+        .chain(info)
+        .finalize_with(EXPAND, output_len)
+}
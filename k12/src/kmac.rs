@@ -0,0 +1,157 @@
+//! KMAC12: a message authentication code built on KangarooTwelve.
+
+use crate::{
+    ct_eq_slice,
+    encoding::{bytepad, encode_string, right_encode_u128},
+    output_vec, Error, KangarooTwelve,
+};
+use alloc::vec::Vec;
+use core::fmt;
+#[cfg(feature = "digest")]
+use crypto_mac::{
+    consts::U32, generic_array::GenericArray, InvalidKeyLength, Key, Mac, NewMac, Output,
+};
+#[cfg(feature = "zeroize")]
+use zeroize::ZeroizeOnDrop;
+
+/// Name of the function, encoded into the customization string.
+const FUNCTION_NAME: &[u8] = b"KMAC";
+
+/// Width the encoded key is padded to, which is the rate of KangarooTwelve.
+const KEY_BLOCK_SIZE: usize = 1344 / 8;
+
+/// The KMAC12 message authentication code.
+///
+/// Follows the construction of KMAC in NIST SP 800-185 with KangarooTwelve
+/// in place of cSHAKE: a tag of `L` bytes for the message `X` with the key
+/// `K` and customization string `S` is the KangarooTwelve output for the
+/// input
+///
+/// `bytepad(encode_string(K), 168) || X || right_encode(8 * L)`
+///
+/// with the customization string `encode_string("KMAC") || encode_string(S)`.
+/// The customization string is never empty, so tags cannot be confused with
+/// plain KangarooTwelve hashes.
+///
+/// With the `digest` feature this implements the [`Mac`] and [`NewMac`]
+/// traits with 256-bit tags.
+#[derive(Clone)]
+pub struct Kmac12 {
+    /// Hasher which has absorbed the encoded key
+    keyed: KangarooTwelve,
+
+    /// Hasher absorbing the message
+    k12: KangarooTwelve,
+}
+
+impl Kmac12 {
+    /// Create a new [`Kmac12`] instance with the given key.
+    pub fn new(key: impl AsRef<[u8]>) -> Self {
+        Self::new_with_customization(key, [])
+    }
+
+    /// Create a new [`Kmac12`] instance with the given key and customization.
+    pub fn new_with_customization(key: impl AsRef<[u8]>, customization: impl AsRef<[u8]>) -> Self {
+        let mut c = Vec::new();
+        c.extend_from_slice(encode_string(FUNCTION_NAME).as_ref());
+        c.extend_from_slice(encode_string(customization).as_ref());
+
+        let key = bytepad(encode_string(key).as_ref(), KEY_BLOCK_SIZE);
+        let keyed = KangarooTwelve::new_with_customization(c).chain(key);
+        Self {
+            k12: keyed.clone(),
+            keyed,
+        }
+    }
+
+    /// Input message data into the MAC.
+    pub fn update(&mut self, bytes: impl AsRef<[u8]>) {
+        self.k12.update(bytes);
+    }
+
+    /// Input message data into the MAC, returning the updated instance.
+    pub fn chain(mut self, bytes: impl AsRef<[u8]>) -> Self {
+        self.update(bytes);
+        self
+    }
+
+    /// Discard the message absorbed so far, keeping the key and
+    /// customization string.
+    pub fn reset(&mut self) {
+        self.k12 = self.keyed.clone();
+    }
+
+    /// Compute a tag of `output_len` bytes and consume the instance.
+    ///
+    /// Returns [`Error::ZeroOutputLength`] if `output_len` is zero, or
+    /// [`Error::AllocationFailed`] if the tag cannot be allocated.
+    pub fn tag(self, output_len: usize) -> Result<Vec<u8>, Error> {
+        let mut tag = output_vec(output_len)?;
+        self.tag_into(&mut tag);
+        Ok(tag)
+    }
+
+    /// Check in constant time whether `tag` is the correct tag for the input
+    /// and consume the instance.
+    ///
+    /// Empty tags are always rejected.
+    pub fn verify(self, tag: &[u8]) -> bool {
+        match self.tag(tag.len()) {
+            Ok(expected) => ct_eq_slice(&expected, tag),
+            Err(_) => false,
+        }
+    }
+
+    /// Fill `output` with the tag of `output.len()` bytes and consume the
+    /// instance.
+    fn tag_into(self, output: &mut [u8]) {
+        // Cannot overflow, unlike the same product in a `usize`
+        let bits = 8 * output.len() as u128;
+        let mut k12 = self.k12;
+        k12.update(right_encode_u128(bits).as_bytes());
+        k12.finalize_xof().read(output);
+    }
+}
+
+impl fmt::Debug for Kmac12 {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("Kmac12 { ... }")
+    }
+}
+
+/// Keys of any length are accepted.
+#[cfg(feature = "digest")]
+impl NewMac for Kmac12 {
+    type KeySize = U32;
+
+    fn new(key: &Key<Self>) -> Self {
+        Kmac12::new(key)
+    }
+
+    fn new_varkey(key: &[u8]) -> Result<Self, InvalidKeyLength> {
+        Ok(Kmac12::new(key))
+    }
+}
+
+#[cfg(feature = "digest")]
+impl Mac for Kmac12 {
+    type OutputSize = U32;
+
+    fn update(&mut self, data: &[u8]) {
+        Kmac12::update(self, data);
+    }
+
+    fn reset(&mut self) {
+        Kmac12::reset(self);
+    }
+
+    fn finalize(self) -> Output<Self> {
+        let mut tag = GenericArray::default();
+        self.tag_into(&mut tag);
+        Output::new(tag)
+    }
+}
+
+// The hashers zeroize their state when dropped
+#[cfg(feature = "zeroize")]
+impl ZeroizeOnDrop for Kmac12 {}
@@ -1,5 +1,7 @@
 #![allow(clippy::unreadable_literal)]
 
+use core::{cmp::min, convert::TryInto};
+
 macro_rules! REPEAT4 {
     ($e: expr) => {
         $e;
@@ -63,7 +65,20 @@ macro_rules! FOR5 {
     };
 }
 
-pub const RC: [u64; 12] = [
+/// Round constants of Keccak-f[1600]; Keccak-p[1600, n_r] uses the last `n_r`.
+pub const RC: [u64; 24] = [
+    0x0000000000000001,
+    0x0000000000008082,
+    0x800000000000808a,
+    0x8000000080008000,
+    0x000000000000808b,
+    0x0000000080000001,
+    0x8000000080008081,
+    0x8000000000008009,
+    0x000000000000008a,
+    0x0000000000000088,
+    0x0000000080008009,
+    0x000000008000000a,
     0x000000008000808b,
     0x800000000000008b,
     0x8000000000008089,
@@ -86,48 +101,156 @@ pub const PI: [usize; 24] = [
     10, 7, 11, 17, 18, 3, 5, 16, 8, 21, 24, 4, 15, 23, 19, 13, 12, 2, 20, 14, 22, 9, 6, 1,
 ];
 
-pub fn keccak(lanes: &mut [u64; 25]) {
+/// XOR `input` into the state starting at byte `offset`, where the bytes of
+/// the state are the little-endian bytes of its lanes.
+pub fn absorb_bytes(lanes: &mut [u64; 25], offset: usize, mut input: &[u8]) {
+    debug_assert!(offset + input.len() <= 200);
+    let (mut i, shift) = (offset / 8, offset % 8);
+
+    // Partial lane at the start
+    if shift != 0 {
+        let n = min(8 - shift, input.len());
+        for (j, byte) in input[..n].iter().enumerate() {
+            lanes[i] ^= u64::from(*byte) << (8 * (shift + j));
+        }
+        input = &input[n..];
+        i += 1;
+    }
+
+    let full = input.len() / 8;
+    let (body, tail) = input.split_at(8 * full);
+    for (lane, chunk) in lanes[i..i + full].iter_mut().zip(body.chunks_exact(8)) {
+        *lane ^= u64::from_le_bytes(chunk.try_into().unwrap());
+    }
+    i += full;
+
+    // Partial lane at the end
+    for (j, byte) in tail.iter().enumerate() {
+        lanes[i] ^= u64::from(*byte) << (8 * j);
+    }
+}
+
+/// Copy the bytes of the state starting at byte `offset` to `output`, where
+/// the bytes of the state are the little-endian bytes of its lanes.
+pub fn squeeze_bytes(lanes: &[u64; 25], offset: usize, mut output: &mut [u8]) {
+    debug_assert!(offset + output.len() <= 200);
+    let (mut i, shift) = (offset / 8, offset % 8);
+
+    // Partial lane at the start
+    if shift != 0 {
+        let n = min(8 - shift, output.len());
+        output[..n].copy_from_slice(&lanes[i].to_le_bytes()[shift..shift + n]);
+        output = &mut output[n..];
+        i += 1;
+    }
+
+    let full = output.len() / 8;
+    let (body, tail) = output.split_at_mut(8 * full);
+    for (lane, chunk) in lanes[i..i + full].iter().zip(body.chunks_exact_mut(8)) {
+        chunk.copy_from_slice(&lane.to_le_bytes());
+    }
+    i += full;
+
+    // Partial lane at the end
+    if !tail.is_empty() {
+        tail.copy_from_slice(&lanes[i].to_le_bytes()[..tail.len()]);
+    }
+}
+
+/// Apply the Keccak-p[1600, `rounds`] permutation, i.e. the last `rounds`
+/// rounds of Keccak-f[1600].
+pub fn keccak_p(lanes: &mut [u64; 25], rounds: usize) {
+    debug_assert!(rounds <= RC.len());
+    let rcs = &RC[RC.len() - rounds..];
+
+    // Unrolling pairs of rounds is faster than both the plain loop and
+    // unrolling all 12 rounds of KangarooTwelve
+    if rcs.len() % 2 == 1 {
+        round(lanes, rcs[0]);
+    }
+    for pair in rcs[rcs.len() % 2..].chunks_exact(2) {
+        round(lanes, pair[0]);
+        round(lanes, pair[1]);
+    }
+}
+
+/// Apply one round of Keccak-f[1600] with the round constant `rc`.
+#[inline(always)]
+pub(crate) fn round(lanes: &mut [u64; 25], rc: u64) {
     let mut c = [0u64; 5];
     let (mut x, mut y): (usize, usize);
 
-    #[allow(clippy::needless_range_loop)]
-    for round in 0..12 {
-        // θ
-        FOR5!(x, 1, {
-            c[x] = lanes[x] ^ lanes[x + 5] ^ lanes[x + 10] ^ lanes[x + 15] ^ lanes[x + 20];
+    // θ
+    FOR5!(x, 1, {
+        c[x] = lanes[x] ^ lanes[x + 5] ^ lanes[x + 10] ^ lanes[x + 15] ^ lanes[x + 20];
+    });
+
+    FOR5!(x, 1, {
+        FOR5!(y, 5, {
+            lanes[x + y] ^= c[(x + 4) % 5] ^ c[(x + 1) % 5].rotate_left(1);
         });
+    });
 
+    // ρ and π
+    let mut a = lanes[1];
+    x = 0;
+    REPEAT24!(
+        {
+            c[0] = lanes[PI[x]];
+            lanes[PI[x]] = a.rotate_left(RHO[x]);
+        },
+        {
+            a = c[0];
+            x += 1;
+        }
+    );
+
+    // χ
+    FOR5!(y, 5, {
+        FOR5!(x, 1, {
+            c[x] = lanes[x + y];
+        });
         FOR5!(x, 1, {
-            FOR5!(y, 5, {
-                lanes[x + y] ^= c[(x + 4) % 5] ^ c[(x + 1) % 5].rotate_left(1);
-            });
+            lanes[x + y] = c[x] ^ ((!c[(x + 1) % 5]) & c[(x + 2) % 5]);
         });
+    });
+
+    // ι
+    lanes[0] ^= rc;
+}
 
-        // ρ and π
-        let mut a = lanes[1];
-        x = 0;
-        REPEAT24!(
-            {
-                c[0] = lanes[PI[x]];
-                lanes[PI[x]] = a.rotate_left(RHO[x]);
-            },
-            {
-                a = c[0];
-                x += 1;
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn bytes_of_lanes() {
+        let mut lanes = [0u64; 25];
+        for (i, lane) in lanes.iter_mut().enumerate() {
+            *lane = 0x0706_0504_0302_0100 + 0x0808_0808_0808_0808 * i as u64;
+        }
+        let bytes: [u8; 200] = {
+            let mut bytes = [0u8; 200];
+            for (i, b) in bytes.iter_mut().enumerate() {
+                *b = i as u8;
             }
-        );
+            bytes
+        };
 
-        // χ
-        FOR5!(y, 5, {
-            FOR5!(x, 1, {
-                c[x] = lanes[x + y];
-            });
-            FOR5!(x, 1, {
-                lanes[x + y] = c[x] ^ ((!c[(x + 1) % 5]) & c[(x + 2) % 5]);
-            });
-        });
+        for offset in 0..200 {
+            for len in 0..=(200 - offset) {
+                let mut output = [0u8; 200];
+                squeeze_bytes(&lanes, offset, &mut output[..len]);
+                assert_eq!(output[..len], bytes[offset..offset + len]);
 
-        // ι
-        lanes[0] ^= RC[round];
+                let mut state = lanes;
+                absorb_bytes(&mut state, offset, &bytes[offset..offset + len]);
+                let mut output = [0xFFu8; 200];
+                squeeze_bytes(&state, 0, &mut output);
+                assert!(output[..offset].iter().eq(&bytes[..offset]));
+                assert!(output[offset..offset + len].iter().all(|&b| b == 0));
+                assert!(output[offset + len..].iter().eq(&bytes[offset + len..]));
+            }
+        }
     }
 }
@@ -0,0 +1,188 @@
+//! Keccak-p[800] on 25 lanes of 32 bits, for targets without fast 64-bit
+//! arithmetic such as the Cortex-M0 and Cortex-M3.
+//!
+//! The steps are those of [`lanes`](crate::lanes) with 32-bit lanes, so only
+//! 32-bit rotations, XORs and ANDs are used.
+//!
+//! Code size of [`keccak_p`] for `thumbv6m-none-eabi`, and of the
+//! Keccak-p[1600] backend selected for the same target, as measured with
+//! `nm`:
+//!
+//! | `opt-level` | Keccak-p[800] | Keccak-p[1600] |
+//! |-------------|---------------|----------------|
+//! | `3`         | 892 B         | 2704 B         |
+//! | `"s"`       | 892 B         | 2328 B         |
+
+use crate::lanes::{self, PI};
+use core::{cmp::min, convert::TryInto};
+
+/// Round constants of Keccak-f[800], the low halves of those of
+/// Keccak-f[1600]; Keccak-p[800, n_r] uses the last `n_r`.
+pub const RC: [u32; 22] = {
+    let mut rc = [0; 22];
+    let mut i = 0;
+    while i < 22 {
+        rc[i] = lanes::RC[i] as u32;
+        i += 1;
+    }
+    rc
+};
+
+/// Rotation offsets of ρ modulo the lane size.
+const RHO: [u32; 24] = {
+    let mut rho = [0; 24];
+    let mut i = 0;
+    while i < 24 {
+        rho[i] = lanes::RHO[i] % 32;
+        i += 1;
+    }
+    rho
+};
+
+/// XOR `input` into the state starting at byte `offset`, where the bytes of
+/// the state are the little-endian bytes of its lanes.
+///
+/// Any state of 32-bit lanes is accepted, so that Xoodoo can share this.
+pub fn absorb_bytes(lanes: &mut [u32], offset: usize, mut input: &[u8]) {
+    debug_assert!(offset + input.len() <= 4 * lanes.len());
+    let (mut i, shift) = (offset / 4, offset % 4);
+
+    // Partial lane at the start
+    if shift != 0 {
+        let n = min(4 - shift, input.len());
+        for (j, byte) in input[..n].iter().enumerate() {
+            lanes[i] ^= u32::from(*byte) << (8 * (shift + j));
+        }
+        input = &input[n..];
+        i += 1;
+    }
+
+    let full = input.len() / 4;
+    let (body, tail) = input.split_at(4 * full);
+    for (lane, chunk) in lanes[i..i + full].iter_mut().zip(body.chunks_exact(4)) {
+        *lane ^= u32::from_le_bytes(chunk.try_into().unwrap());
+    }
+    i += full;
+
+    // Partial lane at the end
+    for (j, byte) in tail.iter().enumerate() {
+        lanes[i] ^= u32::from(*byte) << (8 * j);
+    }
+}
+
+/// Copy the bytes of the state starting at byte `offset` to `output`, where
+/// the bytes of the state are the little-endian bytes of its lanes.
+pub fn squeeze_bytes(lanes: &[u32], offset: usize, mut output: &mut [u8]) {
+    debug_assert!(offset + output.len() <= 4 * lanes.len());
+    let (mut i, shift) = (offset / 4, offset % 4);
+
+    // Partial lane at the start
+    if shift != 0 {
+        let n = min(4 - shift, output.len());
+        output[..n].copy_from_slice(&lanes[i].to_le_bytes()[shift..shift + n]);
+        output = &mut output[n..];
+        i += 1;
+    }
+
+    let full = output.len() / 4;
+    let (body, tail) = output.split_at_mut(4 * full);
+    for (lane, chunk) in lanes[i..i + full].iter().zip(body.chunks_exact_mut(4)) {
+        chunk.copy_from_slice(&lane.to_le_bytes());
+    }
+    i += full;
+
+    // Partial lane at the end
+    if !tail.is_empty() {
+        tail.copy_from_slice(&lanes[i].to_le_bytes()[..tail.len()]);
+    }
+}
+
+/// Apply the Keccak-p[800, `rounds`] permutation, i.e. the last `rounds`
+/// rounds of Keccak-f[800].
+pub fn keccak_p(lanes: &mut [u32; 25], rounds: usize) {
+    debug_assert!(rounds <= RC.len());
+    for &rc in &RC[RC.len() - rounds..] {
+        round(lanes, rc);
+    }
+}
+
+/// Apply one round of Keccak-f[800] with the round constant `rc`.
+#[inline(always)]
+fn round(lanes: &mut [u32; 25], rc: u32) {
+    let mut c = [0u32; 5];
+    let (mut x, mut y): (usize, usize);
+
+    // θ
+    FOR5!(x, 1, {
+        c[x] = lanes[x] ^ lanes[x + 5] ^ lanes[x + 10] ^ lanes[x + 15] ^ lanes[x + 20];
+    });
+
+    FOR5!(x, 1, {
+        FOR5!(y, 5, {
+            lanes[x + y] ^= c[(x + 4) % 5] ^ c[(x + 1) % 5].rotate_left(1);
+        });
+    });
+
+    // ρ and π
+    let mut a = lanes[1];
+    x = 0;
+    REPEAT24!(
+        {
+            c[0] = lanes[PI[x]];
+            lanes[PI[x]] = a.rotate_left(RHO[x]);
+        },
+        {
+            a = c[0];
+            x += 1;
+        }
+    );
+
+    // χ
+    FOR5!(y, 5, {
+        FOR5!(x, 1, {
+            c[x] = lanes[x + y];
+        });
+        FOR5!(x, 1, {
+            lanes[x + y] = c[x] ^ ((!c[(x + 1) % 5]) & c[(x + 2) % 5]);
+        });
+    });
+
+    // ι
+    lanes[0] ^= rc;
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn bytes_of_lanes() {
+        let mut lanes = [0u32; 25];
+        for (i, lane) in lanes.iter_mut().enumerate() {
+            *lane = 0x0302_0100 + 0x0404_0404 * i as u32;
+        }
+        let bytes: [u8; 100] = {
+            let mut bytes = [0u8; 100];
+            for (i, b) in bytes.iter_mut().enumerate() {
+                *b = i as u8;
+            }
+            bytes
+        };
+
+        for offset in 0..100 {
+            for len in 0..=(100 - offset) {
+                let mut output = [0u8; 100];
+                squeeze_bytes(&lanes, offset, &mut output[..len]);
+                assert_eq!(output[..len], bytes[offset..offset + len]);
+
+                let mut state = lanes;
+                absorb_bytes(&mut state, offset, &bytes[offset..offset + len]);
+                let mut output = [0xFFu8; 100];
+                squeeze_bytes(&state, 0, &mut output);
+                assert!(output[..offset].iter().eq(&bytes[..offset]));
+                assert!(output[offset..offset + len].iter().all(|&b| b == 0));
+                assert!(output[offset + len..].iter().eq(&bytes[offset + len..]));
+            }
+        }
+    }
+}
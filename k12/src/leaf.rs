@@ -0,0 +1,181 @@
+//! Leaves of the KangarooTwelve tree, for hashing them on threads of the
+//! caller's choosing.
+
+use crate::{
+    sponge::{KeccakP1600, Sponge},
+    tree, Error,
+};
+use core::fmt;
+#[cfg(feature = "zeroize")]
+use zeroize::ZeroizeOnDrop;
+
+/// Size of the chaining value of a leaf of KangarooTwelve in bytes.
+const CV_SIZE: usize = 256 / 8;
+
+/// Configuration of the leaves of a KangarooTwelve tree, which can be sent
+/// to worker threads to create a [`LeafHasher`] for each chunk there.
+///
+/// The input string `S = M || C || right_encode(|C|)` is cut into chunks of
+/// [`block_size`](Self::block_size) bytes. The first chunk `S_0` is part of
+/// the final node, while the chaining value `CV_i` of every other chunk
+/// `S_i` is computed by a [`LeafHasher`] independently of the others. The
+/// final node is then `TurboSHAKE128` with the domain byte `0x06` of
+///
+/// `S_0 || 0x03 0x00^7 || CV_1 || ... || CV_{n-1} || right_encode(n - 1) || 0xFF 0xFF`
+///
+/// which is left to the caller to assemble, as in this example:
+///
+/// ```
+/// use k12::{LeafHasherFactory, TurboShake128};
+/// use std::thread;
+///
+/// let s: Vec<u8> = (0..30_000).map(|j| (j % 251) as u8).chain([0]).collect();
+/// let factory = LeafHasherFactory::new();
+/// let (first, rest) = s.split_at(factory.block_size());
+///
+/// let workers: Vec<_> = rest
+///     .chunks(factory.block_size())
+///     .map(|chunk| {
+///         let chunk = chunk.to_vec();
+///         thread::spawn(move || {
+///             let mut leaf = factory.leaf_hasher();
+///             leaf.update(&chunk).unwrap();
+///             leaf.finalize()
+///         })
+///     })
+///     .collect();
+///
+/// let mut final_node = TurboShake128::new_with_domain(0x06)?
+///     .chain(first)
+///     .chain([3, 0, 0, 0, 0, 0, 0, 0]);
+/// for worker in workers {
+///     final_node.update(worker.join().unwrap());
+/// }
+/// // `right_encode(3)`
+/// let final_node = final_node.chain([3, 1]).chain([0xFF, 0xFF]);
+///
+/// let mut output = [0u8; 32];
+/// final_node.finalize_xof().read(&mut output);
+/// assert_eq!(output, *k12::hash_fixed::<32>(&s[..30_000], b""));
+/// # Ok::<(), k12::Error>(())
+/// ```
+#[derive(Clone, Copy, Debug)]
+pub struct LeafHasherFactory {
+    /// Size of the chunks `S_i` (B)
+    block_size: usize,
+}
+
+impl LeafHasherFactory {
+    /// Create a factory for the leaves of KangarooTwelve with the default
+    /// block size of 8192 bytes (B).
+    pub fn new() -> Self {
+        Self {
+            block_size: tree::CHUNK_SIZE,
+        }
+    }
+
+    /// Create a factory for leaves of `block_size` bytes, as for
+    /// [`KangarooTwelve::with_block_size`](crate::KangarooTwelve::with_block_size).
+    ///
+    /// Returns [`Error::InvalidBlockSize`] if `block_size` is smaller than the
+    /// rate of 168 bytes.
+    pub fn with_block_size(block_size: usize) -> Result<Self, Error> {
+        let params = tree::K12
+            .with_chunk_size(block_size)
+            .ok_or(Error::InvalidBlockSize)?;
+        Ok(Self {
+            block_size: params.chunk_size(),
+        })
+    }
+
+    /// Create a factory for a block size which has already been checked.
+    #[cfg(feature = "alloc")]
+    pub(crate) fn from_block_size(block_size: usize) -> Self {
+        Self { block_size }
+    }
+
+    /// Size of the chunks of the input string in bytes.
+    pub fn block_size(&self) -> usize {
+        self.block_size
+    }
+
+    /// Create a hasher for one leaf.
+    ///
+    /// The chaining value of a leaf does not depend on its position in the
+    /// tree, so the caller keeps track of the order of the leaves.
+    pub fn leaf_hasher(&self) -> LeafHasher {
+        LeafHasher {
+            sponge: Sponge::new(),
+            block_size: self.block_size(),
+            len: 0,
+        }
+    }
+}
+
+impl Default for LeafHasherFactory {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Hasher computing the chaining value `CV_i` of one chunk `S_i` with
+/// `i > 0`, created by a [`LeafHasherFactory`].
+#[derive(Clone)]
+pub struct LeafHasher {
+    /// Sponge absorbing the chunk
+    sponge: Sponge<KeccakP1600<12>, { 1344 / 8 }, { 256 / 8 }>,
+
+    /// Size of a complete chunk
+    block_size: usize,
+
+    /// Number of bytes of the chunk absorbed so far
+    len: usize,
+}
+
+impl LeafHasher {
+    /// Input bytes of the chunk.
+    ///
+    /// Returns [`Error::CapacityExceeded`] and leaves the state unchanged if
+    /// the chunk would grow beyond the block size.
+    pub fn update(&mut self, bytes: impl AsRef<[u8]>) -> Result<(), Error> {
+        let bytes = bytes.as_ref();
+        let len = self
+            .len
+            .checked_add(bytes.len())
+            .filter(|&len| len <= self.block_size)
+            .ok_or(Error::CapacityExceeded)?;
+        self.sponge.absorb(bytes);
+        self.len = len;
+        Ok(())
+    }
+
+    /// Number of bytes of the chunk absorbed so far.
+    ///
+    /// Every chunk but the last one of the input string must be complete,
+    /// i.e. as long as the block size.
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    /// Whether no bytes of the chunk have been absorbed yet.
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// Chaining value of the chunk, i.e. `TurboSHAKE128(S_i, 0x0B, 32)`.
+    pub fn finalize(self) -> [u8; CV_SIZE] {
+        let mut cv = [0u8; CV_SIZE];
+        self.sponge.finalize(0x0B).read(&mut cv);
+        cv
+    }
+}
+
+impl fmt::Debug for LeafHasher {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("LeafHasher { ... }")
+    }
+}
+
+// The sponge zeroizes its state when dropped
+#[cfg(feature = "zeroize")]
+impl ZeroizeOnDrop for LeafHasher {}
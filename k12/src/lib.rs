@@ -4,6 +4,9 @@
 //! <https://github.com/gvanas/KeccakCodePackage/blob/master/Standalone/kangaroo_twelve-reference/K12.py>
 //!
 //! Some optimisations copied from: <https://github.com/RustCrypto/hashes/tree/master/sha3/src>
+//!
+//! All hashers and output readers are `Send` and `Sync`, so they can be moved
+//! to other threads or shared between them.
 
 // Based off this translation originally by Diggory Hardy:
 // <https://github.com/dhardy/hash-bench/blob/master/src/k12.rs>
@@ -13,234 +16,704 @@
     html_logo_url = "https://raw.githubusercontent.com/RustCrypto/meta/master/logo.svg",
     html_favicon_url = "https://raw.githubusercontent.com/RustCrypto/meta/master/logo.svg"
 )]
-#![forbid(unsafe_code)]
+// `unsafe` code is confined to the x86-64 and NEON backends and the C
+// interface, which allow it. A `forbid` could not be overridden by them, so it
+// only applies when none of them is compiled.
+#![cfg_attr(
+    not(any(
+        target_arch = "x86_64",
+        all(feature = "neon", target_arch = "aarch64", target_feature = "neon"),
+        feature = "ffi"
+    )),
+    forbid(unsafe_code)
+)]
+#![cfg_attr(
+    any(
+        target_arch = "x86_64",
+        all(feature = "neon", target_arch = "aarch64", target_feature = "neon"),
+        feature = "ffi"
+    ),
+    deny(unsafe_code)
+)]
 #![warn(missing_docs, rust_2018_idioms)]
 
-// TODO(tarcieri): eliminate alloc requirement
-#[macro_use]
+#[cfg(feature = "alloc")]
 extern crate alloc;
 
+#[cfg(feature = "std")]
+extern crate std;
+
+#[cfg(feature = "digest")]
+pub use crypto_mac;
+#[cfg(feature = "digest")]
 pub use digest;
+#[cfg(feature = "rand_core")]
+pub use rand_core;
 
 #[macro_use]
+#[cfg_attr(
+    all(feature = "neon", target_arch = "aarch64", target_feature = "neon"),
+    allow(dead_code)
+)]
 mod lanes;
-
-// TODO(tarcieri): eliminate usage of `Vec`
+mod lanes32;
+mod leaf;
+
+#[cfg(feature = "tokio")]
+mod async_io;
+#[cfg(feature = "alloc")]
+mod builder;
+mod const_eval;
+mod domain;
+mod duplex;
+mod encoding;
+mod errors;
+#[cfg(feature = "ffi")]
+pub mod ffi;
+#[cfg(feature = "alloc")]
+mod fixed;
+#[cfg(all(fuzzing, feature = "alloc"))]
+#[doc(hidden)]
+pub mod fuzzing;
+#[cfg(feature = "alloc")]
+mod kdf;
+#[cfg(feature = "alloc")]
+mod kmac;
+#[cfg(feature = "alloc")]
+mod m14;
+#[cfg(feature = "masking")]
+mod masked;
+mod output;
+mod platform;
+mod rng;
+mod rt;
+mod sha3;
+mod shake;
+mod sponge;
+mod sponge800;
+mod stack;
+#[cfg(feature = "serde")]
+mod state;
+mod tree;
+mod turbo_shake;
+mod xoodoo;
+
+pub use crate::{
+    const_eval::{hash_const, keccak_p_1600_const},
+    domain::{Domain, IntoCustomization},
+    duplex::Duplex,
+    errors::Error,
+    leaf::{LeafHasher, LeafHasherFactory},
+    output::{ct_eq_slice, K12Output},
+    rng::K12Rng,
+    rt::KangarooTwelveRt,
+    sha3::{Keccak256, Sha3_256},
+    shake::{Shake128, Shake256, ShakeCore},
+    sponge::{
+        Keccak1600x12, Keccak1600x24, KeccakP1600, KeccakP800, Permutation, Sponge, SpongeReader,
+        Squeezer,
+    },
+    sponge800::Sponge800,
+    stack::StackKangarooTwelve,
+    turbo_shake::{TurboShake128, TurboShake256, TurboShakeCore},
+    xoodoo::{XooSponge, Xoodoo, XoodooState},
+};
+
+#[cfg(feature = "tokio")]
+pub use crate::async_io::AsyncK12Absorber;
+#[cfg(feature = "alloc")]
+pub use crate::builder::KangarooTwelveBuilder;
+#[cfg(feature = "alloc")]
+pub use crate::fixed::{K12_256, K12_512};
+#[cfg(feature = "alloc")]
+pub use crate::kdf::{k12_expand, k12_extract};
+#[cfg(feature = "alloc")]
+pub use crate::kmac::Kmac12;
+#[cfg(feature = "alloc")]
+pub use crate::m14::MarsupilamiFourteen;
+#[cfg(feature = "masking")]
+pub use crate::masked::keccak_p_1600_masked;
+
+#[cfg(feature = "alloc")]
+use crate::{encoding::left_encode_u128, tree::K12Tree};
+#[cfg(feature = "alloc")]
 use alloc::vec::Vec;
-use core::{cmp::min, convert::TryInto, mem};
-use digest::{ExtendableOutputDirty, Reset, Update, XofReader};
+#[cfg(feature = "alloc")]
+use core::{fmt, mem};
+#[cfg(all(feature = "alloc", feature = "digest"))]
+use digest::{ExtendableOutputDirty, Reset, Update};
+#[cfg(feature = "rayon")]
+use rayon::prelude::*;
+#[cfg(all(feature = "alloc", feature = "zeroize"))]
+use zeroize::{Zeroize, ZeroizeOnDrop};
 
 /// The KangarooTwelve extendable-output function (XOF).
-#[derive(Debug, Default)]
+///
+/// Input is hashed as it arrives, so the memory use of the hasher does not
+/// depend on the length of the input.
+///
+/// With the `zeroize` feature the customization string and the intermediate
+/// state are zeroized when the hasher is reset or dropped.
+///
+/// With the `serde` feature the hasher implements `Serialize` and
+/// `Deserialize`, so that a long computation can be checkpointed and resumed.
+/// The limit set with [`set_parallelism`](Self::set_parallelism) is restored
+/// along with the state.
+///
+/// Cloning the hasher snapshots its state, so that a common prefix only needs
+/// to be absorbed once before diverging into independent computations.
+#[cfg(feature = "alloc")]
+#[derive(Clone)]
 pub struct KangarooTwelve {
-    /// Input to be processed
-    // TODO(tarcieri): don't store input in a `Vec`
-    buffer: Vec<u8>,
+    /// Tree hash of the input absorbed so far
+    tree: K12Tree,
 
     /// Customization string to apply
     // TODO(tarcieri): don't store customization in a `Vec`
     customization: Vec<u8>,
 }
 
+#[cfg(feature = "alloc")]
 impl KangarooTwelve {
     /// Create a new [`KangarooTwelve`] instance.
     pub fn new() -> Self {
-        Self::default()
+        Self::new_with_customization([])
     }
 
     /// Create a new [`KangarooTwelve`] instance with the given customization.
     pub fn new_with_customization(customization: impl AsRef<[u8]>) -> Self {
         Self {
-            buffer: Vec::new(),
+            tree: K12Tree::new(tree::K12),
             customization: customization.as_ref().into(),
         }
     }
+
+    /// Create a new [`KangarooTwelve`] instance which cuts its input into
+    /// blocks of `block_size` bytes instead of the default 8192 (B).
+    ///
+    /// Returns [`Error::InvalidBlockSize`] if `block_size` is smaller than the
+    /// rate of 168 bytes.
+    ///
+    /// The result is no longer the KangarooTwelve function of RFC 9861, so
+    /// its outputs are only reproducible with the same block size. Cutting
+    /// the input differently does not affect the security level of the tree
+    /// hashing mode, but the block size is not a domain separator: inputs
+    /// that fit in a single block hash to the same output for any block
+    /// size. Use a customization string to separate applications instead.
+    pub fn with_block_size(block_size: usize) -> Result<Self, Error> {
+        Self::with_block_size_and_customization(block_size, [])
+    }
+
+    /// Create a new [`KangarooTwelve`] instance with the given block size
+    /// and customization.
+    ///
+    /// See [`KangarooTwelve::with_block_size`] for the implications of a
+    /// non-default block size.
+    pub fn with_block_size_and_customization(
+        block_size: usize,
+        customization: impl AsRef<[u8]>,
+    ) -> Result<Self, Error> {
+        let params = tree::K12
+            .with_chunk_size(block_size)
+            .ok_or(Error::InvalidBlockSize)?;
+        Ok(Self {
+            tree: K12Tree::new(params),
+            customization: customization.as_ref().into(),
+        })
+    }
+
+    /// Factory of [`LeafHasher`]s for the leaves of this instance's tree,
+    /// for hashing them on other threads without `rayon`.
+    pub fn leaf_hasher_factory(&self) -> LeafHasherFactory {
+        LeafHasherFactory::from_block_size(self.tree.params().chunk_size())
+    }
+
+    /// Hash at most `parallelism` groups of leaves in parallel with the
+    /// `rayon` feature, where a group is as many leaves as the backend can
+    /// permute at once. Zero is treated as one, which hashes the leaves
+    /// sequentially on the calling thread.
+    ///
+    /// This does not affect the output, and is ignored without `rayon`.
+    pub fn set_parallelism(&mut self, parallelism: usize) {
+        #[cfg(feature = "rayon")]
+        self.tree.set_parallelism(parallelism.max(1));
+        #[cfg(not(feature = "rayon"))]
+        let _ = parallelism;
+    }
+
+    /// Hash at most `parallelism` groups of leaves in parallel with the
+    /// `rayon` feature, returning the updated hasher.
+    ///
+    /// See [`set_parallelism`](Self::set_parallelism).
+    pub fn with_parallelism(mut self, parallelism: usize) -> Self {
+        self.set_parallelism(parallelism);
+        self
+    }
+
+    /// Input data into the hash function.
+    pub fn update(&mut self, bytes: impl AsRef<[u8]>) {
+        self.tree.update(bytes.as_ref());
+    }
+
+    /// Input data into the hash function, returning the updated hasher.
+    pub fn chain(mut self, bytes: impl AsRef<[u8]>) -> Self {
+        self.update(bytes);
+        self
+    }
+
+    /// Input data into the hash function, reporting progress for long inputs.
+    ///
+    /// `progress` is called on the calling thread with the total number of
+    /// bytes input so far each time a chunk of the tree is complete, i.e.
+    /// after every [`block_size`](Self::with_block_size) bytes, and once more
+    /// at the end of the call unless that total has just been reported.
+    /// Nothing is reported for empty `bytes`.
+    pub fn update_with_progress(&mut self, bytes: impl AsRef<[u8]>, mut progress: impl FnMut(u64)) {
+        let bytes = bytes.as_ref();
+        if bytes.is_empty() {
+            return;
+        }
+        let mut reported = None;
+        self.tree.update_with_progress(bytes, |absorbed| {
+            reported = Some(absorbed);
+            progress(absorbed);
+        });
+        let absorbed = self.tree.absorbed();
+        if reported != Some(absorbed) {
+            progress(absorbed);
+        }
+    }
+
+    /// Input each chunk of data yielded by `chunks` in order, which is
+    /// equivalent to calling [`update`](Self::update) for each of them.
+    pub fn update_iter<I>(&mut self, chunks: I)
+    where
+        I: IntoIterator,
+        I::Item: AsRef<[u8]>,
+    {
+        for chunk in chunks {
+            self.update(chunk);
+        }
+    }
+
+    /// Input each chunk of data yielded by `chunks` in order, returning the
+    /// updated hasher.
+    pub fn chain_iter<I>(mut self, chunks: I) -> Self
+    where
+        I: IntoIterator,
+        I::Item: AsRef<[u8]>,
+    {
+        self.update_iter(chunks);
+        self
+    }
+
+    /// Input `data` as `left_encode(8 * |data|) || data`, like
+    /// `encode_string` in SP 800-185.
+    ///
+    /// Unlike plain [`update`](Self::update)s, a sequence of encoded inputs
+    /// can be told apart from any other sequence: inputting `"a"` and `"b"`
+    /// differs from inputting `"ab"`. This only holds if every part of the
+    /// message is input with this method or [`update_field`](Self::update_field).
+    pub fn update_encoded(&mut self, data: impl AsRef<[u8]>) {
+        let data = data.as_ref();
+        // Cannot overflow, unlike the same product in a `usize`
+        let bits = 8 * data.len() as u128;
+        self.tree.update(left_encode_u128(bits).as_bytes());
+        self.tree.update(data);
+    }
+
+    /// Input a field of a structured message as `tag || left_encode(8 *
+    /// |data|) || data`, so that fields with different tags cannot be
+    /// confused even if their contents are equal.
+    ///
+    /// See [`update_encoded`](Self::update_encoded).
+    pub fn update_field(&mut self, tag: u8, data: impl AsRef<[u8]>) {
+        self.tree.update(&[tag]);
+        self.update_encoded(data);
+    }
+
+    /// Input the remaining bytes of `buf` without copying them, advancing
+    /// `buf` to its end.
+    ///
+    /// This is equivalent to calling [`update`](Self::update) for each chunk
+    /// of `buf`, so a chain of [`bytes::Bytes`] can be hashed as it is.
+    #[cfg(feature = "bytes")]
+    pub fn update_buf(&mut self, buf: &mut impl bytes::Buf) {
+        while buf.has_remaining() {
+            let chunk = buf.chunk();
+            let n = chunk.len();
+            self.update(chunk);
+            buf.advance(n);
+        }
+    }
+
+    /// Retrieve the [`Squeezer`] for the output and consume the hasher.
+    pub fn finalize_xof(self) -> Squeezer {
+        self.into_squeezer()
+    }
+
+    /// Compute `output_len` bytes of output with `domain` in place of the
+    /// customization string, and consume the hasher.
+    ///
+    /// Returns [`Error::ZeroOutputLength`] if `output_len` is zero, or
+    /// [`Error::AllocationFailed`] if the output cannot be allocated.
+    pub fn finalize_with_domain(self, domain: Domain, output_len: usize) -> Result<Vec<u8>, Error> {
+        self.finalize_with(domain, output_len)
+    }
+
+    /// Compute `output_len` bytes of output with `customization` in place of
+    /// the customization string of the hasher, and consume the hasher.
+    ///
+    /// `customization` is either a [`Domain`] or the bytes of the string.
+    ///
+    /// Returns [`Error::ZeroOutputLength`] if `output_len` is zero, or
+    /// [`Error::AllocationFailed`] if the output cannot be allocated.
+    pub fn finalize_with(
+        mut self,
+        customization: impl IntoCustomization,
+        output_len: usize,
+    ) -> Result<Vec<u8>, Error> {
+        let mut output = output_vec(output_len)?;
+        self.take_tree()
+            .finalize_with_customization(customization.customization_bytes())
+            .read(&mut output);
+        Ok(output)
+    }
+
+    /// Split the output for `customization`, in place of the customization
+    /// string of the hasher, into `streams` independent [`Squeezer`]s and
+    /// consume the hasher.
+    ///
+    /// This is [`Squeezer::fork`] of the output, so the streams are an
+    /// extension of this crate: none of them is the KangarooTwelve output
+    /// itself, and they can only be reproduced by implementations of the
+    /// same extension.
+    pub fn finalize_multi(
+        mut self,
+        customization: impl IntoCustomization,
+        streams: usize,
+    ) -> Vec<Squeezer> {
+        self.take_tree()
+            .finalize_with_customization(customization.customization_bytes())
+            .fork(streams)
+    }
+
+    /// Compute `output_len` bytes of output and consume the hasher.
+    ///
+    /// Returns [`Error::ZeroOutputLength`] if `output_len` is zero, or
+    /// [`Error::AllocationFailed`] if the output cannot be allocated.
+    pub fn try_finalize(self, output_len: usize) -> Result<Vec<u8>, Error> {
+        let mut output = output_vec(output_len)?;
+        self.finalize_into(&mut output);
+        Ok(output)
+    }
+
+    /// Fill `output` with `output.len()` bytes of output and consume the
+    /// hasher, without allocating.
+    pub fn finalize_into(self, output: &mut [u8]) {
+        if !output.is_empty() {
+            self.into_squeezer().read(output);
+        }
+    }
+
+    /// Replace the contents of `buf` with `output_len` bytes of output and
+    /// consume the hasher.
+    ///
+    /// The existing allocation of `buf` is reused if it is large enough, so
+    /// a single buffer can serve a series of hashes.
+    pub fn finalize_into_vec(self, output_len: usize, buf: &mut Vec<u8>) {
+        buf.clear();
+        buf.resize(output_len, 0);
+        self.finalize_into(buf);
+    }
+
+    /// Compute `N` bytes of output and consume the hasher.
+    pub fn finalize_fixed<const N: usize>(self) -> K12Output<N> {
+        K12Output::squeeze(|output| self.finalize_into(output))
+    }
+
+    /// Discard the input absorbed so far, keeping the customization string.
+    ///
+    /// This does not allocate, so the hasher can be reused for a series of
+    /// messages.
+    pub fn reset(&mut self) {
+        self.tree.reset();
+    }
+
+    /// Take the tree out of the hasher, leaving an empty one in its place,
+    /// without copying its state.
+    fn take_tree(&mut self) -> K12Tree {
+        let empty = K12Tree::new(self.tree.params());
+        mem::replace(&mut self.tree, empty)
+    }
+
+    /// Compute the tree hash of the input absorbed so far and consume the
+    /// hasher.
+    fn into_squeezer(mut self) -> Squeezer {
+        self.take_tree()
+            .finalize_with_customization(&self.customization)
+    }
+
+    /// Compute the tree hash of the input absorbed so far, keeping the
+    /// hasher.
+    #[cfg(feature = "digest")]
+    fn squeezer(&self) -> Squeezer {
+        self.tree
+            .clone()
+            .finalize_with_customization(&self.customization)
+    }
 }
 
+#[cfg(feature = "alloc")]
+impl Default for KangarooTwelve {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(feature = "alloc")]
+impl fmt::Debug for KangarooTwelve {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("KangarooTwelve { ... }")
+    }
+}
+
+#[cfg(all(feature = "alloc", feature = "digest"))]
 impl Update for KangarooTwelve {
-    /// Input data into the hash function
     fn update(&mut self, bytes: impl AsRef<[u8]>) {
-        self.buffer.extend_from_slice(bytes.as_ref());
+        KangarooTwelve::update(self, bytes);
     }
 }
 
+#[cfg(all(feature = "alloc", feature = "digest"))]
 impl ExtendableOutputDirty for KangarooTwelve {
-    type Reader = Reader;
+    type Reader = Squeezer;
 
     fn finalize_xof_dirty(&mut self) -> Self::Reader {
-        let mut buffer = vec![];
-        let mut customization = vec![];
-
-        mem::swap(&mut self.buffer, &mut buffer);
-        mem::swap(&mut self.customization, &mut customization);
-
-        Reader {
-            buffer,
-            customization,
-            finished: false,
-        }
+        self.squeezer()
     }
 }
 
+#[cfg(all(feature = "alloc", feature = "digest"))]
 impl Reset for KangarooTwelve {
     fn reset(&mut self) {
-        self.buffer.clear();
+        KangarooTwelve::reset(self);
     }
 }
 
-/// Extensible output reader.
+/// Absorbs the written bytes, so that input can be hashed straight from a
+/// reader with [`std::io::copy`].
 ///
-/// NOTE: this presently only supports one invocation and will *panic* if
-/// [`XofReader::read`] is invoked on it multiple times.
-#[derive(Debug, Default)]
-pub struct Reader {
-    /// Input to be processed
-    // TODO(tarcieri): don't store input in a `Vec`
-    buffer: Vec<u8>,
-
-    /// Customization string to apply
-    // TODO(tarcieri): don't store customization in a `Vec`
-    customization: Vec<u8>,
+/// Writing never fails.
+#[cfg(feature = "std")]
+impl std::io::Write for KangarooTwelve {
+    fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+        self.update(buf);
+        Ok(buf.len())
+    }
 
-    /// Has the XOF output already been consumed?
-    // TODO(tarcieri): allow `XofReader::result` to be called multiple times
-    finished: bool,
+    fn flush(&mut self) -> std::io::Result<()> {
+        Ok(())
+    }
 }
 
-// TODO(tarcieri): factor more of this logic into the `KangarooTwelve` type
-impl XofReader for Reader {
-    /// Get the resulting output of the function.
-    ///
-    /// Panics if called multiple times on the same instance (TODO: don't panic!)
-    fn read(&mut self, output: &mut [u8]) {
-        assert!(
-            !self.finished,
-            "not yet implemented: multiple XofReader::read invocations unsupported"
-        );
+#[cfg(all(feature = "alloc", feature = "zeroize"))]
+impl Drop for KangarooTwelve {
+    fn drop(&mut self) {
+        // The sponges of the tree zeroize themselves
+        self.customization.zeroize();
+    }
+}
 
-        let b = 8192;
-        let c = 256;
+#[cfg(all(feature = "alloc", feature = "zeroize"))]
+impl ZeroizeOnDrop for KangarooTwelve {}
 
-        let mut slice = Vec::new(); // S
-        slice.extend_from_slice(&self.buffer);
-        slice.extend_from_slice(&self.customization);
-        slice.extend_from_slice(&right_encode(self.customization.len())[..]);
+/// Zeroed buffer for `output_len` bytes of output, which must not be zero.
+#[cfg(feature = "alloc")]
+pub(crate) fn output_vec(output_len: usize) -> Result<Vec<u8>, Error> {
+    if output_len == 0 {
+        return Err(Error::ZeroOutputLength);
+    }
+    let mut output = Vec::new();
+    output
+        .try_reserve_exact(output_len)
+        .map_err(|_| Error::AllocationFailed)?;
+    output.resize(output_len, 0);
+    Ok(output)
+}
 
-        // === Cut the input string into chunks of b bytes ===
-        let n = (slice.len() + b - 1) / b;
-        let mut slices = Vec::with_capacity(n); // Si
-        for i in 0..n {
-            let ub = min((i + 1) * b, slice.len());
-            slices.push(&slice[i * b..ub]);
-        }
+/// Compute `output_len` bytes of KangarooTwelve output for `data` with the
+/// given `customization` string.
+///
+/// Requesting no output is almost certainly a mistake, so this returns
+/// [`Error::ZeroOutputLength`] if `output_len` is zero. Returns
+/// [`Error::AllocationFailed`] if the output cannot be allocated.
+#[cfg(feature = "alloc")]
+pub fn hash(data: &[u8], customization: &[u8], output_len: usize) -> Result<Vec<u8>, Error> {
+    let mut output = output_vec(output_len)?;
+    hash_into(data, customization, &mut output);
+    Ok(output)
+}
 
-        // TODO(tarcieri): get rid of intermediate output buffer
-        let tmp_buffer = if n == 1 {
-            // === Process the tree with only a final node ===
-            f(slices[0], 0x07, output.len())
-        } else {
-            // === Process the tree with kangaroo hopping ===
-            // TODO: in parallel
-            let mut intermediate = Vec::with_capacity(n - 1); // CVi
-            for i in 0..n - 1 {
-                intermediate.push(f(slices[i + 1], 0x0B, c / 8));
-            }
-
-            let mut node_star = Vec::new();
-            node_star.extend_from_slice(slices[0]);
-            node_star.extend_from_slice(&[3, 0, 0, 0, 0, 0, 0, 0]);
-
-            #[allow(clippy::needless_range_loop)]
-            for i in 0..n - 1 {
-                node_star.extend_from_slice(&intermediate[i][..]);
-            }
-
-            node_star.extend_from_slice(&right_encode(n - 1));
-            node_star.extend_from_slice(b"\xFF\xFF");
-
-            f(&node_star[..], 0x06, output.len())
+/// Compute `output_len` bytes of KangarooTwelve output for the contents of
+/// the file at `path` with the given `customization` string.
+///
+/// The file is read in chunks of 64 KiB, so it is never loaded into memory
+/// as a whole. The errors of [`hash`] are returned as
+/// [`std::io::ErrorKind::InvalidInput`] before the file is opened.
+#[cfg(feature = "std")]
+pub fn hash_file(
+    path: impl AsRef<std::path::Path>,
+    customization: &[u8],
+    output_len: usize,
+) -> std::io::Result<Vec<u8>> {
+    use std::io::BufRead;
+
+    let mut output = output_vec(output_len)
+        .map_err(|err| std::io::Error::new(std::io::ErrorKind::InvalidInput, err))?;
+    let file = std::fs::File::open(path)?;
+    let mut reader = std::io::BufReader::with_capacity(64 * 1024, file);
+    let mut hasher = KangarooTwelve::new_with_customization(customization);
+    loop {
+        let chunk = match reader.fill_buf() {
+            Ok(chunk) => chunk,
+            Err(err) if err.kind() == std::io::ErrorKind::Interrupted => continue,
+            Err(err) => return Err(err),
         };
+        if chunk.is_empty() {
+            break;
+        }
+        let n = chunk.len();
+        hasher.update(chunk);
+        reader.consume(n);
+    }
+    hasher.finalize_into(&mut output);
+    Ok(output)
+}
 
-        output.copy_from_slice(&tmp_buffer);
-        self.finished = true;
+/// Compute `output_len` bytes of KangarooTwelve output for each of the
+/// independent `messages` with the given `customization` string.
+///
+/// The outputs are in the same order as the messages. With the `rayon`
+/// feature the messages are hashed in parallel. Returns
+/// [`Error::ZeroOutputLength`] if `output_len` is zero, or
+/// [`Error::AllocationFailed`] if an output cannot be allocated.
+#[cfg(feature = "alloc")]
+pub fn hash_batch(
+    messages: &[&[u8]],
+    customization: &[u8],
+    output_len: usize,
+) -> Result<Vec<Vec<u8>>, Error> {
+    if output_len == 0 {
+        return Err(Error::ZeroOutputLength);
     }
+
+    #[cfg(feature = "rayon")]
+    let messages = messages.par_iter();
+    #[cfg(not(feature = "rayon"))]
+    let messages = messages.iter();
+
+    messages
+        .map(|message| hash(message, customization, output_len))
+        .collect()
 }
 
-fn f(input: &[u8], suffix: u8, mut output_len: usize) -> Vec<u8> {
-    let mut state = [0u8; 200];
-    let max_block_size = 1344 / 8; // r, also known as rate in bytes
+/// Fill `output` with KangarooTwelve output for `data` with the given
+/// `customization` string, without allocating.
+pub fn hash_into(data: &[u8], customization: &[u8], output: &mut [u8]) {
+    tree::finalize(tree::K12, data, customization).read(output);
+}
 
-    // === Absorb all the input blocks ===
-    // We unroll first loop, which allows simple copy
-    let mut block_size = min(input.len(), max_block_size);
-    state[0..block_size].copy_from_slice(&input[0..block_size]);
+/// Compute `N` bytes of KangarooTwelve output for `data` with the given
+/// `customization` string, without allocating.
+pub fn hash_fixed<const N: usize>(data: &[u8], customization: &[u8]) -> K12Output<N> {
+    K12Output::squeeze(|output| hash_into(data, customization, output))
+}
 
-    let mut offset = block_size;
-    while offset < input.len() {
-        keccak(&mut state);
-        block_size = min(input.len() - offset, max_block_size);
-        for i in 0..block_size {
-            // TODO: is this sufficiently optimisable or better to convert to u64 first?
-            state[i] ^= input[i + offset];
-        }
-        offset += block_size;
-    }
-    if block_size == max_block_size {
-        // TODO: condition is nearly always false; tests pass without this.
-        // Why is it here?
-        keccak(&mut state);
-        block_size = 0;
-    }
-
-    // === Do the padding and switch to the squeezing phase ===
-    state[block_size] ^= suffix;
-    if ((suffix & 0x80) != 0) && (block_size == (max_block_size - 1)) {
-        // TODO: condition is almost always false — in fact tests pass without
-        // this block! So why is it here?
-        keccak(&mut state);
-    }
-    state[max_block_size - 1] ^= 0x80;
-    keccak(&mut state);
-
-    // === Squeeze out all the output blocks ===
-    let mut output = Vec::with_capacity(output_len);
-    while output_len > 0 {
-        block_size = min(output_len, max_block_size);
-        output.extend_from_slice(&state[0..block_size]);
-        output_len -= block_size;
-        if output_len > 0 {
-            keccak(&mut state);
-        }
-    }
-    output
+/// Apply the Keccak-p[1600, 12] permutation to `state`, the permutation
+/// underlying KangarooTwelve and TurboSHAKE.
+///
+/// Keccak-p[1600, 12] consists of the last 12 of the 24 rounds of
+/// Keccak-f[1600], as specified in FIPS 202, Section 3.3:
+///
+/// <https://nvlpubs.nist.gov/nistpubs/FIPS/NIST.FIPS.202.pdf>
+///
+/// The bytes of `state` are the 25 lanes of 64 bits in little-endian order,
+/// lane `x + 5 * y` starting at byte `8 * (x + 5 * y)`, which is the byte
+/// order of the Keccak team's reference implementations. The fastest
+/// backend for the target is used, as for hashing.
+#[inline]
+pub fn keccak_p_1600_12(state: &mut [u8; 200]) {
+    keccak_p_1600(state, 12);
 }
 
-fn keccak(state: &mut [u8; 200]) {
+/// Apply the Keccak-p[1600, `rounds`] permutation to `state`, i.e. the last
+/// `rounds` of the 24 rounds of Keccak-f[1600].
+///
+/// With 24 rounds this is the Keccak-f[1600] permutation of SHA-3, with 14
+/// rounds the permutation of MarsupilamiFourteen, and with 12 rounds
+/// [`keccak_p_1600_12`]. The bytes of `state` are in the same order as for
+/// [`keccak_p_1600_12`], and the fastest backend for the target is used.
+///
+/// # Panics
+///
+/// If `rounds` is greater than 24.
+pub fn keccak_p_1600(state: &mut [u8; 200], rounds: usize) {
+    assert!(rounds <= 24, "Keccak-f[1600] has only 24 rounds");
     let mut lanes = [0u64; 25];
-    let mut y;
-    for x in 0..5 {
-        FOR5!(y, 5, {
-            let pos = 8 * (x + y);
-            lanes[x + y] = u64::from_le_bytes(state[pos..(pos + 8)].try_into().unwrap());
-        });
-    }
-    lanes::keccak(&mut lanes);
-    for x in 0..5 {
-        FOR5!(y, 5, {
-            let i = 8 * (x + y);
-            state[i..i + 8].copy_from_slice(&lanes[x + y].to_le_bytes());
-        });
-    }
+    lanes::absorb_bytes(&mut lanes, 0, state);
+    platform::keccak_p(&mut lanes, rounds);
+    lanes::squeeze_bytes(&lanes, 0, state);
+    #[cfg(feature = "zeroize")]
+    zeroize::Zeroize::zeroize(&mut lanes);
 }
 
-fn right_encode(mut x: usize) -> Vec<u8> {
-    let mut slice = Vec::new();
-    while x > 0 {
-        slice.push((x % 256) as u8);
-        x /= 256;
+/// Apply the Keccak-p[1600, `rounds`] permutation to `state` as
+/// [`keccak_p_1600`] does, calling `callback` with the state after each
+/// round.
+///
+/// The callback receives the index `i_r` of the round in FIPS 202, which
+/// runs from `24 - rounds` to 23, so that the states can be compared with
+/// the intermediate values of other implementations. The portable backend is
+/// always used. This is only available with the `debug_permutation` feature.
+///
+/// # Panics
+///
+/// If `rounds` is greater than 24.
+#[cfg(feature = "debug_permutation")]
+pub fn keccak_p_debug(
+    state: &mut [u8; 200],
+    rounds: usize,
+    callback: &mut impl FnMut(usize, &[u8; 200]),
+) {
+    assert!(rounds <= 24, "Keccak-f[1600] has only 24 rounds");
+    let mut lanes = [0u64; 25];
+    lanes::absorb_bytes(&mut lanes, 0, state);
+    for (i, &rc) in lanes::RC.iter().enumerate().skip(24 - rounds) {
+        lanes::round(&mut lanes, rc);
+        lanes::squeeze_bytes(&lanes, 0, state);
+        callback(i, state);
     }
-    slice.reverse();
-    let len = slice.len();
-    slice.push(len as u8);
-    slice
+    #[cfg(feature = "zeroize")]
+    zeroize::Zeroize::zeroize(&mut lanes);
+}
+
+/// Apply the Keccak-p[800, 12] permutation to `state`, for 32-bit targets
+/// and [`Sponge800`].
+///
+/// Keccak-p[800, 12] consists of the last 12 of the 22 rounds of
+/// Keccak-f[800], with lanes of 32 bits. The bytes of `state` are the 25
+/// lanes in little-endian order, as for [`keccak_p_1600_12`].
+#[inline]
+pub fn keccak_p800_12(state: &mut [u8; 100]) {
+    let mut lanes = [0u32; 25];
+    lanes32::absorb_bytes(&mut lanes, 0, state);
+    lanes32::keccak_p(&mut lanes, 12);
+    lanes32::squeeze_bytes(&lanes, 0, state);
+    #[cfg(feature = "zeroize")]
+    zeroize::Zeroize::zeroize(&mut lanes);
 }
+
+/// Extensible output reader.
+#[deprecated(since = "0.1.1", note = "use `Squeezer` instead")]
+pub type Reader = Squeezer;
@@ -0,0 +1,113 @@
+//! MarsupilamiFourteen: the 14-round sibling of KangarooTwelve.
+
+use crate::{
+    tree::{self, M14Tree},
+    Squeezer,
+};
+use alloc::vec::Vec;
+use core::{fmt, mem};
+#[cfg(feature = "digest")]
+use digest::{ExtendableOutputDirty, Reset, Update};
+#[cfg(feature = "zeroize")]
+use zeroize::{Zeroize, ZeroizeOnDrop};
+
+/// The MarsupilamiFourteen extendable-output function (XOF).
+///
+/// Uses the same tree hashing mode as [`KangarooTwelve`][crate::KangarooTwelve],
+/// with a capacity of 512 bits and Keccak-p[1600, 14] for a higher security
+/// margin.
+#[derive(Clone)]
+pub struct MarsupilamiFourteen {
+    /// Tree hash of the input absorbed so far
+    tree: M14Tree,
+
+    /// Customization string to apply
+    customization: Vec<u8>,
+}
+
+impl MarsupilamiFourteen {
+    /// Create a new [`MarsupilamiFourteen`] instance.
+    pub fn new() -> Self {
+        Self::new_with_customization([])
+    }
+
+    /// Create a new [`MarsupilamiFourteen`] instance with the given customization.
+    pub fn new_with_customization(customization: impl AsRef<[u8]>) -> Self {
+        Self {
+            tree: M14Tree::new(tree::M14),
+            customization: customization.as_ref().into(),
+        }
+    }
+
+    /// Input data into the hash function.
+    pub fn update(&mut self, bytes: impl AsRef<[u8]>) {
+        self.tree.update(bytes.as_ref());
+    }
+
+    /// Input data into the hash function, returning the updated hasher.
+    pub fn chain(mut self, bytes: impl AsRef<[u8]>) -> Self {
+        self.update(bytes);
+        self
+    }
+
+    /// Retrieve the [`Squeezer`] for the output and consume the hasher.
+    pub fn finalize_xof(mut self) -> Squeezer {
+        let empty = M14Tree::new(self.tree.params());
+        mem::replace(&mut self.tree, empty).finalize_with_customization(&self.customization)
+    }
+
+    /// Compute the tree hash of the input absorbed so far, keeping the
+    /// hasher.
+    #[cfg(feature = "digest")]
+    fn squeezer(&self) -> Squeezer {
+        self.tree
+            .clone()
+            .finalize_with_customization(&self.customization)
+    }
+}
+
+impl Default for MarsupilamiFourteen {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl fmt::Debug for MarsupilamiFourteen {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("MarsupilamiFourteen { ... }")
+    }
+}
+
+#[cfg(feature = "digest")]
+impl Update for MarsupilamiFourteen {
+    fn update(&mut self, bytes: impl AsRef<[u8]>) {
+        MarsupilamiFourteen::update(self, bytes);
+    }
+}
+
+#[cfg(feature = "digest")]
+impl ExtendableOutputDirty for MarsupilamiFourteen {
+    type Reader = Squeezer;
+
+    fn finalize_xof_dirty(&mut self) -> Self::Reader {
+        self.squeezer()
+    }
+}
+
+#[cfg(feature = "digest")]
+impl Reset for MarsupilamiFourteen {
+    fn reset(&mut self) {
+        self.tree.reset();
+    }
+}
+
+#[cfg(feature = "zeroize")]
+impl Drop for MarsupilamiFourteen {
+    fn drop(&mut self) {
+        // The sponges of the tree zeroize themselves
+        self.customization.zeroize();
+    }
+}
+
+#[cfg(feature = "zeroize")]
+impl ZeroizeOnDrop for MarsupilamiFourteen {}
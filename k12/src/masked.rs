@@ -0,0 +1,87 @@
+//! Keccak-p[1600] on a state split into two Boolean shares.
+
+use crate::lanes::{PI, RC, RHO};
+use rand_core::RngCore;
+
+/// Apply the Keccak-p[1600, `rounds`] permutation to a state given as two
+/// Boolean shares, so that the state itself is never computed.
+///
+/// The state is `share[i] ^ mask[i]` for every lane `i`, in the lane order of
+/// [`keccak_p_1600_12`](crate::keccak_p_1600_12). Afterwards the two shares
+/// hold the permuted state in the same way. The linear steps θ, ρ and π are
+/// applied to both shares, ι to `share` alone, and the AND gates of χ are
+/// computed as in Ishai, Sahai and Wagner (2003) with 25 fresh random lanes
+/// from `rng` per round, which protects against first-order power analysis.
+///
+/// This is only available with the `masking` feature. The compiler does not
+/// promise to keep the shares apart, so the protection of the generated code
+/// should be checked on the target device.
+///
+/// # Panics
+///
+/// If `rounds` is greater than 24.
+pub fn keccak_p_1600_masked(
+    share: &mut [u64; 25],
+    mask: &mut [u64; 25],
+    rounds: usize,
+    rng: &mut impl RngCore,
+) {
+    assert!(rounds <= 24, "Keccak-f[1600] has only 24 rounds");
+    for &rc in &RC[RC.len() - rounds..] {
+        theta(share);
+        theta(mask);
+        rho_pi(share);
+        rho_pi(mask);
+        chi(share, mask, rng);
+        share[0] ^= rc;
+    }
+}
+
+/// θ on one share, which is linear.
+fn theta(a: &mut [u64; 25]) {
+    let mut c = [0u64; 5];
+    for x in 0..5 {
+        c[x] = a[x] ^ a[x + 5] ^ a[x + 10] ^ a[x + 15] ^ a[x + 20];
+    }
+    for x in 0..5 {
+        let d = c[(x + 4) % 5] ^ c[(x + 1) % 5].rotate_left(1);
+        for y in (0..25).step_by(5) {
+            a[x + y] ^= d;
+        }
+    }
+}
+
+/// ρ and π on one share, which only move bits.
+fn rho_pi(a: &mut [u64; 25]) {
+    let mut last = a[1];
+    for (&pi, &rho) in PI.iter().zip(&RHO) {
+        let next = a[pi];
+        a[pi] = last.rotate_left(rho);
+        last = next;
+    }
+}
+
+/// χ on both shares, `a[x] ^= !a[x + 1] & a[x + 2]` for each row.
+///
+/// The complement of a shared value is the complement of one of its shares.
+/// The product of `(p0, p1)` and `(q0, q1)` is shared as
+///
+/// `(p0 & q0 ^ r, p1 & q1 ^ (r ^ p0 & q1 ^ p1 & q0))`
+///
+/// with a fresh random `r`, where the order of the XORs keeps every
+/// intermediate value independent of the unshared inputs.
+fn chi(share: &mut [u64; 25], mask: &mut [u64; 25], rng: &mut impl RngCore) {
+    for y in (0..25).step_by(5) {
+        let mut s = [0u64; 5];
+        let mut m = [0u64; 5];
+        s.copy_from_slice(&share[y..y + 5]);
+        m.copy_from_slice(&mask[y..y + 5]);
+        for x in 0..5 {
+            let (p0, p1) = (!s[(x + 1) % 5], m[(x + 1) % 5]);
+            let (q0, q1) = (s[(x + 2) % 5], m[(x + 2) % 5]);
+            let r = rng.next_u64();
+            share[x + y] = s[x] ^ ((p0 & q0) ^ r);
+            mask[x + y] = m[x] ^ ((p1 & q1) ^ ((r ^ (p0 & q1)) ^ (p1 & q0)));
+        }
+    }
+}
@@ -0,0 +1,164 @@
+//! Fixed-size output.
+
+use crate::Error;
+use core::{array::TryFromSliceError, convert::TryFrom, fmt, ops::Deref, str::FromStr};
+use subtle::{Choice, ConstantTimeEq};
+
+/// `N` bytes of output, stored inline.
+///
+/// Dereferences to `[u8]` and formats as hexadecimal with `{:x}` and `{:X}`,
+/// and as lowercase hexadecimal with `{}`.
+///
+/// `==` may return as soon as a byte differs. Compare secret outputs, such
+/// as tags or derived keys, with [`ct_eq`](Self::ct_eq) instead.
+#[derive(Clone, PartialEq, Eq)]
+pub struct K12Output<const N: usize>([u8; N]);
+
+impl<const N: usize> K12Output<N> {
+    /// Fill the output from `squeeze`.
+    pub(crate) fn squeeze(squeeze: impl FnOnce(&mut [u8])) -> Self {
+        let mut output = [0u8; N];
+        squeeze(&mut output);
+        Self(output)
+    }
+
+    /// Parse an output from exactly `2 * N` hexadecimal digits.
+    ///
+    /// Digits may be lowercase or uppercase and may be separated by
+    /// whitespace and colons. Returns [`Error::InvalidHexCharacter`] for any
+    /// other character, and [`Error::InvalidOutputLength`] if the string does
+    /// not encode exactly `N` bytes.
+    ///
+    /// ```
+    /// use k12::K12Output;
+    ///
+    /// let output = K12Output::<4>::from_hex("1a:c2:d4:50")?;
+    /// assert_eq!(output, K12Output::from([0x1a, 0xc2, 0xd4, 0x50]));
+    /// # Ok::<(), k12::Error>(())
+    /// ```
+    pub fn from_hex(s: &str) -> Result<Self, Error> {
+        let mut output = [0u8; N];
+        let mut digits = 0;
+        for c in s.chars() {
+            if c.is_whitespace() || c == ':' {
+                continue;
+            }
+            let digit = c.to_digit(16).ok_or(Error::InvalidHexCharacter)? as u8;
+            let byte = output
+                .get_mut(digits / 2)
+                .ok_or(Error::InvalidOutputLength)?;
+            *byte = (*byte << 4) | digit;
+            digits += 1;
+        }
+        if digits != 2 * N {
+            return Err(Error::InvalidOutputLength);
+        }
+        Ok(Self(output))
+    }
+
+    /// Check in constant time whether the output is equal to `other`.
+    pub fn ct_eq(&self, other: &Self) -> bool {
+        ConstantTimeEq::ct_eq(self, other).into()
+    }
+
+    /// Output bytes.
+    pub fn into_bytes(self) -> [u8; N] {
+        self.0
+    }
+}
+
+impl<const N: usize> ConstantTimeEq for K12Output<N> {
+    fn ct_eq(&self, other: &Self) -> Choice {
+        self.0.ct_eq(&other.0)
+    }
+}
+
+impl<const N: usize> Deref for K12Output<N> {
+    type Target = [u8];
+
+    fn deref(&self) -> &[u8] {
+        &self.0
+    }
+}
+
+impl<const N: usize> AsRef<[u8]> for K12Output<N> {
+    fn as_ref(&self) -> &[u8] {
+        &self.0
+    }
+}
+
+impl<const N: usize> From<[u8; N]> for K12Output<N> {
+    fn from(bytes: [u8; N]) -> Self {
+        Self(bytes)
+    }
+}
+
+impl<const N: usize> From<K12Output<N>> for [u8; N] {
+    fn from(output: K12Output<N>) -> Self {
+        output.0
+    }
+}
+
+/// Fails unless the slice is exactly `N` bytes long.
+impl<const N: usize> TryFrom<&[u8]> for K12Output<N> {
+    type Error = TryFromSliceError;
+
+    fn try_from(bytes: &[u8]) -> Result<Self, Self::Error> {
+        <[u8; N]>::try_from(bytes).map(Self)
+    }
+}
+
+/// Parses hexadecimal strings as [`K12Output::from_hex`] does.
+impl<const N: usize> FromStr for K12Output<N> {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Self::from_hex(s)
+    }
+}
+
+impl<const N: usize> fmt::LowerHex for K12Output<N> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for byte in &self.0 {
+            write!(f, "{:02x}", byte)?;
+        }
+        Ok(())
+    }
+}
+
+impl<const N: usize> fmt::UpperHex for K12Output<N> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for byte in &self.0 {
+            write!(f, "{:02X}", byte)?;
+        }
+        Ok(())
+    }
+}
+
+impl<const N: usize> fmt::Display for K12Output<N> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::LowerHex::fmt(self, f)
+    }
+}
+
+impl<const N: usize> fmt::Debug for K12Output<N> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "K12Output({:x})", self)
+    }
+}
+
+/// Check whether `a` and `b` are equal, in time depending only on their
+/// lengths.
+///
+/// Slices of different lengths are never equal; the lengths are not
+/// considered secret.
+///
+/// ```
+/// let tag = k12::hash_fixed::<32>(b"message", b"");
+/// assert!(k12::ct_eq_slice(&tag, &k12::hash_fixed::<32>(b"message", b"")));
+/// assert!(!k12::ct_eq_slice(&tag, &k12::hash_fixed::<32>(b"massage", b"")));
+/// assert!(!k12::ct_eq_slice(&tag, &tag[..16]));
+/// ```
+pub fn ct_eq_slice(a: &[u8], b: &[u8]) -> bool {
+    a.ct_eq(b).into()
+}
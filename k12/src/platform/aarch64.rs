@@ -0,0 +1,149 @@
+//! NEON backend for the Keccak-p[1600] permutation on `aarch64`.
+//!
+//! Each row of the state is kept as two vectors of the lanes `x = 0..4` and a
+//! scalar for the lane `x = 4`.
+
+#![allow(unsafe_code)]
+
+use crate::lanes::RC;
+use core::arch::aarch64::*;
+
+/// Rotation offsets of the ρ step, indexed by lane `x + 5 * y`.
+const RHO: [i64; 25] = [
+    0, 1, 62, 28, 27, 36, 44, 6, 55, 20, 3, 10, 43, 25, 39, 41, 45, 15, 21, 8, 18, 2, 61, 56, 14,
+];
+
+/// Rotate each lane of `v` left by the corresponding lane of `n`.
+#[inline]
+unsafe fn rotl(v: uint64x2_t, n: int64x2_t) -> uint64x2_t {
+    // Negative shift amounts shift to the right
+    let m = vsubq_s64(n, vdupq_n_s64(64));
+    vorrq_u64(vshlq_u64(v, n), vshlq_u64(v, m))
+}
+
+/// Rotate each lane of `v` left by one bit.
+#[inline]
+unsafe fn rotl1(v: uint64x2_t) -> uint64x2_t {
+    vorrq_u64(vshlq_n_u64(v, 1), vshrq_n_u64(v, 63))
+}
+
+/// Concatenate lane `i` of `a` and lane `j` of `b`.
+macro_rules! pair {
+    ($a:expr, 0, $b:expr, 0) => {
+        vtrn1q_u64($a, $b)
+    };
+    ($a:expr, 1, $b:expr, 1) => {
+        vtrn2q_u64($a, $b)
+    };
+    ($a:expr, 1, $b:expr, 0) => {
+        vextq_u64($a, $b, 1)
+    };
+    ($a:expr, 0, $b:expr, 1) => {
+        vcombine_u64(vget_low_u64($a), vget_high_u64($b))
+    };
+}
+
+/// Apply Keccak-p[1600, `rounds`] to a state.
+pub(crate) fn keccak_neon(lanes: &mut [u64; 25], rounds: usize) {
+    debug_assert!(rounds <= RC.len());
+
+    // SAFETY: this module is only compiled with the `neon` target feature
+    unsafe {
+        let mut lo = [vdupq_n_u64(0); 5];
+        let mut hi = [vdupq_n_u64(0); 5];
+        let mut s = [0u64; 5];
+        let mut rho_lo = [vdupq_n_s64(0); 5];
+        let mut rho_hi = [vdupq_n_s64(0); 5];
+        for y in 0..5 {
+            lo[y] = vld1q_u64(lanes[5 * y..].as_ptr());
+            hi[y] = vld1q_u64(lanes[5 * y + 2..].as_ptr());
+            s[y] = lanes[5 * y + 4];
+            rho_lo[y] = vld1q_s64(RHO[5 * y..].as_ptr());
+            rho_hi[y] = vld1q_s64(RHO[5 * y + 2..].as_ptr());
+        }
+
+        for &rc in &RC[RC.len() - rounds..] {
+            // θ
+            let mut c_lo = lo[0];
+            let mut c_hi = hi[0];
+            for y in 1..5 {
+                c_lo = veorq_u64(c_lo, lo[y]);
+                c_hi = veorq_u64(c_hi, hi[y]);
+            }
+            let c4 = vdupq_n_u64(s[0] ^ s[1] ^ s[2] ^ s[3] ^ s[4]);
+            let c12 = pair!(c_lo, 1, c_hi, 0);
+            let d_lo = veorq_u64(pair!(c4, 1, c_lo, 0), rotl1(c12));
+            let d_hi = veorq_u64(c12, rotl1(pair!(c_hi, 1, c4, 0)));
+            let d4 = vgetq_lane_u64(c_hi, 1) ^ vgetq_lane_u64(c_lo, 0).rotate_left(1);
+
+            // θ and ρ
+            for y in 0..5 {
+                lo[y] = rotl(veorq_u64(lo[y], d_lo), rho_lo[y]);
+                hi[y] = rotl(veorq_u64(hi[y], d_hi), rho_hi[y]);
+                s[y] = (s[y] ^ d4).rotate_left(RHO[5 * y + 4] as u32);
+            }
+
+            // π: row `Y` of the result takes column `(3 * Y + X) % 5` of row `X`
+            let s0 = vdupq_n_u64(s[0]);
+            let s1 = vdupq_n_u64(s[1]);
+            let s2 = vdupq_n_u64(s[2]);
+            let s3 = vdupq_n_u64(s[3]);
+            let b_lo = [
+                pair!(lo[0], 0, lo[1], 1),
+                pair!(hi[0], 1, s1, 0),
+                pair!(lo[0], 1, hi[1], 0),
+                pair!(s0, 1, lo[1], 0),
+                pair!(hi[0], 0, hi[1], 1),
+            ];
+            let b_hi = [
+                pair!(hi[2], 0, hi[3], 1),
+                pair!(lo[2], 0, lo[3], 1),
+                pair!(hi[2], 1, s3, 0),
+                pair!(lo[2], 1, hi[3], 0),
+                pair!(s2, 1, lo[3], 0),
+            ];
+            let t = [
+                s[4],
+                vgetq_lane_u64(hi[4], 0),
+                vgetq_lane_u64(lo[4], 0),
+                vgetq_lane_u64(hi[4], 1),
+                vgetq_lane_u64(lo[4], 1),
+            ];
+
+            // χ
+            for y in 0..5 {
+                let tv = vdupq_n_u64(t[y]);
+                let next_lo = pair!(b_lo[y], 1, b_hi[y], 0);
+                let next_hi = pair!(b_hi[y], 1, tv, 0);
+                let next2_hi = pair!(tv, 1, b_lo[y], 0);
+                lo[y] = veorq_u64(b_lo[y], vbicq_u64(b_hi[y], next_lo));
+                hi[y] = veorq_u64(b_hi[y], vbicq_u64(next2_hi, next_hi));
+                let b0 = vgetq_lane_u64(b_lo[y], 0);
+                let b1 = vgetq_lane_u64(b_lo[y], 1);
+                s[y] = t[y] ^ (!b0 & b1);
+            }
+
+            // ι
+            lo[0] = veorq_u64(lo[0], vcombine_u64(vcreate_u64(rc), vcreate_u64(0)));
+        }
+
+        for y in 0..5 {
+            vst1q_u64(lanes[5 * y..].as_mut_ptr(), lo[y]);
+            vst1q_u64(lanes[5 * y + 2..].as_mut_ptr(), hi[y]);
+            lanes[5 * y + 4] = s[y];
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::platform::assert_matches_soft;
+
+    #[test]
+    fn neon_matches_soft() {
+        assert_matches_soft(|states: &mut [[u64; 25]; 1], rounds| {
+            keccak_neon(&mut states[0], rounds)
+        });
+    }
+}
@@ -0,0 +1,191 @@
+//! Backend for the Keccak-p[1600] permutation on 32-bit ARM, tuned for the
+//! Cortex-M4.
+//!
+//! Each 64-bit lane is kept as two 32-bit words holding its even and odd
+//! bits (bit interleaving). A 64-bit rotation then becomes two independent
+//! 32-bit rotations, which the compiler folds into the barrel shifter of the
+//! next `EOR` or `BIC`, instead of the shift-and-or sequences across both
+//! halves of a lane that the portable backend compiles to. The lanes are
+//! interleaved once before the rounds and restored once after them.
+//!
+//! The even and odd halves never depend on each other within a step, so the
+//! compiler can alternate their instructions and avoid back-to-back
+//! dependencies in the rotation chain, which stall the M4 pipeline.
+//!
+//! The `target_feature = "v7"` cfg is only set by nightly compilers, so the
+//! backend is selected for all of `target_arch = "arm"`.
+//!
+//! Code size of the permutation for `thumbv7em-none-eabihf`, and in brackets
+//! of the whole crate without default features, as measured with `nm`:
+//!
+//! | `opt-level` | this backend       | portable backend   |
+//! |-------------|--------------------|--------------------|
+//! | `3`         | 2790 B (9044 B)    | 5682 B (11936 B)   |
+//! | `"s"`       | 2394 B (5182 B)    | 5634 B (8392 B)    |
+
+use crate::lanes::{PI, RC, RHO};
+
+/// Bits of `x` at even positions, packed into the low 32 bits.
+#[inline(always)]
+const fn even_bits(x: u64) -> u32 {
+    let mut x = x & 0x5555_5555_5555_5555;
+    x = (x | (x >> 1)) & 0x3333_3333_3333_3333;
+    x = (x | (x >> 2)) & 0x0F0F_0F0F_0F0F_0F0F;
+    x = (x | (x >> 4)) & 0x00FF_00FF_00FF_00FF;
+    x = (x | (x >> 8)) & 0x0000_FFFF_0000_FFFF;
+    x = (x | (x >> 16)) & 0x0000_0000_FFFF_FFFF;
+    x as u32
+}
+
+/// Inverse of [`even_bits`], spreading `x` over the even bit positions.
+#[inline(always)]
+const fn spread_bits(x: u32) -> u64 {
+    let mut x = x as u64;
+    x = (x | (x << 16)) & 0x0000_FFFF_0000_FFFF;
+    x = (x | (x << 8)) & 0x00FF_00FF_00FF_00FF;
+    x = (x | (x << 4)) & 0x0F0F_0F0F_0F0F_0F0F;
+    x = (x | (x << 2)) & 0x3333_3333_3333_3333;
+    x = (x | (x << 1)) & 0x5555_5555_5555_5555;
+    x
+}
+
+/// Split a lane into the words of its even and odd bits.
+#[inline(always)]
+const fn interleave(x: u64) -> (u32, u32) {
+    (even_bits(x), even_bits(x >> 1))
+}
+
+/// Inverse of [`interleave`].
+#[inline(always)]
+const fn deinterleave(even: u32, odd: u32) -> u64 {
+    spread_bits(even) | (spread_bits(odd) << 1)
+}
+
+/// Round constants of Keccak-f[1600], interleaved.
+const RC_INTERLEAVED: [(u32, u32); 24] = {
+    let mut rc = [(0, 0); 24];
+    let mut i = 0;
+    while i < 24 {
+        rc[i] = interleave(RC[i]);
+        i += 1;
+    }
+    rc
+};
+
+/// Rotate the interleaved lane `(even, odd)` left by `n` bits.
+#[inline(always)]
+fn rotl(even: u32, odd: u32, n: u32) -> (u32, u32) {
+    if n % 2 == 1 {
+        // Odd bits move to even positions and vice versa
+        (odd.rotate_left(n / 2 + 1), even.rotate_left(n / 2))
+    } else {
+        (even.rotate_left(n / 2), odd.rotate_left(n / 2))
+    }
+}
+
+/// Apply Keccak-p[1600, `rounds`] to a state.
+pub(crate) fn keccak_armv7m(state: &mut [u64; 25], rounds: usize) {
+    debug_assert!(rounds <= RC.len());
+
+    let mut e = [0u32; 25];
+    let mut o = [0u32; 25];
+    for (i, lane) in state.iter().enumerate() {
+        let (even, odd) = interleave(*lane);
+        e[i] = even;
+        o[i] = odd;
+    }
+
+    for &(rc_e, rc_o) in &RC_INTERLEAVED[RC.len() - rounds..] {
+        round(&mut e, &mut o, rc_e, rc_o);
+    }
+
+    for (i, lane) in state.iter_mut().enumerate() {
+        *lane = deinterleave(e[i], o[i]);
+    }
+}
+
+/// Apply one round of Keccak-f[1600] with the interleaved round constant
+/// `(rc_e, rc_o)` to the even words `e` and odd words `o` of the lanes.
+#[inline(always)]
+fn round(e: &mut [u32; 25], o: &mut [u32; 25], rc_e: u32, rc_o: u32) {
+    let mut c_e = [0u32; 5];
+    let mut c_o = [0u32; 5];
+    let (mut x, mut y): (usize, usize);
+
+    // θ
+    FOR5!(x, 1, {
+        c_e[x] = e[x] ^ e[x + 5] ^ e[x + 10] ^ e[x + 15] ^ e[x + 20];
+        c_o[x] = o[x] ^ o[x + 5] ^ o[x + 10] ^ o[x + 15] ^ o[x + 20];
+    });
+
+    FOR5!(x, 1, {
+        let (r_e, r_o) = rotl(c_e[(x + 1) % 5], c_o[(x + 1) % 5], 1);
+        let d_e = c_e[(x + 4) % 5] ^ r_e;
+        let d_o = c_o[(x + 4) % 5] ^ r_o;
+        FOR5!(y, 5, {
+            e[x + y] ^= d_e;
+            o[x + y] ^= d_o;
+        });
+    });
+
+    // ρ and π
+    let (mut a_e, mut a_o) = (e[1], o[1]);
+    x = 0;
+    REPEAT24!(
+        {
+            c_e[0] = e[PI[x]];
+            c_o[0] = o[PI[x]];
+            let (r_e, r_o) = rotl(a_e, a_o, RHO[x]);
+            e[PI[x]] = r_e;
+            o[PI[x]] = r_o;
+        },
+        {
+            a_e = c_e[0];
+            a_o = c_o[0];
+            x += 1;
+        }
+    );
+
+    // χ
+    FOR5!(y, 5, {
+        FOR5!(x, 1, {
+            c_e[x] = e[x + y];
+            c_o[x] = o[x + y];
+        });
+        FOR5!(x, 1, {
+            e[x + y] = c_e[x] ^ ((!c_e[(x + 1) % 5]) & c_e[(x + 2) % 5]);
+            o[x + y] = c_o[x] ^ ((!c_o[(x + 1) % 5]) & c_o[(x + 2) % 5]);
+        });
+    });
+
+    // ι
+    e[0] ^= rc_e;
+    o[0] ^= rc_o;
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::platform::{assert_matches_soft, xorshift64};
+
+    #[test]
+    fn interleave_roundtrip() {
+        let mut x = 0x9E37_79B9_7F4A_7C15u64;
+        for _ in 0..1000 {
+            let lane = xorshift64(&mut x);
+            let (even, odd) = interleave(lane);
+            assert_eq!(deinterleave(even, odd), lane);
+            for n in 0..64 {
+                let (r_e, r_o) = rotl(even, odd, n);
+                assert_eq!(deinterleave(r_e, r_o), lane.rotate_left(n));
+            }
+        }
+    }
+
+    #[test]
+    fn armv7m_matches_soft() {
+        assert_matches_soft(|states: &mut [[u64; 25]; 1], rounds| {
+            keccak_armv7m(&mut states[0], rounds)
+        });
+    }
+}
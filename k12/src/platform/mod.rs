@@ -0,0 +1,126 @@
+//! Backends for the Keccak-p[1600] permutation.
+
+#[cfg(all(feature = "neon", target_arch = "aarch64", target_feature = "neon"))]
+mod aarch64;
+// Portable, so it is also tested on other architectures
+#[cfg(any(test, target_arch = "arm"))]
+mod armv7m;
+#[cfg(all(target_arch = "wasm32", target_feature = "simd128"))]
+mod wasm;
+#[cfg(target_arch = "x86_64")]
+mod x86;
+
+// Only used for testing when the NEON or ARMv7-M backend is selected
+#[cfg_attr(
+    any(
+        all(feature = "neon", target_arch = "aarch64", target_feature = "neon"),
+        target_arch = "arm"
+    ),
+    allow(dead_code)
+)]
+mod soft;
+
+#[cfg(all(feature = "neon", target_arch = "aarch64", target_feature = "neon"))]
+pub(crate) use aarch64::keccak_neon as keccak_p;
+#[cfg(target_arch = "arm")]
+pub(crate) use armv7m::keccak_armv7m as keccak_p;
+#[cfg(target_arch = "x86_64")]
+pub(crate) use x86::{
+    has_avx2 as has_x4, has_avx512 as has_x8, keccak_p, keccak_p_x4, keccak_p_x8,
+};
+
+#[cfg(not(any(
+    all(feature = "neon", target_arch = "aarch64", target_feature = "neon"),
+    target_arch = "arm",
+    target_arch = "x86_64"
+)))]
+pub(crate) use soft::keccak_p;
+
+/// Whether [`keccak_p_x2`] is faster than applying [`keccak_p`] twice.
+pub(crate) const HAS_X2: bool = cfg!(all(target_arch = "wasm32", target_feature = "simd128"));
+
+#[cfg(all(target_arch = "wasm32", target_feature = "simd128"))]
+pub(crate) use wasm::keccak_wasm_simd_x2 as keccak_p_x2;
+
+/// Apply Keccak-p[1600, `rounds`] to two states.
+#[cfg(not(all(target_arch = "wasm32", target_feature = "simd128")))]
+pub(crate) fn keccak_p_x2(states: &mut [[u64; 25]; 2], rounds: usize) {
+    for state in states {
+        keccak_p(state, rounds);
+    }
+}
+
+/// Whether [`keccak_p_x4`] is faster than applying [`keccak_p`] four times.
+#[cfg(not(target_arch = "x86_64"))]
+pub(crate) fn has_x4() -> bool {
+    false
+}
+
+/// Apply Keccak-p[1600, `rounds`] to four states.
+#[cfg(not(target_arch = "x86_64"))]
+pub(crate) fn keccak_p_x4(states: &mut [[u64; 25]; 4], rounds: usize) {
+    for state in states {
+        keccak_p(state, rounds);
+    }
+}
+
+/// Whether [`keccak_p_x8`] is faster than applying [`keccak_p`] eight times.
+#[cfg(not(target_arch = "x86_64"))]
+pub(crate) fn has_x8() -> bool {
+    false
+}
+
+/// Apply Keccak-p[1600, `rounds`] to eight states.
+#[cfg(not(target_arch = "x86_64"))]
+pub(crate) fn keccak_p_x8(states: &mut [[u64; 25]; 8], rounds: usize) {
+    for state in states {
+        keccak_p(state, rounds);
+    }
+}
+
+/// Next value of the xorshift64 generator with the given `state`, for
+/// filling states with arbitrary data in tests.
+#[cfg(test)]
+fn xorshift64(state: &mut u64) -> u64 {
+    *state ^= *state << 13;
+    *state ^= *state >> 7;
+    *state ^= *state << 17;
+    *state
+}
+
+/// Check that `permute` agrees with the portable permutation on batches of
+/// `N` arbitrary states, for numbers of rounds from none to all 24.
+#[cfg(test)]
+fn assert_matches_soft<const N: usize>(permute: fn(&mut [[u64; 25]; N], usize)) {
+    let mut x = 0x9E37_79B9_7F4A_7C15u64;
+    let mut states = [[0u64; 25]; N];
+    for _ in 0..100 {
+        for lane in states.iter_mut().flatten() {
+            *lane = xorshift64(&mut x);
+        }
+        for &rounds in &[0, 1, 12, 14, 24] {
+            let mut expected = states;
+            for state in expected.iter_mut() {
+                soft::keccak_p(state, rounds);
+            }
+            let mut actual = states;
+            permute(&mut actual, rounds);
+            assert_eq!(actual, expected, "{} rounds", rounds);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// The backends selected for the current target and CPU agree with the
+    /// portable permutation.
+    #[test]
+    fn dispatch_matches_soft() {
+        assert_matches_soft(|states: &mut [[u64; 25]; 1], rounds| keccak_p(&mut states[0], rounds));
+        assert_matches_soft(keccak_p_x2);
+        assert_matches_soft(keccak_p_x4);
+        assert_matches_soft(keccak_p_x8);
+    }
+}
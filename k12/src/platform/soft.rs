@@ -0,0 +1,9 @@
+//! Portable backend for the Keccak-p[1600] permutation.
+
+use crate::lanes;
+
+/// Apply Keccak-p[1600, `rounds`] to a state.
+#[inline]
+pub(crate) fn keccak_p(state: &mut [u64; 25], rounds: usize) {
+    lanes::keccak_p(state, rounds);
+}
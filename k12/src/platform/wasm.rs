@@ -0,0 +1,92 @@
+//! SIMD128 backend for the Keccak-p[1600] permutation on `wasm32`.
+//!
+//! Two 64-bit lanes per vector are not enough to beat the scalar code on a
+//! single state, so this backend applies the permutation to two states at
+//! once, keeping the same lane of both states in each vector. The tree
+//! hashing mode uses it to process pairs of leaves.
+//!
+//! Unlike on other architectures the intrinsics are safe to call.
+
+use crate::lanes::{PI, RC, RHO};
+use core::arch::wasm32::*;
+
+/// Rotate both lanes of `v` left by `n` bits.
+#[inline(always)]
+fn rotl(v: v128, n: u32) -> v128 {
+    // Shift amounts are taken modulo 64, so a rotation by zero is preserved
+    v128_or(i64x2_shl(v, n), u64x2_shr(v, 64 - n))
+}
+
+/// Apply Keccak-p[1600, `rounds`] to two states.
+pub(crate) fn keccak_wasm_simd_x2(states: &mut [[u64; 25]; 2], rounds: usize) {
+    debug_assert!(rounds <= RC.len());
+
+    let mut lanes = [u64x2_splat(0); 25];
+    for (i, lane) in lanes.iter_mut().enumerate() {
+        *lane = u64x2(states[0][i], states[1][i]);
+    }
+
+    let mut c = [u64x2_splat(0); 5];
+    let (mut x, mut y): (usize, usize);
+
+    #[allow(clippy::needless_range_loop)]
+    for round in (RC.len() - rounds)..RC.len() {
+        // θ
+        FOR5!(x, 1, {
+            c[x] = v128_xor(
+                v128_xor(lanes[x], lanes[x + 5]),
+                v128_xor(v128_xor(lanes[x + 10], lanes[x + 15]), lanes[x + 20]),
+            );
+        });
+
+        FOR5!(x, 1, {
+            let d = v128_xor(c[(x + 4) % 5], rotl(c[(x + 1) % 5], 1));
+            FOR5!(y, 5, {
+                lanes[x + y] = v128_xor(lanes[x + y], d);
+            });
+        });
+
+        // ρ and π
+        let mut a = lanes[1];
+        x = 0;
+        REPEAT24!(
+            {
+                c[0] = lanes[PI[x]];
+                lanes[PI[x]] = rotl(a, RHO[x]);
+            },
+            {
+                a = c[0];
+                x += 1;
+            }
+        );
+
+        // χ
+        FOR5!(y, 5, {
+            FOR5!(x, 1, {
+                c[x] = lanes[x + y];
+            });
+            FOR5!(x, 1, {
+                lanes[x + y] = v128_xor(c[x], v128_andnot(c[(x + 2) % 5], c[(x + 1) % 5]));
+            });
+        });
+
+        // ι
+        lanes[0] = v128_xor(lanes[0], u64x2_splat(RC[round]));
+    }
+
+    for (i, lane) in lanes.iter().enumerate() {
+        states[0][i] = u64x2_extract_lane::<0>(*lane);
+        states[1][i] = u64x2_extract_lane::<1>(*lane);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::platform::assert_matches_soft;
+
+    #[test]
+    fn wasm_simd_matches_soft() {
+        assert_matches_soft(keccak_wasm_simd_x2);
+    }
+}
@@ -0,0 +1,511 @@
+//! AVX2 and AVX-512 backends for the Keccak-p[1600] permutation on `x86_64`.
+//!
+//! With AVX2, the steps of each round operate on four lanes at a time; the
+//! lane of each row which does not fit in a 256-bit vector is processed with
+//! scalar operations.
+//!
+//! Groups of four states can also be permuted at once with AVX2, keeping the
+//! same lane of all states in each 256-bit vector. The tree hashing mode uses
+//! it for groups of four leaves.
+//!
+//! With AVX-512, eight states are permuted at once, keeping the same lane of
+//! all states in each 512-bit vector. The tree hashing mode uses it for
+//! groups of eight leaves. The AVX-512 intrinsics require a newer compiler
+//! than the MSRV, so this backend is written in inline assembly.
+
+#![allow(unsafe_code)]
+
+use core::arch::{asm, x86_64::*};
+
+use super::soft;
+use crate::lanes::{PI, RC};
+use core::mem;
+#[cfg(feature = "zeroize")]
+use zeroize::Zeroize;
+
+cpufeatures::new!(avx2_cpuid, "avx2");
+cpufeatures::new!(avx512_cpuid, "avx512f");
+
+/// Rotation offsets of the ρ step, indexed by lane `x + 5 * y`.
+const RHO: [u64; 25] = [
+    0, 1, 62, 28, 27, 36, 44, 6, 55, 20, 3, 10, 43, 25, 39, 41, 45, 15, 21, 8, 18, 2, 61, 56, 14,
+];
+
+/// Apply Keccak-p[1600, `rounds`] to a state, using AVX2 if the CPU supports
+/// it.
+pub(crate) fn keccak_p(state: &mut [u64; 25], rounds: usize) {
+    // TODO: Replace with https://github.com/rust-lang/rfcs/pull/2725
+    // after stabilization
+    if avx2_cpuid::get() {
+        unsafe {
+            keccak_avx2(state, rounds);
+        }
+    } else {
+        soft::keccak_p(state, rounds);
+    }
+}
+
+/// Whether the CPU supports AVX2, so that [`keccak_p_x4`] is faster than
+/// applying [`keccak_p`] four times.
+pub(crate) fn has_avx2() -> bool {
+    avx2_cpuid::get()
+}
+
+/// Apply Keccak-p[1600, `rounds`] to four states, using AVX2 if the CPU
+/// supports it.
+pub(crate) fn keccak_p_x4(states: &mut [[u64; 25]; 4], rounds: usize) {
+    if avx2_cpuid::get() {
+        unsafe {
+            keccak_avx2_x4(states, rounds);
+        }
+    } else {
+        for state in states {
+            soft::keccak_p(state, rounds);
+        }
+    }
+}
+
+/// Whether the CPU supports AVX-512, so that [`keccak_p_x8`] is faster than
+/// applying [`keccak_p`] eight times.
+pub(crate) fn has_avx512() -> bool {
+    avx512_cpuid::get()
+}
+
+/// Apply Keccak-p[1600, `rounds`] to eight states, using AVX-512 if the CPU
+/// supports it.
+pub(crate) fn keccak_p_x8(states: &mut [[u64; 25]; 8], rounds: usize) {
+    if avx512_cpuid::get() {
+        unsafe {
+            keccak_avx512(states, rounds);
+        }
+    } else {
+        for state in states {
+            keccak_p(state, rounds);
+        }
+    }
+}
+
+/// Select lanes `a`, `b`, `c` and `d` of `v`.
+macro_rules! perm {
+    ($v:expr, $a:expr, $b:expr, $c:expr, $d:expr) => {
+        _mm256_permute4x64_epi64($v, $a | ($b << 2) | ($c << 4) | ($d << 6))
+    };
+}
+
+/// Take the lanes of `b` given by the bit mask `$lanes`, and the rest from `a`.
+macro_rules! blend {
+    ($a:expr, $b:expr, $lanes:expr) => {
+        _mm256_blend_epi32(
+            $a,
+            $b,
+            (($lanes & 1) * 0x03)
+                | (($lanes & 2) * 0x06)
+                | (($lanes & 4) * 0x0C)
+                | (($lanes & 8) * 0x18),
+        )
+    };
+}
+
+/// Rotate each lane of `v` left by the corresponding lane of `n`.
+#[inline]
+#[target_feature(enable = "avx2")]
+unsafe fn rotl(v: __m256i, n: __m256i) -> __m256i {
+    let m = _mm256_sub_epi64(_mm256_set1_epi64x(64), n);
+    _mm256_or_si256(_mm256_sllv_epi64(v, n), _mm256_srlv_epi64(v, m))
+}
+
+/// Apply Keccak-p[1600, `rounds`] to a state.
+///
+/// Each row `y` of the state is kept as a vector of the lanes `x = 0..4`
+/// and a scalar for the lane `x = 4`.
+///
+/// # Safety
+///
+/// The CPU must support AVX2.
+// we use unaligned loads with `__m256i` pointers
+#[allow(clippy::cast_ptr_alignment)]
+#[target_feature(enable = "avx2")]
+pub(crate) unsafe fn keccak_avx2(state: &mut [u64; 25], rounds: usize) {
+    debug_assert!(rounds <= RC.len());
+
+    let mut r = [_mm256_setzero_si256(); 5];
+    let mut s = [0u64; 5];
+    let mut rho = [_mm256_setzero_si256(); 5];
+    for y in 0..5 {
+        r[y] = _mm256_loadu_si256(state[5 * y..].as_ptr() as *const __m256i);
+        s[y] = state[5 * y + 4];
+        rho[y] = _mm256_loadu_si256(RHO[5 * y..5 * y + 4].as_ptr() as *const __m256i);
+    }
+
+    for &rc in &RC[RC.len() - rounds..] {
+        // θ
+        let c = _mm256_xor_si256(
+            _mm256_xor_si256(_mm256_xor_si256(r[0], r[1]), _mm256_xor_si256(r[2], r[3])),
+            r[4],
+        );
+        let c4 = s[0] ^ s[1] ^ s[2] ^ s[3] ^ s[4];
+        let c4v = _mm256_set1_epi64x(c4 as i64);
+        let prev = blend!(perm!(c, 3, 0, 1, 2), c4v, 0b0001);
+        let next = blend!(perm!(c, 1, 2, 3, 0), c4v, 0b1000);
+        let d = _mm256_xor_si256(
+            prev,
+            _mm256_or_si256(_mm256_slli_epi64(next, 1), _mm256_srli_epi64(next, 63)),
+        );
+        let d4 = (_mm256_extract_epi64(c, 3) as u64)
+            ^ (_mm256_extract_epi64(c, 0) as u64).rotate_left(1);
+
+        // θ and ρ
+        for y in 0..5 {
+            r[y] = rotl(_mm256_xor_si256(r[y], d), rho[y]);
+            s[y] = (s[y] ^ d4).rotate_left(RHO[5 * y + 4] as u32);
+        }
+
+        // π: row `Y` of the result takes column `(3 * Y + X) % 5` of row `X`
+        let b = [
+            blend!(
+                blend!(r[0], r[1], 0b0010),
+                blend!(r[2], r[3], 0b1000),
+                0b1100
+            ),
+            blend!(
+                perm!(blend!(blend!(r[2], r[3], 0b0010), r[0], 0b1000), 3, 0, 0, 1),
+                _mm256_set1_epi64x(s[1] as i64),
+                0b0010
+            ),
+            blend!(
+                perm!(blend!(blend!(r[0], r[1], 0b0100), r[2], 0b1000), 1, 2, 3, 0),
+                _mm256_set1_epi64x(s[3] as i64),
+                0b1000
+            ),
+            blend!(
+                perm!(blend!(blend!(r[1], r[2], 0b0010), r[3], 0b0100), 0, 0, 1, 2),
+                _mm256_set1_epi64x(s[0] as i64),
+                0b0001
+            ),
+            blend!(
+                perm!(blend!(blend!(r[3], r[0], 0b0100), r[1], 0b1000), 2, 3, 0, 0),
+                _mm256_set1_epi64x(s[2] as i64),
+                0b0100
+            ),
+        ];
+        let t = [
+            s[4],
+            _mm256_extract_epi64(r[4], 2) as u64,
+            _mm256_extract_epi64(r[4], 0) as u64,
+            _mm256_extract_epi64(r[4], 3) as u64,
+            _mm256_extract_epi64(r[4], 1) as u64,
+        ];
+
+        // χ
+        for y in 0..5 {
+            let tv = _mm256_set1_epi64x(t[y] as i64);
+            let next = blend!(perm!(b[y], 1, 2, 3, 0), tv, 0b1000);
+            let next2 = blend!(perm!(b[y], 2, 3, 0, 0), tv, 0b0100);
+            r[y] = _mm256_xor_si256(b[y], _mm256_andnot_si256(next, next2));
+            let b0 = _mm256_extract_epi64(b[y], 0) as u64;
+            let b1 = _mm256_extract_epi64(b[y], 1) as u64;
+            s[y] = t[y] ^ (!b0 & b1);
+        }
+
+        // ι
+        r[0] = _mm256_xor_si256(r[0], _mm256_set_epi64x(0, 0, 0, rc as i64));
+    }
+
+    for y in 0..5 {
+        _mm256_storeu_si256(state[5 * y..].as_mut_ptr() as *mut __m256i, r[y]);
+        state[5 * y + 4] = s[y];
+    }
+}
+
+/// Rotate the four lanes of `v` left by `n` bits.
+#[inline]
+#[target_feature(enable = "avx2")]
+unsafe fn rotl_x4(v: __m256i, n: u32) -> __m256i {
+    _mm256_or_si256(
+        _mm256_sll_epi64(v, _mm_cvtsi32_si128(n as i32)),
+        _mm256_srl_epi64(v, _mm_cvtsi32_si128(64 - n as i32)),
+    )
+}
+
+/// Apply Keccak-p[1600, `rounds`] to four states, keeping the same lane of
+/// all states in each vector.
+///
+/// # Safety
+///
+/// The CPU must support AVX2.
+#[target_feature(enable = "avx2")]
+pub(crate) unsafe fn keccak_avx2_x4(states: &mut [[u64; 25]; 4], rounds: usize) {
+    debug_assert!(rounds <= RC.len());
+
+    let mut lanes = [_mm256_setzero_si256(); 25];
+    for (i, lane) in lanes.iter_mut().enumerate() {
+        *lane = _mm256_set_epi64x(
+            states[3][i] as i64,
+            states[2][i] as i64,
+            states[1][i] as i64,
+            states[0][i] as i64,
+        );
+    }
+
+    let mut c = [_mm256_setzero_si256(); 5];
+    let (mut x, mut y): (usize, usize);
+
+    #[allow(clippy::needless_range_loop)]
+    for round in (RC.len() - rounds)..RC.len() {
+        // θ
+        FOR5!(x, 1, {
+            c[x] = _mm256_xor_si256(
+                _mm256_xor_si256(lanes[x], lanes[x + 5]),
+                _mm256_xor_si256(
+                    _mm256_xor_si256(lanes[x + 10], lanes[x + 15]),
+                    lanes[x + 20],
+                ),
+            );
+        });
+
+        FOR5!(x, 1, {
+            let d = _mm256_xor_si256(c[(x + 4) % 5], rotl_x4(c[(x + 1) % 5], 1));
+            FOR5!(y, 5, {
+                lanes[x + y] = _mm256_xor_si256(lanes[x + y], d);
+            });
+        });
+
+        // ρ and π
+        let mut a = lanes[1];
+        x = 0;
+        REPEAT24!(
+            {
+                c[0] = lanes[PI[x]];
+                lanes[PI[x]] = rotl_x4(a, crate::lanes::RHO[x]);
+            },
+            {
+                a = c[0];
+                x += 1;
+            }
+        );
+
+        // χ
+        FOR5!(y, 5, {
+            FOR5!(x, 1, {
+                c[x] = lanes[x + y];
+            });
+            FOR5!(x, 1, {
+                lanes[x + y] =
+                    _mm256_xor_si256(c[x], _mm256_andnot_si256(c[(x + 1) % 5], c[(x + 2) % 5]));
+            });
+        });
+
+        // ι
+        lanes[0] = _mm256_xor_si256(lanes[0], _mm256_set1_epi64x(RC[round] as i64));
+    }
+
+    let mut out = [0u64; 4];
+    for (i, lane) in lanes.iter().enumerate() {
+        _mm256_storeu_si256(out.as_mut_ptr() as *mut __m256i, *lane);
+        for (state, l) in states.iter_mut().zip(&out) {
+            state[i] = *l;
+        }
+    }
+
+    #[cfg(feature = "zeroize")]
+    out.zeroize();
+}
+
+/// Lane `i` of eight states, stored at `64 * i` bytes.
+#[repr(C, align(64))]
+struct Lanes([[u64; 8]; 25]);
+
+/// Parity of column `x` of `{src}` into `zmm{x}`.
+#[rustfmt::skip]
+macro_rules! parity {
+    ($x:literal) => {
+        concat!(
+            "vmovdqu64 zmm", $x, ", zmmword ptr [{src} + 64 * ", $x, "]\n",
+            "vmovdqu64 zmm15, zmmword ptr [{src} + 64 * (", $x, " + 5)]\n",
+            "vpternlogq zmm", $x, ", zmm15, zmmword ptr [{src} + 64 * (", $x, " + 10)], 0x96\n",
+            "vmovdqu64 zmm15, zmmword ptr [{src} + 64 * (", $x, " + 15)]\n",
+            "vpternlogq zmm", $x, ", zmm15, zmmword ptr [{src} + 64 * (", $x, " + 20)], 0x96\n",
+        )
+    };
+}
+
+/// Value `zmm{prev} ^ rotl(zmm{next}, 1)` of the θ step into `zmm{d}`.
+#[rustfmt::skip]
+macro_rules! theta {
+    ($d:literal, $prev:literal, $next:literal) => {
+        concat!(
+            "vprolq zmm", $d, ", zmm", $next, ", 1\n",
+            "vpxorq zmm", $d, ", zmm", $d, ", zmm", $prev, "\n",
+        )
+    };
+}
+
+/// Lane `i` of `{src}` after the θ, ρ and π steps into `zmm{b}`.
+#[rustfmt::skip]
+macro_rules! lane {
+    ($b:literal, $i:literal, $d:literal, $rho:literal) => {
+        concat!(
+            "vpxorq zmm", $b, ", zmm", $d, ", zmmword ptr [{src} + 64 * ", $i, "]\n",
+            "vprolq zmm", $b, ", zmm", $b, ", ", $rho, "\n",
+        )
+    };
+}
+
+/// χ step for row `y` of `{dst}` from the lanes in `zmm10` to `zmm14`.
+#[rustfmt::skip]
+macro_rules! chi {
+    ($y:literal) => {
+        concat!(
+            chi!($y, 0, 10, 11, 12),
+            chi!($y, 1, 11, 12, 13),
+            chi!($y, 2, 12, 13, 14),
+            chi!($y, 3, 13, 14, 10),
+            chi!($y, 4, 14, 10, 11),
+        )
+    };
+    ($y:literal, $x:literal, $a:literal, $b:literal, $c:literal) => {
+        concat!(
+            "vmovdqa64 zmm15, zmm", $a, "\n",
+            // a ^ (!b & c)
+            "vpternlogq zmm15, zmm", $b, ", zmm", $c, ", 0xD2\n",
+            "vmovdqu64 zmmword ptr [{dst} + 64 * (5 * ", $y, " + ", $x, ")], zmm15\n",
+        )
+    };
+}
+
+/// Apply one round of Keccak-p[1600] with the round constant `rc` to the
+/// states in `src`, writing the result to `dst`.
+///
+/// Only `zmm0` to `zmm15` are used, since the compiler is unaware of the
+/// upper registers when AVX-512 is not enabled at compile time.
+///
+/// # Safety
+///
+/// The CPU must support AVX-512F.
+#[inline(always)]
+unsafe fn round_avx512(src: &Lanes, dst: &mut Lanes, rc: &u64) {
+    asm!(
+        // θ: column parities in zmm0 to zmm4, and the values to add to each
+        // column in zmm5 to zmm9
+        parity!(0),
+        parity!(1),
+        parity!(2),
+        parity!(3),
+        parity!(4),
+        theta!(5, 4, 1),
+        theta!(6, 0, 2),
+        theta!(7, 1, 3),
+        theta!(8, 2, 4),
+        theta!(9, 3, 0),
+        // Row `y` of the result takes column `(3 * y + x) % 5` of row `x`
+        // after θ and ρ, followed by χ
+        lane!(10, 0, 5, 0),
+        lane!(11, 6, 6, 44),
+        lane!(12, 12, 7, 43),
+        lane!(13, 18, 8, 21),
+        lane!(14, 24, 9, 14),
+        chi!(0),
+        lane!(10, 3, 8, 28),
+        lane!(11, 9, 9, 20),
+        lane!(12, 10, 5, 3),
+        lane!(13, 16, 6, 45),
+        lane!(14, 22, 7, 61),
+        chi!(1),
+        lane!(10, 1, 6, 1),
+        lane!(11, 7, 7, 6),
+        lane!(12, 13, 8, 25),
+        lane!(13, 19, 9, 8),
+        lane!(14, 20, 5, 18),
+        chi!(2),
+        lane!(10, 4, 9, 27),
+        lane!(11, 5, 5, 36),
+        lane!(12, 11, 6, 10),
+        lane!(13, 17, 7, 15),
+        lane!(14, 23, 8, 56),
+        chi!(3),
+        lane!(10, 2, 7, 62),
+        lane!(11, 8, 8, 55),
+        lane!(12, 14, 9, 39),
+        lane!(13, 15, 5, 41),
+        lane!(14, 21, 6, 2),
+        chi!(4),
+        // ι
+        "vmovdqu64 zmm15, zmmword ptr [{dst}]",
+        "vpxorq zmm15, zmm15, qword ptr [{rc}]{{1to8}}",
+        "vmovdqu64 zmmword ptr [{dst}], zmm15",
+        // Avoid penalties for mixing with SSE instructions
+        "vzeroupper",
+        src = in(reg) src.0.as_ptr(),
+        dst = in(reg) dst.0.as_mut_ptr(),
+        rc = in(reg) rc,
+        out("xmm0") _, out("xmm1") _, out("xmm2") _, out("xmm3") _,
+        out("xmm4") _, out("xmm5") _, out("xmm6") _, out("xmm7") _,
+        out("xmm8") _, out("xmm9") _, out("xmm10") _, out("xmm11") _,
+        out("xmm12") _, out("xmm13") _, out("xmm14") _, out("xmm15") _,
+        options(nostack, preserves_flags),
+    );
+}
+
+/// Apply Keccak-p[1600, `rounds`] to eight states.
+///
+/// # Safety
+///
+/// The CPU must support AVX-512F.
+pub(crate) unsafe fn keccak_avx512(states: &mut [[u64; 25]; 8], rounds: usize) {
+    debug_assert!(rounds <= RC.len());
+
+    let mut a = Lanes([[0u64; 8]; 25]);
+    let mut b = Lanes([[0u64; 8]; 25]);
+    for (i, lane) in a.0.iter_mut().enumerate() {
+        for (l, state) in lane.iter_mut().zip(states.iter()) {
+            *l = state[i];
+        }
+    }
+
+    let (mut src, mut dst) = (&mut a, &mut b);
+    for rc in &RC[RC.len() - rounds..] {
+        round_avx512(src, dst, rc);
+        mem::swap(&mut src, &mut dst);
+    }
+
+    for (i, lane) in src.0.iter().enumerate() {
+        for (l, state) in lane.iter().zip(states.iter_mut()) {
+            state[i] = *l;
+        }
+    }
+
+    #[cfg(feature = "zeroize")]
+    {
+        a.0.zeroize();
+        b.0.zeroize();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::platform::assert_matches_soft;
+
+    #[test]
+    fn avx2_matches_soft() {
+        if avx2_cpuid::get() {
+            assert_matches_soft(|states: &mut [[u64; 25]; 1], rounds| unsafe {
+                keccak_avx2(&mut states[0], rounds)
+            });
+        }
+    }
+
+    #[test]
+    fn avx2_x4_matches_soft() {
+        if avx2_cpuid::get() {
+            assert_matches_soft(|states, rounds| unsafe { keccak_avx2_x4(states, rounds) });
+        }
+    }
+
+    #[test]
+    fn avx512_matches_soft() {
+        if avx512_cpuid::get() {
+            assert_matches_soft(|states, rounds| unsafe { keccak_avx512(states, rounds) });
+        }
+    }
+}
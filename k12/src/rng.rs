@@ -0,0 +1,100 @@
+//! Deterministic random bit generator on the output of KangarooTwelve.
+
+use crate::{tree, Squeezer};
+use core::fmt;
+#[cfg(feature = "rand_core")]
+use rand_core::{CryptoRng, Error as RngError, RngCore, SeedableRng};
+#[cfg(feature = "zeroize")]
+use zeroize::ZeroizeOnDrop;
+
+/// Deterministic cryptographically secure pseudorandom number generator,
+/// which reads its output from KangarooTwelve.
+///
+/// The output stream is the KangarooTwelve output for the 32-byte seed with
+/// the personalization as customization string, so that generators with the
+/// same seed and different personalizations are independent.
+///
+/// With the `rand_core` feature this implements [`RngCore`],
+/// [`CryptoRng`] and [`SeedableRng`].
+///
+/// The generator never reseeds, so it is only as unpredictable as its seed.
+#[derive(Clone)]
+pub struct K12Rng {
+    /// Output stream
+    squeezer: Squeezer,
+}
+
+impl K12Rng {
+    /// Create a generator from a 32-byte seed.
+    pub fn new(seed: [u8; 32]) -> Self {
+        Self::new_with_personalization(seed, [])
+    }
+
+    /// Create a generator from a 32-byte seed and a personalization string.
+    pub fn new_with_personalization(seed: [u8; 32], personalization: impl AsRef<[u8]>) -> Self {
+        Self {
+            squeezer: tree::finalize(tree::K12, &seed, personalization.as_ref()),
+        }
+    }
+
+    /// Fill `dest` with the next bytes of output.
+    pub fn fill_bytes(&mut self, dest: &mut [u8]) {
+        self.squeezer.read(dest);
+    }
+
+    /// Next 4 bytes of output as a little-endian integer.
+    pub fn next_u32(&mut self) -> u32 {
+        let mut bytes = [0u8; 4];
+        self.fill_bytes(&mut bytes);
+        u32::from_le_bytes(bytes)
+    }
+
+    /// Next 8 bytes of output as a little-endian integer.
+    pub fn next_u64(&mut self) -> u64 {
+        let mut bytes = [0u8; 8];
+        self.fill_bytes(&mut bytes);
+        u64::from_le_bytes(bytes)
+    }
+}
+
+impl fmt::Debug for K12Rng {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("K12Rng { ... }")
+    }
+}
+
+#[cfg(feature = "rand_core")]
+impl RngCore for K12Rng {
+    fn next_u32(&mut self) -> u32 {
+        K12Rng::next_u32(self)
+    }
+
+    fn next_u64(&mut self) -> u64 {
+        K12Rng::next_u64(self)
+    }
+
+    fn fill_bytes(&mut self, dest: &mut [u8]) {
+        K12Rng::fill_bytes(self, dest);
+    }
+
+    fn try_fill_bytes(&mut self, dest: &mut [u8]) -> Result<(), RngError> {
+        K12Rng::fill_bytes(self, dest);
+        Ok(())
+    }
+}
+
+#[cfg(feature = "rand_core")]
+impl CryptoRng for K12Rng {}
+
+#[cfg(feature = "rand_core")]
+impl SeedableRng for K12Rng {
+    type Seed = [u8; 32];
+
+    fn from_seed(seed: Self::Seed) -> Self {
+        Self::new(seed)
+    }
+}
+
+// The squeezer zeroizes its state when dropped
+#[cfg(feature = "zeroize")]
+impl ZeroizeOnDrop for K12Rng {}
@@ -0,0 +1,94 @@
+//! KangarooTwelve for real-time contexts, which never allocates.
+
+use crate::tree::{self, K12Tree};
+use core::{fmt, mem};
+#[cfg(feature = "zeroize")]
+use zeroize::ZeroizeOnDrop;
+
+/// The KangarooTwelve hash function writing its output into a buffer
+/// provided by the caller, for contexts where allocating is not allowed,
+/// such as audio callbacks or interrupt handlers.
+///
+/// Unlike [`KangarooTwelve`](crate::KangarooTwelve) this borrows the
+/// customization string instead of copying it, and it never hashes leaves
+/// with `rayon`, so no method allocates, even with the `alloc` and `rayon`
+/// features. Unlike [`StackKangarooTwelve`](crate::StackKangarooTwelve) the
+/// input is not buffered, so its length is not bounded.
+///
+/// The chaining values of the leaves are absorbed into the final node as
+/// soon as they are computed, so no buffer for them is needed.
+///
+/// ```
+/// use k12::KangarooTwelveRt;
+///
+/// let mut output = [0u8; 32];
+/// let mut hasher = KangarooTwelveRt::new(b"customization", &mut output);
+/// for block in [&b"first"[..], b"second"] {
+///     hasher.update(block);
+///     let tag = hasher.finalize();
+///     assert_eq!(*tag, *k12::hash_fixed::<32>(block, b"customization"));
+/// }
+/// ```
+pub struct KangarooTwelveRt<'a> {
+    /// Tree hash of the input absorbed so far
+    tree: K12Tree,
+
+    /// Customization string to apply
+    customization: &'a [u8],
+
+    /// Buffer for the output
+    output: &'a mut [u8],
+}
+
+impl<'a> KangarooTwelveRt<'a> {
+    /// Create a hasher which writes `output.len()` bytes of output for the
+    /// given customization string into `output`.
+    pub fn new(customization: &'a [u8], output: &'a mut [u8]) -> Self {
+        Self {
+            tree: empty_tree(),
+            customization,
+            output,
+        }
+    }
+
+    /// Input data into the hash function.
+    pub fn update(&mut self, bytes: impl AsRef<[u8]>) {
+        self.tree.update(bytes.as_ref());
+    }
+
+    /// Write the output for the input absorbed so far into the output
+    /// buffer and return it.
+    ///
+    /// The input is discarded, so the hasher can be reused for the next
+    /// message.
+    pub fn finalize(&mut self) -> &[u8] {
+        mem::replace(&mut self.tree, empty_tree())
+            .finalize_with_customization(self.customization)
+            .read(self.output);
+        self.output
+    }
+
+    /// Release the output buffer.
+    pub fn into_output(self) -> &'a mut [u8] {
+        self.output
+    }
+}
+
+/// Tree which hashes its leaves on the calling thread.
+fn empty_tree() -> K12Tree {
+    #[cfg_attr(not(feature = "rayon"), allow(unused_mut))]
+    let mut tree = K12Tree::new(tree::K12);
+    #[cfg(feature = "rayon")]
+    tree.set_parallelism(1);
+    tree
+}
+
+impl fmt::Debug for KangarooTwelveRt<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("KangarooTwelveRt { ... }")
+    }
+}
+
+// The sponges of the tree zeroize themselves when dropped
+#[cfg(feature = "zeroize")]
+impl ZeroizeOnDrop for KangarooTwelveRt<'_> {}
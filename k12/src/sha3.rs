@@ -0,0 +1,131 @@
+//! SHA-3 hash functions of FIPS 202, and the original Keccak padding used by
+//! Ethereum.
+
+use crate::sponge::{KeccakP1600, Sponge};
+use core::fmt;
+#[cfg(feature = "digest")]
+use digest::{
+    consts::{U136, U32},
+    generic_array::GenericArray,
+    BlockInput, FixedOutputDirty, Reset, Update,
+};
+#[cfg(feature = "zeroize")]
+use zeroize::ZeroizeOnDrop;
+
+/// Number of rounds of Keccak-f[1600].
+const ROUNDS: usize = 24;
+
+macro_rules! impl_sha3 {
+    ($name:ident, $rate:expr, $capacity:expr, $len:expr, $output_size:ident, $block_size:ident, $suffix:expr, $doc:expr) => {
+        #[allow(non_camel_case_types)]
+        #[derive(Clone)]
+        #[doc = $doc]
+        pub struct $name {
+            /// Sponge absorbing the input
+            sponge: Sponge<KeccakP1600<ROUNDS>, { $rate }, { $capacity }>,
+        }
+
+        impl $name {
+            #[doc = concat!("Create a new [`", stringify!($name), "`] instance.")]
+            pub fn new() -> Self {
+                Self {
+                    sponge: Sponge::new(),
+                }
+            }
+
+            /// Input data into the hash function.
+            pub fn update(&mut self, bytes: impl AsRef<[u8]>) {
+                self.sponge.absorb(bytes.as_ref());
+            }
+
+            /// Input data into the hash function, returning the updated hasher.
+            pub fn chain(mut self, bytes: impl AsRef<[u8]>) -> Self {
+                self.update(bytes);
+                self
+            }
+
+            /// Retrieve the hash and consume the hasher.
+            pub fn finalize(self) -> [u8; $len] {
+                let mut output = [0u8; $len];
+                self.sponge.finalize($suffix).read(&mut output);
+                output
+            }
+        }
+
+        impl Default for $name {
+            fn default() -> Self {
+                Self::new()
+            }
+        }
+
+        /// The block size is the rate of the sponge, which is what HMAC pads
+        /// the key to.
+        #[cfg(feature = "digest")]
+        impl BlockInput for $name {
+            type BlockSize = $block_size;
+        }
+
+        #[cfg(feature = "digest")]
+        impl Update for $name {
+            fn update(&mut self, bytes: impl AsRef<[u8]>) {
+                $name::update(self, bytes);
+            }
+        }
+
+        #[cfg(feature = "digest")]
+        impl FixedOutputDirty for $name {
+            type OutputSize = $output_size;
+
+            fn finalize_into_dirty(&mut self, out: &mut GenericArray<u8, Self::OutputSize>) {
+                self.sponge.clone().finalize($suffix).read(out);
+            }
+        }
+
+        #[cfg(feature = "digest")]
+        impl Reset for $name {
+            fn reset(&mut self) {
+                self.sponge = Sponge::new();
+            }
+        }
+
+        // The sponge zeroizes its state when dropped
+        #[cfg(feature = "zeroize")]
+        impl ZeroizeOnDrop for $name {}
+
+        impl fmt::Debug for $name {
+            fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+                f.write_str(concat!(stringify!($name), " { ... }"))
+            }
+        }
+    };
+}
+
+impl_sha3!(
+    Sha3_256,
+    1088 / 8,
+    512 / 8,
+    32,
+    U32,
+    U136,
+    0x06,
+    "The SHA3-256 hash function of FIPS 202, i.e. a sponge over the full
+Keccak-f[1600] permutation with a capacity of 512 bits, giving the same
+output as `sha3::Sha3_256`."
+);
+impl_sha3!(
+    Keccak256,
+    1088 / 8,
+    512 / 8,
+    32,
+    U32,
+    U136,
+    0x01,
+    "The Keccak-256 hash function used by Ethereum, giving the same output as
+`sha3::Keccak256`.
+
+This is the sponge of [`Sha3_256`] with the padding of the original Keccak
+submission, whose domain separation suffix is `0x01` instead of the `0x06` of
+FIPS 202. The outputs of both differ for every input, so SHA3-256 cannot be
+used in place of Keccak-256 for Ethereum hashes, addresses and function
+selectors, or vice versa."
+);
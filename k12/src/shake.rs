@@ -0,0 +1,104 @@
+//! SHAKE extendable-output functions of FIPS 202.
+
+use crate::{
+    sponge::{KeccakP1600, Sponge},
+    Squeezer,
+};
+use core::fmt;
+#[cfg(feature = "digest")]
+use digest::{ExtendableOutputDirty, Reset, Update};
+#[cfg(feature = "zeroize")]
+use zeroize::ZeroizeOnDrop;
+
+/// Domain separation suffix of SHAKE, including the first bit of padding.
+const SUFFIX: u8 = 0x1F;
+
+/// Number of rounds of Keccak-f[1600].
+const ROUNDS: usize = 24;
+
+/// A SHAKE extendable-output function (XOF) of FIPS 202, i.e. a sponge over
+/// the full Keccak-f[1600] permutation with a rate of `RATE` bytes and a
+/// capacity of `CAPACITY` bytes, which add up to the 200 bytes of the state.
+///
+/// This is the sponge of TurboSHAKE with 24 instead of 12 rounds. Use the
+/// [`Shake128`] and [`Shake256`] instances:
+///
+/// <https://nvlpubs.nist.gov/nistpubs/FIPS/NIST.FIPS.202.pdf>
+#[derive(Clone)]
+pub struct ShakeCore<const RATE: usize, const CAPACITY: usize> {
+    /// Sponge absorbing the input
+    sponge: Sponge<KeccakP1600<ROUNDS>, RATE, CAPACITY>,
+}
+
+/// The SHAKE128 XOF, with a capacity of 256 bits, giving the same output as
+/// `sha3::Shake128`.
+pub type Shake128 = ShakeCore<{ 1344 / 8 }, { 256 / 8 }>;
+
+/// The SHAKE256 XOF, with a capacity of 512 bits, giving the same output as
+/// `sha3::Shake256`.
+pub type Shake256 = ShakeCore<{ 1088 / 8 }, { 512 / 8 }>;
+
+impl<const RATE: usize, const CAPACITY: usize> ShakeCore<RATE, CAPACITY> {
+    /// Create a new instance.
+    pub fn new() -> Self {
+        Self {
+            sponge: Sponge::new(),
+        }
+    }
+
+    /// Input data into the hash function.
+    pub fn update(&mut self, bytes: impl AsRef<[u8]>) {
+        self.sponge.absorb(bytes.as_ref());
+    }
+
+    /// Input data into the hash function, returning the updated hasher.
+    pub fn chain(mut self, bytes: impl AsRef<[u8]>) -> Self {
+        self.update(bytes);
+        self
+    }
+
+    /// Retrieve the [`Squeezer`] for the output and consume the hasher.
+    pub fn finalize_xof(self) -> Squeezer {
+        self.sponge.finalize(SUFFIX)
+    }
+}
+
+impl<const RATE: usize, const CAPACITY: usize> Default for ShakeCore<RATE, CAPACITY> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(feature = "digest")]
+impl<const RATE: usize, const CAPACITY: usize> Update for ShakeCore<RATE, CAPACITY> {
+    fn update(&mut self, bytes: impl AsRef<[u8]>) {
+        ShakeCore::update(self, bytes);
+    }
+}
+
+#[cfg(feature = "digest")]
+impl<const RATE: usize, const CAPACITY: usize> ExtendableOutputDirty for ShakeCore<RATE, CAPACITY> {
+    type Reader = Squeezer;
+
+    fn finalize_xof_dirty(&mut self) -> Self::Reader {
+        self.sponge.clone().finalize(SUFFIX)
+    }
+}
+
+#[cfg(feature = "digest")]
+impl<const RATE: usize, const CAPACITY: usize> Reset for ShakeCore<RATE, CAPACITY> {
+    fn reset(&mut self) {
+        self.sponge = Sponge::new();
+    }
+}
+
+// The sponge zeroizes its state when dropped
+#[cfg(feature = "zeroize")]
+impl<const RATE: usize, const CAPACITY: usize> ZeroizeOnDrop for ShakeCore<RATE, CAPACITY> {}
+
+impl<const RATE: usize, const CAPACITY: usize> fmt::Debug for ShakeCore<RATE, CAPACITY> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        // The security level is half the capacity
+        write!(f, "Shake{} {{ ... }}", 4 * CAPACITY)
+    }
+}
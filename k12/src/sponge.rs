@@ -0,0 +1,608 @@
+//! Sponge construction over any permutation, used by the tree nodes,
+//! TurboSHAKE, SHAKE and SHA-3 with Keccak-p[1600].
+
+use crate::{
+    lanes::{absorb_bytes, squeeze_bytes},
+    lanes32,
+    platform::keccak_p,
+};
+use core::{cmp::min, fmt, marker::PhantomData, mem};
+#[cfg(feature = "digest")]
+use digest::XofReader;
+#[cfg(feature = "zeroize")]
+use zeroize::{Zeroize, ZeroizeOnDrop};
+#[cfg(feature = "alloc")]
+use {crate::encoding::right_encode, alloc::vec::Vec};
+
+/// A cryptographic permutation of a state of [`WIDTH`](Self::WIDTH) bytes,
+/// which a [`Sponge`] can be built on.
+pub trait Permutation {
+    /// State of the permutation
+    #[cfg(not(feature = "zeroize"))]
+    type State: Clone + Default;
+
+    /// State of the permutation
+    #[cfg(feature = "zeroize")]
+    type State: Clone + Default + Zeroize;
+
+    /// Size of the state in bytes
+    const WIDTH: usize;
+
+    /// Apply the permutation to `state`.
+    fn permute(state: &mut Self::State);
+
+    /// XOR `input` into the bytes of `state` starting at `offset`.
+    fn absorb_bytes(state: &mut Self::State, offset: usize, input: &[u8]);
+
+    /// Copy the bytes of `state` starting at `offset` to `output`.
+    fn squeeze_bytes(state: &Self::State, offset: usize, output: &mut [u8]);
+}
+
+/// The Keccak-p[1600, `ROUNDS`] permutation, i.e. the last `ROUNDS` of the
+/// 24 rounds of Keccak-f[1600], on the fastest backend for the target.
+#[derive(Clone, Copy, Debug)]
+pub struct KeccakP1600<const ROUNDS: usize>;
+
+/// The Keccak-p[1600, 12] permutation of KangarooTwelve and TurboSHAKE.
+pub type Keccak1600x12 = KeccakP1600<12>;
+
+/// The Keccak-f[1600] permutation of SHA-3 and SHAKE, with all 24 rounds.
+pub type Keccak1600x24 = KeccakP1600<24>;
+
+impl<const ROUNDS: usize> Permutation for KeccakP1600<ROUNDS> {
+    type State = [u64; 25];
+
+    const WIDTH: usize = 200;
+
+    fn permute(state: &mut Self::State) {
+        keccak_p(state, ROUNDS);
+    }
+
+    fn absorb_bytes(state: &mut Self::State, offset: usize, input: &[u8]) {
+        absorb_bytes(state, offset, input);
+    }
+
+    fn squeeze_bytes(state: &Self::State, offset: usize, output: &mut [u8]) {
+        squeeze_bytes(state, offset, output);
+    }
+}
+
+/// The Keccak-p[800, `ROUNDS`] permutation, i.e. the last `ROUNDS` of the
+/// 22 rounds of Keccak-f[800], using only 32-bit arithmetic.
+#[derive(Clone, Copy, Debug)]
+pub struct KeccakP800<const ROUNDS: usize>;
+
+impl<const ROUNDS: usize> Permutation for KeccakP800<ROUNDS> {
+    type State = [u32; 25];
+
+    const WIDTH: usize = 100;
+
+    fn permute(state: &mut Self::State) {
+        lanes32::keccak_p(state, ROUNDS);
+    }
+
+    fn absorb_bytes(state: &mut Self::State, offset: usize, input: &[u8]) {
+        lanes32::absorb_bytes(state, offset, input);
+    }
+
+    fn squeeze_bytes(state: &Self::State, offset: usize, output: &mut [u8]) {
+        lanes32::squeeze_bytes(state, offset, output);
+    }
+}
+
+/// Sponge in the absorbing phase, over the permutation `P` with a rate of
+/// `RATE` bytes and a capacity of `CAP` bytes.
+///
+/// The rate and capacity must add up to the width of the permutation, which
+/// is checked when the sponge is instantiated:
+///
+/// ```compile_fail
+/// use k12::{KeccakP1600, Sponge};
+///
+/// let sponge = Sponge::<KeccakP1600<24>, 168, 64>::new();
+/// ```
+///
+/// The input is padded with a domain separation suffix, which includes the
+/// first bit of padding as in FIPS 202, followed by `0x80` in the last byte
+/// of the block:
+///
+/// ```
+/// use k12::{KeccakP1600, Sponge};
+///
+/// // SHAKE128
+/// let mut sponge = Sponge::<KeccakP1600<24>, 168, 32>::new();
+/// sponge.absorb(b"abc");
+/// let mut output = [0u8; 4];
+/// sponge.finalize_xof(0x1F).read(&mut output);
+/// assert_eq!(output, [0x58, 0x81, 0x09, 0x2D]);
+/// ```
+pub struct Sponge<P: Permutation, const RATE: usize, const CAP: usize> {
+    /// State of the permutation
+    state: P::State,
+
+    /// Number of bytes absorbed into the current block
+    pos: usize,
+}
+
+impl<P: Permutation, const RATE: usize, const CAP: usize> Sponge<P, RATE, CAP> {
+    /// Compile-time check of the rate and capacity.
+    const VALID: () = assert!(
+        RATE > 0 && CAP > 0 && RATE + CAP == P::WIDTH,
+        "the rate and capacity must add up to the width of the permutation"
+    );
+
+    /// Create a sponge with an all-zero state.
+    pub fn new() -> Self {
+        #[allow(clippy::let_unit_value)]
+        let () = Self::VALID;
+        Self {
+            state: P::State::default(),
+            pos: 0,
+        }
+    }
+
+    /// Absorb `input` into the sponge.
+    ///
+    /// The permutation is only applied once more input arrives for a full
+    /// block, so that padding can be applied to the last block.
+    pub fn absorb(&mut self, mut input: &[u8]) {
+        while !input.is_empty() {
+            if self.pos == RATE {
+                P::permute(&mut self.state);
+                self.pos = 0;
+            }
+
+            let n = min(RATE - self.pos, input.len());
+            P::absorb_bytes(&mut self.state, self.pos, &input[..n]);
+            self.pos += n;
+            input = &input[n..];
+        }
+    }
+
+    /// Pad the absorbed input with the given domain `suffix` and return the
+    /// reader for the output.
+    pub fn finalize_xof(mut self, suffix: u8) -> SpongeReader<P, RATE> {
+        self.pad(suffix);
+        SpongeReader {
+            state: mem::take(&mut self.state),
+            pos: 0,
+            permutation: PhantomData,
+        }
+    }
+
+    /// Pad the absorbed input with the given domain `suffix` and permute the
+    /// state for the first block of output.
+    fn pad(&mut self, suffix: u8) {
+        if self.pos == RATE {
+            P::permute(&mut self.state);
+            self.pos = 0;
+        }
+
+        P::absorb_bytes(&mut self.state, self.pos, &[suffix]);
+        if ((suffix & 0x80) != 0) && (self.pos == (RATE - 1)) {
+            P::permute(&mut self.state);
+        }
+        P::absorb_bytes(&mut self.state, RATE - 1, &[0x80]);
+        P::permute(&mut self.state);
+    }
+}
+
+impl<const ROUNDS: usize, const RATE: usize, const CAP: usize>
+    Sponge<KeccakP1600<ROUNDS>, RATE, CAP>
+{
+    /// Pad the absorbed input with the given domain `suffix` and switch to
+    /// the squeezing phase.
+    pub(crate) fn finalize(mut self, suffix: u8) -> Squeezer {
+        self.pad(suffix);
+        Squeezer {
+            state: mem::take(&mut self.state),
+            rate: RATE,
+            rounds: ROUNDS,
+            pos: 0,
+        }
+    }
+}
+
+impl<P: Permutation, const RATE: usize, const CAP: usize> Clone for Sponge<P, RATE, CAP> {
+    fn clone(&self) -> Self {
+        Self {
+            state: self.state.clone(),
+            pos: self.pos,
+        }
+    }
+}
+
+impl<P: Permutation, const RATE: usize, const CAP: usize> Default for Sponge<P, RATE, CAP> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<P: Permutation, const RATE: usize, const CAP: usize> fmt::Debug for Sponge<P, RATE, CAP> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("Sponge { ... }")
+    }
+}
+
+#[cfg(feature = "zeroize")]
+impl<P: Permutation, const RATE: usize, const CAP: usize> Drop for Sponge<P, RATE, CAP> {
+    fn drop(&mut self) {
+        self.state.zeroize();
+    }
+}
+
+#[cfg(feature = "zeroize")]
+impl<P: Permutation, const RATE: usize, const CAP: usize> ZeroizeOnDrop for Sponge<P, RATE, CAP> {}
+
+/// Extensible output reader of a [`Sponge`], which squeezes it on demand.
+///
+/// Cloning a [`SpongeReader`] forks the output stream.
+pub struct SpongeReader<P: Permutation, const RATE: usize> {
+    /// State of the permutation after absorption
+    state: P::State,
+
+    /// Number of bytes of the current block already returned
+    pos: usize,
+
+    /// The permutation only appears in the type of the state
+    permutation: PhantomData<P>,
+}
+
+impl<P: Permutation, const RATE: usize> SpongeReader<P, RATE> {
+    /// Fill `output` with the next bytes of output.
+    pub fn read(&mut self, output: &mut [u8]) {
+        let mut offset = 0;
+        while offset < output.len() {
+            if self.pos == RATE {
+                P::permute(&mut self.state);
+                self.pos = 0;
+            }
+
+            let n = min(RATE - self.pos, output.len() - offset);
+            P::squeeze_bytes(&self.state, self.pos, &mut output[offset..offset + n]);
+            self.pos += n;
+            offset += n;
+        }
+    }
+}
+
+impl<P: Permutation, const RATE: usize> Clone for SpongeReader<P, RATE> {
+    fn clone(&self) -> Self {
+        Self {
+            state: self.state.clone(),
+            pos: self.pos,
+            permutation: PhantomData,
+        }
+    }
+}
+
+#[cfg(feature = "digest")]
+impl<P: Permutation, const RATE: usize> XofReader for SpongeReader<P, RATE> {
+    fn read(&mut self, buffer: &mut [u8]) {
+        SpongeReader::read(self, buffer);
+    }
+}
+
+impl<P: Permutation, const RATE: usize> fmt::Debug for SpongeReader<P, RATE> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("SpongeReader { ... }")
+    }
+}
+
+#[cfg(feature = "zeroize")]
+impl<P: Permutation, const RATE: usize> Drop for SpongeReader<P, RATE> {
+    fn drop(&mut self) {
+        self.state.zeroize();
+    }
+}
+
+#[cfg(feature = "zeroize")]
+impl<P: Permutation, const RATE: usize> ZeroizeOnDrop for SpongeReader<P, RATE> {}
+
+/// Hash `N` inputs of the same length with sponges of the given `rate` in
+/// bytes and `rounds`, padded with the domain `suffix`, filling each output
+/// with at most `rate` bytes.
+///
+/// All states are permuted together with `permute`, which must apply
+/// Keccak-p[1600, `rounds`] to each of them.
+pub(crate) fn hash_many<'a, const N: usize>(
+    rate: usize,
+    rounds: usize,
+    inputs: [&[u8]; N],
+    suffix: u8,
+    outputs: impl IntoIterator<Item = &'a mut [u8]>,
+    permute: fn(&mut [[u64; 25]; N], usize),
+) {
+    debug_assert!(inputs.iter().all(|input| input.len() == inputs[0].len()));
+
+    let mut states = [[0u64; 25]; N];
+    let mut offset = 0;
+    let pos = loop {
+        let n = min(rate, inputs[0].len() - offset);
+        for (state, input) in states.iter_mut().zip(&inputs) {
+            absorb_bytes(state, 0, &input[offset..offset + n]);
+        }
+        offset += n;
+        if n < rate {
+            break n;
+        }
+        permute(&mut states, rounds);
+    };
+
+    for state in states.iter_mut() {
+        absorb_bytes(state, pos, &[suffix]);
+    }
+    if ((suffix & 0x80) != 0) && (pos == (rate - 1)) {
+        permute(&mut states, rounds);
+    }
+    for state in states.iter_mut() {
+        absorb_bytes(state, rate - 1, &[0x80]);
+    }
+    permute(&mut states, rounds);
+
+    for (state, output) in states.iter().zip(outputs) {
+        debug_assert!(output.len() <= rate);
+        squeeze_bytes(state, 0, output);
+    }
+    #[cfg(feature = "zeroize")]
+    states.zeroize();
+}
+
+#[cfg(feature = "serde")]
+impl<const ROUNDS: usize, const RATE: usize, const CAP: usize>
+    Sponge<KeccakP1600<ROUNDS>, RATE, CAP>
+{
+    /// Bytes of the Keccak state and number of bytes absorbed into the
+    /// current block.
+    pub(crate) fn raw(&self) -> ([u8; 200], usize) {
+        let mut state = [0u8; 200];
+        squeeze_bytes(&self.state, 0, &mut state);
+        (state, self.pos)
+    }
+
+    /// Restore a sponge from the output of [`Sponge::raw`], or return `None`
+    /// if `pos` is outside of the block.
+    pub(crate) fn from_raw(state: [u8; 200], pos: usize) -> Option<Self> {
+        if pos > RATE {
+            return None;
+        }
+        let mut sponge = Self::new();
+        absorb_bytes(&mut sponge.state, 0, &state);
+        sponge.pos = pos;
+        Some(sponge)
+    }
+}
+
+/// Extensible output reader which squeezes a Keccak sponge on demand.
+///
+/// Output can be read in pieces of any size: the Keccak permutation is only
+/// applied once the current block of output has been consumed. Cloning a
+/// [`Squeezer`] forks the output stream.
+#[derive(Clone)]
+pub struct Squeezer {
+    /// Keccak sponge state after absorption
+    state: [u64; 25],
+
+    /// Rate of the sponge in bytes
+    rate: usize,
+
+    /// Number of rounds of the Keccak-p[1600] permutation
+    rounds: usize,
+
+    /// Number of bytes of the current block already returned
+    pos: usize,
+}
+
+impl Squeezer {
+    /// Fill `output` with the next bytes of output.
+    pub fn read(&mut self, output: &mut [u8]) {
+        let mut offset = 0;
+        while offset < output.len() {
+            if self.pos == self.rate {
+                keccak_p(&mut self.state, self.rounds);
+                self.pos = 0;
+            }
+
+            let n = min(self.rate - self.pos, output.len() - offset);
+            squeeze_bytes(&self.state, self.pos, &mut output[offset..offset + n]);
+            self.pos += n;
+            offset += n;
+        }
+    }
+
+    /// Split the rest of the output into `n` independent streams, which can
+    /// be read in parallel, e.g. to derive many keys at once.
+    ///
+    /// This is an extension of this crate and not part of KangarooTwelve or
+    /// TurboSHAKE: stream `i` discards the rest of the current block, XORs
+    /// `right_encode(i)` into the start of the rate and `0x80` into its last
+    /// byte, and applies the permutation. The marker byte keeps the streams
+    /// distinct from the output of the [`Squeezer`] itself, which is left
+    /// unchanged, and `right_encode` keeps them distinct from each other.
+    ///
+    /// ```
+    /// let reader = k12::KangarooTwelve::new().chain(b"secret").finalize_xof();
+    /// let keys: Vec<[u8; 32]> = reader
+    ///     .fork(4)
+    ///     .into_iter()
+    ///     .map(|mut stream| {
+    ///         let mut key = [0u8; 32];
+    ///         stream.read(&mut key);
+    ///         key
+    ///     })
+    ///     .collect();
+    /// assert_ne!(keys[0], keys[1]);
+    /// ```
+    #[cfg(feature = "alloc")]
+    pub fn fork(&self, n: usize) -> Vec<Squeezer> {
+        (0..n)
+            .map(|i| {
+                let mut stream = self.clone();
+                absorb_bytes(&mut stream.state, 0, right_encode(i).as_bytes());
+                absorb_bytes(&mut stream.state, stream.rate - 1, &[0x80]);
+                keccak_p(&mut stream.state, stream.rounds);
+                stream.pos = 0;
+                stream
+            })
+            .collect()
+    }
+}
+
+#[cfg(feature = "bytes")]
+impl Squeezer {
+    /// Append the next `len` bytes of output to `dst`, one block at a time.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `dst` does not have room for `len` more bytes.
+    pub fn read_into_buf(&mut self, dst: &mut impl bytes::BufMut, mut len: usize) {
+        let mut block = [0u8; 200];
+        while len > 0 {
+            if self.pos == self.rate {
+                keccak_p(&mut self.state, self.rounds);
+                self.pos = 0;
+            }
+
+            let n = min(self.rate - self.pos, len);
+            squeeze_bytes(&self.state, self.pos, &mut block[..n]);
+            dst.put_slice(&block[..n]);
+            self.pos += n;
+            len -= n;
+        }
+        #[cfg(feature = "zeroize")]
+        block.zeroize();
+    }
+}
+
+#[cfg(feature = "std")]
+impl Squeezer {
+    /// Write the next `len` bytes of output to `writer`, one block at a time.
+    ///
+    /// If writing fails, the output of the block being written is skipped.
+    pub fn output_into(
+        &mut self,
+        writer: &mut impl std::io::Write,
+        mut len: usize,
+    ) -> std::io::Result<()> {
+        let mut block = [0u8; 200];
+        let result = loop {
+            if len == 0 {
+                break Ok(());
+            }
+            if self.pos == self.rate {
+                keccak_p(&mut self.state, self.rounds);
+                self.pos = 0;
+            }
+
+            let n = min(self.rate - self.pos, len);
+            squeeze_bytes(&self.state, self.pos, &mut block[..n]);
+            self.pos += n;
+            len -= n;
+            if let Err(err) = writer.write_all(&block[..n]) {
+                break Err(err);
+            }
+        };
+        #[cfg(feature = "zeroize")]
+        block.zeroize();
+        result
+    }
+}
+
+#[cfg(feature = "digest")]
+impl XofReader for Squeezer {
+    fn read(&mut self, buffer: &mut [u8]) {
+        Squeezer::read(self, buffer);
+    }
+}
+
+/// Output never runs out, so reads always fill the whole buffer; use
+/// [`std::io::Read::take`] to limit the output when copying it into a writer.
+#[cfg(feature = "std")]
+impl std::io::Read for Squeezer {
+    fn read(&mut self, buf: &mut [u8]) -> std::io::Result<usize> {
+        Squeezer::read(self, buf);
+        Ok(buf.len())
+    }
+}
+
+#[cfg(feature = "zeroize")]
+impl Drop for Squeezer {
+    fn drop(&mut self) {
+        self.state.zeroize();
+    }
+}
+
+#[cfg(feature = "zeroize")]
+impl ZeroizeOnDrop for Squeezer {}
+
+impl fmt::Debug for Squeezer {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("Squeezer { ... }")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn hash_many_matches_sponge() {
+        use crate::platform::{keccak_p_x2, keccak_p_x4, keccak_p_x8};
+
+        let mut input = [0u8; 700];
+        for (i, byte) in input.iter_mut().enumerate() {
+            *byte = (i % 251) as u8;
+        }
+        for &len in &[0, 1, 167, 168, 169, 336, 690] {
+            let inputs: [&[u8]; 8] = [
+                &input[..len],
+                &input[700 - len..],
+                &input[1..len + 1],
+                &input[2..len + 2],
+                &input[3..len + 3],
+                &input[4..len + 4],
+                &input[5..len + 5],
+                &input[6..len + 6],
+            ];
+            let mut expected = [[0u8; 64]; 8];
+            for (input, output) in inputs.iter().zip(expected.iter_mut()) {
+                let mut sponge = Sponge::<Keccak1600x12, 168, 32>::new();
+                sponge.absorb(input);
+                sponge.finalize(0x0B).read(output);
+            }
+
+            let mut outputs = [[0u8; 64]; 2];
+            hash_many(
+                168,
+                12,
+                [inputs[0], inputs[1]],
+                0x0B,
+                outputs.iter_mut().map(|output| &mut output[..]),
+                keccak_p_x2,
+            );
+            assert_eq!(outputs[..], expected[..2], "length {}", len);
+
+            let mut outputs = [[0u8; 64]; 4];
+            hash_many(
+                168,
+                12,
+                [inputs[0], inputs[1], inputs[2], inputs[3]],
+                0x0B,
+                outputs.iter_mut().map(|output| &mut output[..]),
+                keccak_p_x4,
+            );
+            assert_eq!(outputs[..], expected[..4], "length {}", len);
+
+            let mut outputs = [[0u8; 64]; 8];
+            hash_many(
+                168,
+                12,
+                inputs,
+                0x0B,
+                outputs.iter_mut().map(|output| &mut output[..]),
+                keccak_p_x8,
+            );
+            assert_eq!(outputs[..], expected[..], "length {}", len);
+        }
+    }
+}
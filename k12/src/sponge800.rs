@@ -0,0 +1,93 @@
+//! Sponge over the Keccak-p[800, 12] permutation.
+
+use crate::{
+    sponge::{KeccakP800, Sponge},
+    Error,
+};
+use core::fmt;
+#[cfg(feature = "zeroize")]
+use zeroize::ZeroizeOnDrop;
+
+/// Rate of the sponge in bytes.
+const RATE: usize = 544 / 8;
+
+/// Capacity of the sponge in bytes.
+const CAPACITY: usize = 256 / 8;
+
+/// Domain separation byte used when none is given, as for TurboSHAKE.
+const DEFAULT_DOMAIN: u8 = 0x1F;
+
+/// Extendable-output function on the Keccak-p[800, 12] permutation with a
+/// capacity of 256 bits, for 32-bit microcontrollers.
+///
+/// The padding is the one of TurboSHAKE: the input is followed by the domain
+/// separation byte `D` and the last byte of the block is XORed with `0x80`.
+/// The 100-byte state only needs 32-bit arithmetic.
+#[derive(Clone)]
+pub struct Sponge800 {
+    /// Sponge absorbing the input
+    sponge: Sponge<KeccakP800<12>, RATE, CAPACITY>,
+
+    /// Domain separation byte `D`
+    domain: u8,
+}
+
+impl Sponge800 {
+    /// Create a new [`Sponge800`] instance with the default domain
+    /// separation byte `0x1F`.
+    pub fn new() -> Self {
+        Self {
+            sponge: Sponge::new(),
+            domain: DEFAULT_DOMAIN,
+        }
+    }
+
+    /// Create a new [`Sponge800`] instance with the given domain separation
+    /// byte.
+    ///
+    /// Returns [`Error::InvalidDomainSeparator`] unless `domain` is in the
+    /// range `0x01..=0x7F`.
+    pub fn new_with_domain(domain: u8) -> Result<Self, Error> {
+        if !(0x01..=0x7F).contains(&domain) {
+            return Err(Error::InvalidDomainSeparator);
+        }
+
+        Ok(Self {
+            sponge: Sponge::new(),
+            domain,
+        })
+    }
+
+    /// Input data into the hash function.
+    pub fn update(&mut self, bytes: impl AsRef<[u8]>) {
+        self.sponge.absorb(bytes.as_ref());
+    }
+
+    /// Input data into the hash function, returning the updated hasher.
+    pub fn chain(mut self, bytes: impl AsRef<[u8]>) -> Self {
+        self.update(bytes);
+        self
+    }
+
+    /// Fill `output` with `output.len()` bytes of output and consume the
+    /// hasher.
+    pub fn finalize_into(self, output: &mut [u8]) {
+        self.sponge.finalize_xof(self.domain).read(output);
+    }
+}
+
+impl Default for Sponge800 {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl fmt::Debug for Sponge800 {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("Sponge800 { ... }")
+    }
+}
+
+// The sponge zeroizes its state when dropped
+#[cfg(feature = "zeroize")]
+impl ZeroizeOnDrop for Sponge800 {}
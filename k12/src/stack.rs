@@ -0,0 +1,122 @@
+//! KangarooTwelve with a fixed-size input buffer.
+
+use crate::{tree, Error, Squeezer};
+use core::fmt;
+#[cfg(feature = "digest")]
+use digest::{ExtendableOutputDirty, Reset};
+#[cfg(feature = "zeroize")]
+use zeroize::{Zeroize, ZeroizeOnDrop};
+
+/// The KangarooTwelve extendable-output function (XOF) buffering its input
+/// in a fixed-size array of `CAP` bytes, for use without an allocator.
+///
+/// The customization string is stored in the same buffer as the input, so
+/// `CAP` bounds the combined length of both.
+#[derive(Clone)]
+pub struct StackKangarooTwelve<const CAP: usize> {
+    /// Customization string followed by the input to be processed
+    buffer: [u8; CAP],
+
+    /// Length of the customization string at the start of `buffer`
+    customization_len: usize,
+
+    /// Total number of bytes stored in `buffer`
+    len: usize,
+}
+
+impl<const CAP: usize> StackKangarooTwelve<CAP> {
+    /// Create a new [`StackKangarooTwelve`] instance.
+    pub fn new() -> Self {
+        Self {
+            buffer: [0u8; CAP],
+            customization_len: 0,
+            len: 0,
+        }
+    }
+
+    /// Create a new [`StackKangarooTwelve`] instance with the given
+    /// customization.
+    ///
+    /// Returns [`Error::CapacityExceeded`] if the customization string is
+    /// longer than `CAP`.
+    pub fn new_with_customization(customization: impl AsRef<[u8]>) -> Result<Self, Error> {
+        let mut k12 = Self::new();
+        k12.update(customization)?;
+        k12.customization_len = k12.len;
+        Ok(k12)
+    }
+
+    /// Input data into the hash function.
+    ///
+    /// Returns [`Error::CapacityExceeded`] and leaves the state unchanged if
+    /// the data does not fit in the remaining capacity.
+    pub fn update(&mut self, bytes: impl AsRef<[u8]>) -> Result<(), Error> {
+        let bytes = bytes.as_ref();
+        let end = self
+            .len
+            .checked_add(bytes.len())
+            .filter(|&end| end <= CAP)
+            .ok_or(Error::CapacityExceeded)?;
+
+        self.buffer[self.len..end].copy_from_slice(bytes);
+        self.len = end;
+        Ok(())
+    }
+
+    /// Input data into the hash function, returning the updated hasher.
+    pub fn chain(mut self, bytes: impl AsRef<[u8]>) -> Result<Self, Error> {
+        self.update(bytes)?;
+        Ok(self)
+    }
+
+    /// Retrieve the [`Squeezer`] for the output and consume the hasher.
+    pub fn finalize_xof(self) -> Squeezer {
+        self.squeezer()
+    }
+
+    /// Compute the tree hash of the input buffered so far.
+    fn squeezer(&self) -> Squeezer {
+        let (customization, message) = self.buffer[..self.len].split_at(self.customization_len);
+        tree::finalize(tree::K12, message, customization)
+    }
+}
+
+impl<const CAP: usize> Default for StackKangarooTwelve<CAP> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(feature = "digest")]
+impl<const CAP: usize> ExtendableOutputDirty for StackKangarooTwelve<CAP> {
+    type Reader = Squeezer;
+
+    fn finalize_xof_dirty(&mut self) -> Self::Reader {
+        self.squeezer()
+    }
+}
+
+#[cfg(feature = "digest")]
+impl<const CAP: usize> Reset for StackKangarooTwelve<CAP> {
+    fn reset(&mut self) {
+        #[cfg(feature = "zeroize")]
+        self.buffer[self.customization_len..self.len].zeroize();
+        self.len = self.customization_len;
+    }
+}
+
+#[cfg(feature = "zeroize")]
+impl<const CAP: usize> Drop for StackKangarooTwelve<CAP> {
+    fn drop(&mut self) {
+        self.buffer[..self.len].zeroize();
+    }
+}
+
+#[cfg(feature = "zeroize")]
+impl<const CAP: usize> ZeroizeOnDrop for StackKangarooTwelve<CAP> {}
+
+impl<const CAP: usize> fmt::Debug for StackKangarooTwelve<CAP> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("StackKangarooTwelve { ... }")
+    }
+}
@@ -0,0 +1,103 @@
+//! Serialization of the [`KangarooTwelve`] state.
+
+use crate::{
+    tree::{self, K12Tree},
+    KangarooTwelve,
+};
+use alloc::vec::Vec;
+use core::convert::TryInto;
+use serde::{de, Deserialize, Deserializer, Serialize, Serializer};
+
+/// Version of the serialized state format.
+///
+/// Bump this whenever the layout of the state changes, so that older states
+/// are rejected instead of being misinterpreted.
+const VERSION: u8 = 1;
+
+#[derive(Serialize)]
+struct StateRef<'a> {
+    version: u8,
+    block_size: usize,
+    final_node: &'a [u8],
+    final_node_pos: usize,
+    leaf: &'a [u8],
+    leaf_pos: usize,
+    chunk: u64,
+    chunk_len: usize,
+    parallelism: Option<usize>,
+    customization: &'a [u8],
+}
+
+#[derive(Deserialize)]
+struct State {
+    version: u8,
+    block_size: usize,
+    final_node: Vec<u8>,
+    final_node_pos: usize,
+    leaf: Vec<u8>,
+    leaf_pos: usize,
+    chunk: u64,
+    chunk_len: usize,
+    /// Absent in states written before it was added, which are unbounded
+    #[serde(default)]
+    parallelism: Option<usize>,
+    customization: Vec<u8>,
+}
+
+impl Serialize for KangarooTwelve {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let raw = self.tree.raw();
+        StateRef {
+            version: VERSION,
+            block_size: raw.chunk_size,
+            final_node: &raw.final_node.0,
+            final_node_pos: raw.final_node.1,
+            leaf: &raw.leaf.0,
+            leaf_pos: raw.leaf.1,
+            chunk: raw.chunk,
+            chunk_len: raw.chunk_len,
+            // `None` rather than `usize::MAX`, which depends on the target
+            parallelism: Some(raw.parallelism).filter(|&p| p != usize::MAX),
+            customization: &self.customization,
+        }
+        .serialize(serializer)
+    }
+}
+
+impl<'de> Deserialize<'de> for KangarooTwelve {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let state = State::deserialize(deserializer)?;
+        if state.version != VERSION {
+            return Err(de::Error::invalid_value(
+                de::Unexpected::Unsigned(state.version.into()),
+                &"a supported KangarooTwelve state version",
+            ));
+        }
+
+        let sponge = |bytes: &[u8]| -> Result<[u8; 200], D::Error> {
+            bytes
+                .try_into()
+                .map_err(|_| de::Error::invalid_length(bytes.len(), &"a 200-byte Keccak state"))
+        };
+
+        let params = tree::K12
+            .with_chunk_size(state.block_size)
+            .ok_or_else(|| de::Error::custom("KangarooTwelve block size out of range"))?;
+
+        // Any Keccak state is valid, only the positions need to be checked
+        let tree = K12Tree::from_raw(
+            params,
+            (sponge(&state.final_node)?, state.final_node_pos),
+            (sponge(&state.leaf)?, state.leaf_pos),
+            state.chunk,
+            state.chunk_len,
+            state.parallelism.unwrap_or(usize::MAX),
+        )
+        .ok_or_else(|| de::Error::custom("KangarooTwelve state position out of range"))?;
+
+        Ok(Self {
+            tree,
+            customization: state.customization,
+        })
+    }
+}
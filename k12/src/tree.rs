@@ -0,0 +1,495 @@
+//! Tree hashing mode shared by KangarooTwelve and MarsupilamiFourteen.
+
+use crate::{
+    encoding::{right_encode, right_encode_u128},
+    platform,
+    sponge::{self, KeccakP1600, Sponge, Squeezer},
+};
+use core::{cmp::min, mem};
+#[cfg(feature = "zeroize")]
+use zeroize::Zeroize;
+#[cfg(feature = "rayon")]
+use {alloc::vec, rayon::prelude::*};
+
+/// Default size of the chunks `S_i` the input string is cut into (B = 8192).
+pub(crate) const CHUNK_SIZE: usize = 8192;
+
+/// Largest size of the chaining values computed for each leaf.
+const MAX_CV_SIZE: usize = 512 / 8;
+
+/// Parameters of an instance of the tree hashing mode with `ROUNDS` rounds
+/// of the Keccak-p[1600] permutation, a rate of `RATE` bytes and a capacity
+/// of `CAP` bytes, which is also the size of the chaining values (c / 8).
+#[derive(Clone, Copy)]
+pub(crate) struct Params<const ROUNDS: usize, const RATE: usize, const CAP: usize> {
+    /// Size of the chunks `S_i` the input string is cut into (B)
+    chunk_size: usize,
+}
+
+impl<const ROUNDS: usize, const RATE: usize, const CAP: usize> Params<ROUNDS, RATE, CAP> {
+    /// Use chunks of `chunk_size` bytes, or return `None` if that is smaller
+    /// than the rate.
+    pub(crate) fn with_chunk_size(self, chunk_size: usize) -> Option<Self> {
+        if chunk_size < RATE {
+            return None;
+        }
+        Some(Self { chunk_size })
+    }
+
+    /// Size of the chunks.
+    pub(crate) fn chunk_size(&self) -> usize {
+        self.chunk_size
+    }
+}
+
+/// Sponge of the nodes of the tree.
+type NodeSponge<const ROUNDS: usize, const RATE: usize, const CAP: usize> =
+    Sponge<KeccakP1600<ROUNDS>, RATE, CAP>;
+
+/// Parameters of KangarooTwelve.
+pub(crate) type K12Params = Params<12, { 1344 / 8 }, { 256 / 8 }>;
+
+/// Tree of KangarooTwelve.
+pub(crate) type K12Tree = Tree<12, { 1344 / 8 }, { 256 / 8 }>;
+
+/// KangarooTwelve: c = 256 bits, 12 rounds.
+pub(crate) const K12: K12Params = Params {
+    chunk_size: CHUNK_SIZE,
+};
+
+/// Tree of MarsupilamiFourteen.
+#[cfg(feature = "alloc")]
+pub(crate) type M14Tree = Tree<14, { 1088 / 8 }, { 512 / 8 }>;
+
+/// MarsupilamiFourteen: c = 512 bits, 14 rounds.
+#[cfg(feature = "alloc")]
+pub(crate) const M14: Params<14, { 1088 / 8 }, { 512 / 8 }> = Params {
+    chunk_size: CHUNK_SIZE,
+};
+
+/// Incremental computation of the tree hash over the input string
+/// `S = M || C || right_encode(|C|)`.
+///
+/// The final node absorbs `S_0` directly, followed by the chaining value of
+/// every other chunk as soon as that chunk is complete, so no part of `S`
+/// needs to be kept around.
+#[derive(Clone)]
+pub(crate) struct Tree<const ROUNDS: usize, const RATE: usize, const CAP: usize> {
+    /// Parameters of the instance
+    params: Params<ROUNDS, RATE, CAP>,
+
+    /// Final node: `S_0 || 0x03 0x00^7 || CV_1 || ... || CV_{n-1}`
+    final_node: NodeSponge<ROUNDS, RATE, CAP>,
+
+    /// Leaf node for the chunk `S_i` currently being absorbed (`i > 0`)
+    leaf: NodeSponge<ROUNDS, RATE, CAP>,
+
+    /// Index `i` of the chunk currently being absorbed, which cannot
+    /// overflow even where a `usize` could
+    chunk: u64,
+
+    /// Number of bytes absorbed into the current chunk
+    chunk_len: usize,
+
+    /// Maximum number of groups of leaves hashed in parallel
+    #[cfg(feature = "rayon")]
+    parallelism: usize,
+}
+
+impl<const ROUNDS: usize, const RATE: usize, const CAP: usize> Tree<ROUNDS, RATE, CAP> {
+    /// Create an empty tree with the given parameters.
+    pub(crate) fn new(params: Params<ROUNDS, RATE, CAP>) -> Self {
+        Self {
+            params,
+            final_node: Sponge::new(),
+            leaf: Sponge::new(),
+            chunk: 0,
+            chunk_len: 0,
+            #[cfg(feature = "rayon")]
+            parallelism: usize::MAX,
+        }
+    }
+
+    /// Parameters of the tree.
+    #[cfg(feature = "alloc")]
+    pub(crate) fn params(&self) -> Params<ROUNDS, RATE, CAP> {
+        self.params
+    }
+
+    /// Hash at most `parallelism` groups of leaves in parallel.
+    #[cfg(feature = "rayon")]
+    pub(crate) fn set_parallelism(&mut self, parallelism: usize) {
+        self.parallelism = parallelism;
+    }
+
+    /// Discard the input absorbed so far, keeping the parameters.
+    #[cfg(feature = "alloc")]
+    pub(crate) fn reset(&mut self) {
+        #[cfg(feature = "rayon")]
+        let parallelism = self.parallelism;
+        *self = Self::new(self.params);
+        #[cfg(feature = "rayon")]
+        self.set_parallelism(parallelism);
+    }
+
+    /// Append `input` to the input string `S`.
+    pub(crate) fn update(&mut self, input: &[u8]) {
+        self.update_with_progress(input, |_| {});
+    }
+
+    /// Append `input` to the input string `S`, calling `progress` with the
+    /// number of bytes of `S` absorbed so far whenever a chunk is complete.
+    #[cfg_attr(not(feature = "alloc"), allow(dead_code))]
+    pub(crate) fn update_with_progress(&mut self, mut input: &[u8], mut progress: impl FnMut(u64)) {
+        let chunk_size = self.params.chunk_size as u64;
+        while !input.is_empty() {
+            let complete = self.absorbed() / chunk_size;
+            input = self.absorb_step(input);
+            for chunk in complete + 1..=self.absorbed() / chunk_size {
+                progress(chunk * chunk_size);
+            }
+        }
+    }
+
+    /// Number of bytes of `S` absorbed so far.
+    pub(crate) fn absorbed(&self) -> u64 {
+        self.chunk * self.params.chunk_size as u64 + self.chunk_len as u64
+    }
+
+    /// Absorb a part of `input`, at most up to the end of the current chunk
+    /// or of a group of leaves, and return the rest.
+    fn absorb_step<'a>(&mut self, input: &'a [u8]) -> &'a [u8] {
+        let chunk_size = self.params.chunk_size;
+        if self.chunk_len == chunk_size {
+            self.next_chunk();
+        }
+
+        // Leaves which are complete within `input` are independent of each
+        // other, so their chaining values can be computed in parallel, each
+        // job hashing a group of as many leaves as the backend can permute at
+        // once. The chunk containing the end of `input` is left open.
+        #[cfg(feature = "rayon")]
+        {
+            if self.chunk > 0 && self.chunk_len == 0 {
+                let rest = if platform::has_x8() {
+                    self.par_absorb_leaves(input, platform::keccak_p_x8)
+                } else if platform::has_x4() {
+                    self.par_absorb_leaves(input, platform::keccak_p_x4)
+                } else if platform::HAS_X2 {
+                    self.par_absorb_leaves(input, platform::keccak_p_x2)
+                } else {
+                    self.par_absorb_leaves(input, keccak_p_x1)
+                };
+                if let Some(rest) = rest {
+                    return rest;
+                }
+            }
+        }
+
+        // Otherwise groups of complete leaves are hashed together if the
+        // backend can apply the permutation to several states at once
+        if self.chunk > 0 && self.chunk_len == 0 {
+            if platform::has_x8() && input.len() > 8 * chunk_size {
+                return self.absorb_leaves(input, platform::keccak_p_x8);
+            }
+            if platform::has_x4() && input.len() > 4 * chunk_size {
+                return self.absorb_leaves(input, platform::keccak_p_x4);
+            }
+            if platform::HAS_X2 && input.len() > 2 * chunk_size {
+                return self.absorb_leaves(input, platform::keccak_p_x2);
+            }
+        }
+
+        let n = min(chunk_size - self.chunk_len, input.len());
+        if self.chunk == 0 {
+            self.final_node.absorb(&input[..n]);
+        } else {
+            self.leaf.absorb(&input[..n]);
+        }
+        self.chunk_len += n;
+        &input[n..]
+    }
+
+    /// Finish the tree and return the output of the final node.
+    pub(crate) fn finalize(mut self) -> Squeezer {
+        if self.chunk == 0 {
+            // === Process the tree with only a final node ===
+            return self.final_node.finalize(0x07);
+        }
+
+        // === Process the tree with kangaroo hopping ===
+        self.finish_leaf();
+
+        self.final_node
+            .absorb(right_encode_u128(self.chunk.into()).as_bytes());
+        self.final_node.absorb(b"\xFF\xFF");
+        self.final_node.finalize(0x06)
+    }
+
+    /// Append the customization string as `C || right_encode(|C|)` and
+    /// return the output of the final node.
+    pub(crate) fn finalize_with_customization(mut self, customization: &[u8]) -> Squeezer {
+        self.update(customization);
+        self.update(right_encode(customization.len()).as_bytes());
+        self.finalize()
+    }
+
+    /// Close the current (complete) chunk and move on to the next.
+    fn next_chunk(&mut self) {
+        if self.chunk == 0 {
+            self.final_node.absorb(&[3, 0, 0, 0, 0, 0, 0, 0]);
+        } else {
+            self.finish_leaf();
+        }
+        self.chunk += 1;
+        self.chunk_len = 0;
+    }
+
+    /// Hash the first `N` chunks of `input` as complete leaves, permuting
+    /// their states together with `permute`, and return the rest of `input`.
+    ///
+    /// The current leaf must be empty.
+    fn absorb_leaves<'a, const N: usize>(
+        &mut self,
+        input: &'a [u8],
+        permute: fn(&mut [[u64; 25]; N], usize),
+    ) -> &'a [u8] {
+        let chunk_size = self.params.chunk_size;
+        let (chunks, rest) = input.split_at(N * chunk_size);
+        let mut cvs = [[0u8; MAX_CV_SIZE]; N];
+        leaf_cvs::<ROUNDS, RATE, CAP, N>(chunks, chunk_size, &mut cvs, permute);
+        for cv in &cvs {
+            self.final_node.absorb(&cv[..CAP]);
+        }
+        #[cfg(feature = "zeroize")]
+        cvs.zeroize();
+        self.chunk += N as u64;
+        rest
+    }
+
+    /// Hash the complete leaves at the start of `input` in parallel, in at
+    /// most `parallelism` jobs of `N` leaves permuted together with
+    /// `permute`, and return the rest of `input`. A last group of fewer than
+    /// `N` leaves is hashed one leaf at a time.
+    ///
+    /// Returns `None` without absorbing anything if there are not enough
+    /// leaves for two jobs. The current leaf must be empty.
+    #[cfg(feature = "rayon")]
+    fn par_absorb_leaves<'a, const N: usize>(
+        &mut self,
+        input: &'a [u8],
+        permute: fn(&mut [[u64; 25]; N], usize),
+    ) -> Option<&'a [u8]> {
+        let chunk_size = self.params.chunk_size;
+        let leaves = min(
+            (input.len() - 1) / chunk_size,
+            self.parallelism.saturating_mul(N),
+        );
+        if leaves <= N {
+            return None;
+        }
+
+        let (chunks, rest) = input.split_at(leaves * chunk_size);
+        let mut cvs = vec![[0u8; MAX_CV_SIZE]; leaves];
+        cvs.par_chunks_mut(N)
+            .zip(chunks.par_chunks(N * chunk_size))
+            .for_each(|(cvs, group)| {
+                if cvs.len() == N {
+                    leaf_cvs::<ROUNDS, RATE, CAP, N>(group, chunk_size, cvs, permute);
+                } else {
+                    for (cv, chunk) in cvs.iter_mut().zip(group.chunks(chunk_size)) {
+                        *cv = leaf_cv::<ROUNDS, RATE, CAP>(chunk);
+                    }
+                }
+            });
+        for cv in &cvs {
+            self.final_node.absorb(&cv[..CAP]);
+        }
+        #[cfg(feature = "zeroize")]
+        cvs.zeroize();
+        self.chunk += leaves as u64;
+        Some(rest)
+    }
+
+    /// Absorb the chaining value of the current leaf into the final node.
+    fn finish_leaf(&mut self) {
+        let leaf = mem::take(&mut self.leaf);
+        let cv = &mut [0u8; MAX_CV_SIZE][..CAP];
+        leaf.finalize(0x0B).read(cv);
+        self.final_node.absorb(cv);
+        #[cfg(feature = "zeroize")]
+        cv.zeroize();
+    }
+}
+
+/// Raw state of a [`Tree`], for serialization.
+#[cfg(feature = "serde")]
+pub(crate) struct RawTree {
+    /// Size of the chunks
+    pub(crate) chunk_size: usize,
+
+    /// Keccak state and position of the final node
+    pub(crate) final_node: ([u8; 200], usize),
+
+    /// Keccak state and position of the current leaf
+    pub(crate) leaf: ([u8; 200], usize),
+
+    /// Index of the chunk currently being absorbed
+    pub(crate) chunk: u64,
+
+    /// Number of bytes absorbed into the current chunk
+    pub(crate) chunk_len: usize,
+
+    /// Maximum number of groups of leaves hashed in parallel, which is
+    /// unbounded without `rayon`
+    pub(crate) parallelism: usize,
+}
+
+#[cfg(feature = "serde")]
+impl<const ROUNDS: usize, const RATE: usize, const CAP: usize> Tree<ROUNDS, RATE, CAP> {
+    /// Raw state of the tree.
+    pub(crate) fn raw(&self) -> RawTree {
+        RawTree {
+            chunk_size: self.params.chunk_size,
+            final_node: self.final_node.raw(),
+            leaf: self.leaf.raw(),
+            chunk: self.chunk,
+            chunk_len: self.chunk_len,
+            #[cfg(feature = "rayon")]
+            parallelism: self.parallelism,
+            #[cfg(not(feature = "rayon"))]
+            parallelism: usize::MAX,
+        }
+    }
+
+    /// Restore a tree from the parts of a [`RawTree`], or return `None` if
+    /// they are out of range.
+    pub(crate) fn from_raw(
+        params: Params<ROUNDS, RATE, CAP>,
+        final_node: ([u8; 200], usize),
+        leaf: ([u8; 200], usize),
+        chunk: u64,
+        chunk_len: usize,
+        parallelism: usize,
+    ) -> Option<Self> {
+        if chunk_len > params.chunk_size {
+            return None;
+        }
+        #[cfg(not(feature = "rayon"))]
+        let _ = parallelism;
+        Some(Self {
+            params,
+            final_node: Sponge::from_raw(final_node.0, final_node.1)?,
+            leaf: Sponge::from_raw(leaf.0, leaf.1)?,
+            chunk,
+            chunk_len,
+            #[cfg(feature = "rayon")]
+            parallelism: parallelism.max(1),
+        })
+    }
+}
+
+/// Compute the chaining values of the `N` complete chunks `S_i` (`i > 0`) of
+/// `chunk_size` bytes in `chunks` into `cvs`, permuting their states
+/// together with `permute`.
+fn leaf_cvs<const ROUNDS: usize, const RATE: usize, const CAP: usize, const N: usize>(
+    chunks: &[u8],
+    chunk_size: usize,
+    cvs: &mut [[u8; MAX_CV_SIZE]],
+    permute: fn(&mut [[u64; 25]; N], usize),
+) {
+    let mut leaves = [&[][..]; N];
+    for (leaf, chunk) in leaves.iter_mut().zip(chunks.chunks(chunk_size)) {
+        *leaf = chunk;
+    }
+    sponge::hash_many(
+        RATE,
+        ROUNDS,
+        leaves,
+        0x0B,
+        cvs.iter_mut().map(|cv| &mut cv[..CAP]),
+        permute,
+    );
+}
+
+/// Apply Keccak-p[1600, `rounds`] to a single state, for backends which
+/// cannot permute several states at once.
+#[cfg(feature = "rayon")]
+fn keccak_p_x1(states: &mut [[u64; 25]; 1], rounds: usize) {
+    platform::keccak_p(&mut states[0], rounds);
+}
+
+/// Compute the chaining value of a complete chunk `S_i` (`i > 0`).
+#[cfg(feature = "rayon")]
+fn leaf_cv<const ROUNDS: usize, const RATE: usize, const CAP: usize>(
+    chunk: &[u8],
+) -> [u8; MAX_CV_SIZE] {
+    let mut leaf = NodeSponge::<ROUNDS, RATE, CAP>::new();
+    leaf.absorb(chunk);
+    let mut cv = [0u8; MAX_CV_SIZE];
+    leaf.finalize(0x0B).read(&mut cv[..CAP]);
+    cv
+}
+
+/// Compute the tree hash of `message` with the given `customization` string.
+pub(crate) fn finalize<const ROUNDS: usize, const RATE: usize, const CAP: usize>(
+    params: Params<ROUNDS, RATE, CAP>,
+    message: &[u8],
+    customization: &[u8],
+) -> Squeezer {
+    let mut tree = Tree::new(params);
+    tree.update(message);
+    tree.finalize_with_customization(customization)
+}
+
+#[cfg(all(test, feature = "rayon"))]
+mod tests {
+    use super::*;
+    use alloc::vec::Vec;
+
+    /// Hashing groups of leaves in parallel, with and without a partial last
+    /// group, gives the same tree as hashing them sequentially.
+    #[test]
+    fn par_absorb_leaves_matches_sequential() {
+        let params = K12.with_chunk_size(200).unwrap();
+        let input: Vec<u8> = (0..200 * 40).map(|i| (i % 251) as u8).collect();
+        for &leaves in &[9, 12, 15, 30] {
+            let mut expected = K12Tree::new(params);
+            expected.set_parallelism(1);
+            expected.update(&input[..200]);
+            let mut sequential = &input[200..200 * (leaves + 2)];
+            while !sequential.is_empty() {
+                let n = min(200, sequential.len());
+                expected.update(&sequential[..n]);
+                sequential = &sequential[n..];
+            }
+
+            let mut actual = K12Tree::new(params);
+            actual.update(&input[..200]);
+            actual.next_chunk();
+            let rest = actual
+                .par_absorb_leaves(&input[200..200 * (leaves + 2)], platform::keccak_p_x4)
+                .unwrap();
+            assert_eq!(actual.chunk, 1 + leaves as u64);
+            actual.update(rest);
+
+            let (mut a, mut b) = ([0u8; 32], [0u8; 32]);
+            expected.finalize().read(&mut a);
+            actual.finalize().read(&mut b);
+            assert_eq!(a, b, "{} leaves", leaves);
+        }
+
+        // A single group is left to the sequential path
+        let mut tree = K12Tree::new(params);
+        tree.update(&input[..200]);
+        tree.next_chunk();
+        assert!(tree
+            .par_absorb_leaves(&input[..200 * 5], platform::keccak_p_x4)
+            .is_none());
+        tree.set_parallelism(2);
+        assert!(tree
+            .par_absorb_leaves(&input[..200 * 20], platform::keccak_p_x4)
+            .is_some());
+        assert_eq!(tree.chunk, 9);
+    }
+}
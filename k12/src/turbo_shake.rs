@@ -0,0 +1,119 @@
+//! TurboSHAKE extendable-output functions.
+
+use crate::{
+    sponge::{Keccak1600x12, Sponge},
+    Error, Squeezer,
+};
+use core::fmt;
+#[cfg(feature = "digest")]
+use digest::{ExtendableOutputDirty, Reset, Update};
+#[cfg(feature = "zeroize")]
+use zeroize::ZeroizeOnDrop;
+
+/// Domain separation byte used when none is given (D = 0x1F).
+const DEFAULT_DOMAIN: u8 = 0x1F;
+
+/// A TurboSHAKE extendable-output function (XOF), i.e. a sponge over the
+/// Keccak-p[1600,12] permutation with a rate of `RATE` bytes and a capacity
+/// of `CAPACITY` bytes, which add up to the 200 bytes of the state.
+///
+/// Use the [`TurboShake128`] and [`TurboShake256`] instances.
+#[derive(Clone)]
+pub struct TurboShakeCore<const RATE: usize, const CAPACITY: usize> {
+    /// Sponge absorbing the input
+    sponge: Sponge<Keccak1600x12, RATE, CAPACITY>,
+
+    /// Domain separation byte `D`
+    domain: u8,
+}
+
+/// The TurboSHAKE128 extendable-output function (XOF), i.e. a sponge over
+/// the Keccak-p[1600,12] permutation with a capacity of 256 bits.
+pub type TurboShake128 = TurboShakeCore<{ 1344 / 8 }, { 256 / 8 }>;
+
+/// The TurboSHAKE256 extendable-output function (XOF), i.e. a sponge over
+/// the Keccak-p[1600,12] permutation with a capacity of 512 bits.
+pub type TurboShake256 = TurboShakeCore<{ 1088 / 8 }, { 512 / 8 }>;
+
+impl<const RATE: usize, const CAPACITY: usize> TurboShakeCore<RATE, CAPACITY> {
+    /// Create a new instance with the default domain separation byte `0x1F`.
+    pub fn new() -> Self {
+        Self {
+            sponge: Sponge::new(),
+            domain: DEFAULT_DOMAIN,
+        }
+    }
+
+    /// Create a new instance with the given domain separation byte.
+    ///
+    /// Returns [`Error::InvalidDomainSeparator`] unless `domain` is in the
+    /// range `0x01..=0x7F`.
+    pub fn new_with_domain(domain: u8) -> Result<Self, Error> {
+        if !(0x01..=0x7F).contains(&domain) {
+            return Err(Error::InvalidDomainSeparator);
+        }
+
+        Ok(Self {
+            sponge: Sponge::new(),
+            domain,
+        })
+    }
+
+    /// Input data into the hash function.
+    pub fn update(&mut self, bytes: impl AsRef<[u8]>) {
+        self.sponge.absorb(bytes.as_ref());
+    }
+
+    /// Input data into the hash function, returning the updated hasher.
+    pub fn chain(mut self, bytes: impl AsRef<[u8]>) -> Self {
+        self.update(bytes);
+        self
+    }
+
+    /// Retrieve the [`Squeezer`] for the output and consume the hasher.
+    pub fn finalize_xof(self) -> Squeezer {
+        self.sponge.finalize(self.domain)
+    }
+}
+
+impl<const RATE: usize, const CAPACITY: usize> Default for TurboShakeCore<RATE, CAPACITY> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(feature = "digest")]
+impl<const RATE: usize, const CAPACITY: usize> Update for TurboShakeCore<RATE, CAPACITY> {
+    fn update(&mut self, bytes: impl AsRef<[u8]>) {
+        TurboShakeCore::update(self, bytes);
+    }
+}
+
+#[cfg(feature = "digest")]
+impl<const RATE: usize, const CAPACITY: usize> ExtendableOutputDirty
+    for TurboShakeCore<RATE, CAPACITY>
+{
+    type Reader = Squeezer;
+
+    fn finalize_xof_dirty(&mut self) -> Self::Reader {
+        self.sponge.clone().finalize(self.domain)
+    }
+}
+
+#[cfg(feature = "digest")]
+impl<const RATE: usize, const CAPACITY: usize> Reset for TurboShakeCore<RATE, CAPACITY> {
+    fn reset(&mut self) {
+        self.sponge = Sponge::new();
+    }
+}
+
+// The sponge zeroizes its state when dropped
+#[cfg(feature = "zeroize")]
+impl<const RATE: usize, const CAPACITY: usize> ZeroizeOnDrop for TurboShakeCore<RATE, CAPACITY> {}
+
+impl<const RATE: usize, const CAPACITY: usize> fmt::Debug for TurboShakeCore<RATE, CAPACITY> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        // The security level is half the capacity
+        write!(f, "TurboShake{} {{ ... }}", 4 * CAPACITY)
+    }
+}
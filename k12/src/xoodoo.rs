@@ -0,0 +1,233 @@
+//! The Xoodoo\[12\] permutation and the hash mode of Xoodyak on top of it.
+//!
+//! Xoodoo is a 384-bit permutation by the designers of Keccak, specified in:
+//!
+//! <https://csrc.nist.gov/CSRC/media/Projects/lightweight-cryptography/documents/finalist-round/updated-spec-doc/xoodyak-spec-final.pdf>
+
+use crate::{lanes32, Permutation};
+use core::{cmp::min, fmt};
+#[cfg(feature = "zeroize")]
+use zeroize::{Zeroize, ZeroizeOnDrop};
+
+/// Round constants of the 12 rounds of Xoodoo\[12\].
+const RC: [u32; 12] = [
+    0x058, 0x038, 0x3C0, 0x0D0, 0x120, 0x014, 0x060, 0x02C, 0x380, 0x0F0, 0x1A0, 0x012,
+];
+
+/// State of the Xoodoo permutation: three planes of four 32-bit lanes, lane
+/// `x` of plane `y` at index `x + 4 * y`.
+///
+/// The bytes of the state are the little-endian bytes of its lanes in order,
+/// as in the reference implementation.
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+pub struct XoodooState(pub [u32; 12]);
+
+impl XoodooState {
+    /// Create a state from its 48 bytes.
+    pub fn from_bytes(bytes: &[u8; 48]) -> Self {
+        let mut state = Self::default();
+        state.xor_bytes(0, bytes);
+        state
+    }
+
+    /// Bytes of the state.
+    pub fn to_bytes(&self) -> [u8; 48] {
+        let mut bytes = [0u8; 48];
+        self.extract_bytes(0, &mut bytes);
+        bytes
+    }
+
+    /// Apply the Xoodoo\[12\] permutation.
+    pub fn permute(&mut self) {
+        let a = &mut self.0;
+        for &rc in &RC {
+            // θ
+            let mut p = [0u32; 4];
+            for (x, p) in p.iter_mut().enumerate() {
+                *p = a[x] ^ a[x + 4] ^ a[x + 8];
+            }
+            for x in 0..4 {
+                let p = p[(x + 3) % 4];
+                let e = p.rotate_left(5) ^ p.rotate_left(14);
+                a[x] ^= e;
+                a[x + 4] ^= e;
+                a[x + 8] ^= e;
+            }
+
+            // ρ-west
+            a[4..8].rotate_right(1);
+            for lane in &mut a[8..] {
+                *lane = lane.rotate_left(11);
+            }
+
+            // ι
+            a[0] ^= rc;
+
+            // χ
+            for x in 0..4 {
+                let (a0, a1, a2) = (a[x], a[x + 4], a[x + 8]);
+                a[x] ^= !a1 & a2;
+                a[x + 4] ^= !a2 & a0;
+                a[x + 8] ^= !a0 & a1;
+            }
+
+            // ρ-east
+            for lane in &mut a[4..8] {
+                *lane = lane.rotate_left(1);
+            }
+            a[8..].rotate_right(2);
+            for lane in &mut a[8..] {
+                *lane = lane.rotate_left(8);
+            }
+        }
+    }
+
+    /// XOR `input` into the bytes of the state starting at `offset`.
+    fn xor_bytes(&mut self, offset: usize, input: &[u8]) {
+        lanes32::absorb_bytes(&mut self.0, offset, input);
+    }
+
+    /// Copy the bytes of the state starting at `offset` to `output`.
+    fn extract_bytes(&self, offset: usize, output: &mut [u8]) {
+        lanes32::squeeze_bytes(&self.0, offset, output);
+    }
+}
+
+#[cfg(feature = "zeroize")]
+impl Zeroize for XoodooState {
+    fn zeroize(&mut self) {
+        self.0.zeroize();
+    }
+}
+
+/// The Xoodoo\[12\] permutation, for building a [`Sponge`](crate::Sponge) on
+/// its 48-byte state.
+#[derive(Clone, Copy, Debug)]
+pub struct Xoodoo;
+
+impl Permutation for Xoodoo {
+    type State = XoodooState;
+
+    const WIDTH: usize = 48;
+
+    fn permute(state: &mut Self::State) {
+        state.permute();
+    }
+
+    fn absorb_bytes(state: &mut Self::State, offset: usize, input: &[u8]) {
+        state.xor_bytes(offset, input);
+    }
+
+    fn squeeze_bytes(state: &Self::State, offset: usize, output: &mut [u8]) {
+        state.extract_bytes(offset, output);
+    }
+}
+
+/// Rate of the hash mode of Xoodyak in bytes.
+const RATE: usize = 16;
+
+/// Sponge over the Xoodoo\[12\] permutation, computing the hash mode of
+/// Xoodyak: `Cyclist(ε, ε, ε)` followed by `Absorb(M)` and `Squeeze(ℓ)`.
+///
+/// All of the input is absorbed as a single string `M`, however it is split
+/// into calls of [`update`](Self::update).
+#[derive(Clone)]
+pub struct XooSponge {
+    /// Xoodoo state
+    state: XoodooState,
+
+    /// Input which has not been absorbed into the state yet
+    buffer: [u8; RATE],
+
+    /// Number of bytes in `buffer`
+    buffer_len: usize,
+
+    /// Whether a block has been absorbed into the state
+    absorbed: bool,
+}
+
+impl XooSponge {
+    /// Create a new [`XooSponge`] instance.
+    pub fn new() -> Self {
+        Self {
+            state: XoodooState::default(),
+            buffer: [0u8; RATE],
+            buffer_len: 0,
+            absorbed: false,
+        }
+    }
+
+    /// Input data into the hash function.
+    pub fn update(&mut self, bytes: impl AsRef<[u8]>) {
+        let mut input = bytes.as_ref();
+        while !input.is_empty() {
+            // The last block is only absorbed when finalizing
+            if self.buffer_len == RATE {
+                self.absorb_buffer();
+            }
+
+            let n = min(RATE - self.buffer_len, input.len());
+            self.buffer[self.buffer_len..self.buffer_len + n].copy_from_slice(&input[..n]);
+            self.buffer_len += n;
+            input = &input[n..];
+        }
+    }
+
+    /// Input data into the hash function, returning the updated hasher.
+    pub fn chain(mut self, bytes: impl AsRef<[u8]>) -> Self {
+        self.update(bytes);
+        self
+    }
+
+    /// Fill `output` with `output.len()` bytes of output and consume the
+    /// hasher.
+    pub fn finalize_into(mut self, output: &mut [u8]) {
+        self.absorb_buffer();
+        for (i, block) in output.chunks_mut(RATE).enumerate() {
+            if i > 0 {
+                self.state.xor_bytes(0, &[0x01]);
+            }
+            self.state.permute();
+            self.state.extract_bytes(0, block);
+        }
+    }
+
+    /// Absorb the buffered block, which is the first one with the domain
+    /// separation bit of `Absorb` set.
+    fn absorb_buffer(&mut self) {
+        if self.absorbed {
+            self.state.permute();
+        }
+        let block = &self.buffer[..self.buffer_len];
+        self.state.xor_bytes(0, block);
+        self.state.xor_bytes(block.len(), &[0x01]);
+        if !self.absorbed {
+            self.state.xor_bytes(47, &[0x01]);
+        }
+        self.absorbed = true;
+        self.buffer_len = 0;
+    }
+}
+
+impl Default for XooSponge {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl fmt::Debug for XooSponge {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("XooSponge { ... }")
+    }
+}
+
+#[cfg(feature = "zeroize")]
+impl Drop for XooSponge {
+    fn drop(&mut self) {
+        self.state.zeroize();
+        self.buffer.zeroize();
+    }
+}
+
+#[cfg(feature = "zeroize")]
+impl ZeroizeOnDrop for XooSponge {}
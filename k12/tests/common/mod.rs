@@ -0,0 +1,6 @@
+//! Helpers shared by the integration tests.
+
+/// Pattern of `len` bytes `00 01 02 .. F9 FA 00 01 ..` used as test input.
+pub fn ptn(len: usize) -> Vec<u8> {
+    (0..len).map(|j| (j % 251) as u8).collect()
+}
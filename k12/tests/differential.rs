@@ -0,0 +1,188 @@
+//! Differential tests against the Python reference implementation of the
+//! eXtended Keccak Code Package in `tests/reference/K12.py`.
+//!
+//! These tests need `python3` (or the interpreter named by `PYTHON`) and are
+//! ignored by default; run them with `cargo test --test differential --
+//! --ignored`.
+
+#![cfg(feature = "std")]
+
+use k12::{KangarooTwelve, TurboShake128, TurboShake256};
+use std::{
+    env,
+    io::{BufRead, BufReader, Write},
+    path::Path,
+    process::{Child, ChildStdin, ChildStdout, Command, Stdio},
+};
+
+/// Reference implementation running in a Python process.
+struct Reference {
+    child: Child,
+    stdin: ChildStdin,
+    stdout: BufReader<ChildStdout>,
+}
+
+impl Reference {
+    fn spawn() -> Self {
+        let python = env::var("PYTHON").unwrap_or_else(|_| "python3".into());
+        let dir = Path::new(env!("CARGO_MANIFEST_DIR")).join("tests/reference");
+        let mut child = Command::new(python)
+            .arg("driver.py")
+            .current_dir(dir)
+            // Keep `__pycache__` out of the source tree
+            .env("PYTHONDONTWRITEBYTECODE", "1")
+            .stdin(Stdio::piped())
+            .stdout(Stdio::piped())
+            .spawn()
+            .expect("failed to run the Python reference implementation");
+        let stdin = child.stdin.take().unwrap();
+        let stdout = BufReader::new(child.stdout.take().unwrap());
+        Self {
+            child,
+            stdin,
+            stdout,
+        }
+    }
+
+    /// Output of the function `name` of the driver for the arguments.
+    fn call(&mut self, name: &str, a: &[u8], b: &[u8], output_len: usize) -> Vec<u8> {
+        writeln!(self.stdin, "{} {} {} {}", name, hex(a), hex(b), output_len).unwrap();
+        self.stdin.flush().unwrap();
+        let mut line = String::new();
+        self.stdout.read_line(&mut line).unwrap();
+        assert!(!line.is_empty(), "the reference implementation exited");
+        let line = line.trim_end();
+        (0..line.len())
+            .step_by(2)
+            .map(|i| u8::from_str_radix(&line[i..i + 2], 16).unwrap())
+            .collect()
+    }
+}
+
+impl Drop for Reference {
+    fn drop(&mut self) {
+        let _ = self.child.kill();
+        let _ = self.child.wait();
+    }
+}
+
+fn hex(bytes: &[u8]) -> String {
+    bytes.iter().map(|b| format!("{:02x}", b)).collect()
+}
+
+/// xorshift64* generator, so that failures can be reproduced.
+struct Rng(u64);
+
+impl Rng {
+    fn next(&mut self) -> u64 {
+        self.0 ^= self.0 >> 12;
+        self.0 ^= self.0 << 25;
+        self.0 ^= self.0 >> 27;
+        self.0.wrapping_mul(0x2545_F491_4F6C_DD1D)
+    }
+
+    fn below(&mut self, n: usize) -> usize {
+        (self.next() % n as u64) as usize
+    }
+
+    fn bytes(&mut self, len: usize) -> Vec<u8> {
+        (0..len).map(|_| self.next() as u8).collect()
+    }
+}
+
+/// KangarooTwelve of `m`, absorbed in pieces of random sizes.
+fn k12(rng: &mut Rng, m: &[u8], customization: &[u8], output_len: usize) -> Vec<u8> {
+    let mut hasher = KangarooTwelve::new_with_customization(customization);
+    let mut rest = m;
+    while !rest.is_empty() {
+        let n = match rng.below(4) {
+            0 => rest.len(),
+            1 => rng.below(200),
+            _ => rng.below(3 * 8192),
+        }
+        .min(rest.len());
+        hasher.update(&rest[..n]);
+        rest = &rest[n..];
+    }
+    let mut output = vec![0u8; output_len];
+    hasher.finalize_xof().read(&mut output);
+    output
+}
+
+#[test]
+#[ignore]
+fn k12_chunk_boundaries() {
+    let mut reference = Reference::spawn();
+    let mut rng = Rng(0x4B31_3220_6469_6666);
+
+    // The input string `S = M || C || right_encode(|C|)` straddles the end
+    // of the first and second chunks, and of groups of leaves
+    for &len in &[8192, 8193, 16384, 16385, 9 * 8192 + 1] {
+        for &customization in &[&b""[..], b"C"] {
+            let s_len = len - customization.len() - 1;
+            for m_len in s_len - 2..=s_len + 2 {
+                let m = rng.bytes(m_len);
+                let expected = reference.call("k12", &m, customization, 64);
+                assert_eq!(
+                    k12(&mut rng, &m, customization, 64),
+                    expected,
+                    "|M| = {}, C = {:?}",
+                    m_len,
+                    customization
+                );
+            }
+        }
+    }
+}
+
+#[test]
+#[ignore]
+fn k12_random() {
+    let mut reference = Reference::spawn();
+    let mut rng = Rng(0x6B31_3272_616E_646F);
+    for _ in 0..100 {
+        let (m_len, c_len) = (rng.below(3 * 8192), rng.below(300));
+        let m = rng.bytes(m_len);
+        let customization = rng.bytes(c_len);
+        let output_len = 1 + rng.below(500);
+        let expected = reference.call("k12", &m, &customization, output_len);
+        assert_eq!(
+            k12(&mut rng, &m, &customization, output_len),
+            expected,
+            "|M| = {}, |C| = {}, L = {}",
+            m.len(),
+            customization.len(),
+            output_len
+        );
+    }
+}
+
+#[test]
+#[ignore]
+fn turbo_shake_random() {
+    let mut reference = Reference::spawn();
+    let mut rng = Rng(0x7473_6872_616E_646F);
+    for _ in 0..100 {
+        let m_len = rng.below(1000);
+        let m = rng.bytes(m_len);
+        let domain = 1 + rng.below(0x7F) as u8;
+        let output_len = 1 + rng.below(500);
+
+        let mut output = vec![0u8; output_len];
+        TurboShake128::new_with_domain(domain)
+            .unwrap()
+            .chain(&m)
+            .finalize_xof()
+            .read(&mut output);
+        let expected = reference.call("turboshake128", &m, &[domain], output_len);
+        assert_eq!(output, expected, "TurboSHAKE128, |M| = {}", m.len());
+
+        TurboShake256::new_with_domain(domain)
+            .unwrap()
+            .chain(&m)
+            .finalize_xof()
+            .read(&mut output);
+        let expected = reference.call("turboshake256", &m, &[domain], output_len);
+        assert_eq!(output, expected, "TurboSHAKE256, |M| = {}", m.len());
+    }
+}
@@ -0,0 +1,93 @@
+mod common;
+
+use common::ptn;
+use hex_literal::hex;
+use k12::{Duplex, TurboShake128};
+
+#[test]
+fn matches_turbo_shake128() {
+    for &len in &[0, 1, 167, 168, 169, 500] {
+        let mut duplex = Duplex::new();
+        duplex.absorb(&ptn(len));
+        let mut output = [0u8; 400];
+        duplex.squeeze(&mut output[..100]);
+        duplex.squeeze(&mut output[100..]);
+
+        let mut expected = [0u8; 400];
+        TurboShake128::new_with_domain(0x01)
+            .unwrap()
+            .chain(ptn(len))
+            .finalize_xof()
+            .read(&mut expected);
+        assert_eq!(output[..], expected[..], "length {}", len);
+    }
+}
+
+// Computed with a Python model of the duplex over the reference
+// implementation of Keccak-p[1600, 12]
+#[test]
+fn encrypt_then_tag() {
+    let mut duplex = Duplex::new();
+    duplex.absorb(b"key");
+    duplex.absorb(&ptn(200));
+    let mut ciphertext = [0u8; 300];
+    duplex.absorb_and_squeeze(&ptn(300), &mut ciphertext);
+    duplex.absorb(b"ad");
+    let mut tag = [0u8; 32];
+    duplex.squeeze(&mut tag);
+
+    assert_eq!(
+        ciphertext[268..],
+        hex!("2D2307FB1E2DA7ACEC265DA698F01D016B455854E5D6F405105AD0C3F0A2D4C6")
+    );
+    assert_eq!(
+        tag,
+        hex!("7C953746BDEF19C86562F0B734CBF704C157BCC110D7689FF8B016C52A9F6687")
+    );
+}
+
+#[test]
+fn decrypt() {
+    let plaintext = ptn(500);
+    let mut sender = Duplex::new();
+    sender.absorb(b"key");
+    let receiver = sender.clone();
+
+    let mut ciphertext = vec![0u8; 500];
+    sender.absorb_and_squeeze(&plaintext[..10], &mut ciphertext[..10]);
+    sender.absorb_and_squeeze(&plaintext[10..], &mut ciphertext[10..]);
+    assert_ne!(ciphertext, plaintext);
+
+    let mut receiver = receiver;
+    let mut decrypted = vec![0u8; 500];
+    receiver.squeeze_and_absorb(&ciphertext, &mut decrypted);
+    assert_eq!(decrypted, plaintext);
+
+    // Both ends are in the same state afterwards
+    let (mut a, mut b) = ([0u8; 32], [0u8; 32]);
+    sender.squeeze(&mut a);
+    receiver.squeeze(&mut b);
+    assert_eq!(a, b);
+}
+
+#[test]
+fn phases() {
+    // Squeezing then absorbing differs from absorbing everything at once
+    let mut split = Duplex::new();
+    split.absorb(b"ab");
+    split.squeeze(&mut [0u8; 1]);
+    split.absorb(b"c");
+    let mut joined = Duplex::new();
+    joined.absorb(b"abc");
+    let (mut a, mut b) = ([0u8; 32], [0u8; 32]);
+    split.squeeze(&mut a);
+    joined.squeeze(&mut b);
+    assert_ne!(a, b);
+
+    // Consecutive calls of the same kind are not delimited
+    let mut split = Duplex::new();
+    split.absorb(b"ab");
+    split.absorb(b"c");
+    split.squeeze(&mut a);
+    assert_eq!(a, b);
+}
@@ -0,0 +1,66 @@
+//! Tests of the C interface against the Rust API.
+#![cfg(feature = "ffi")]
+
+use k12::ffi::{k12_finalize, k12_free, k12_new, k12_update};
+use std::ptr;
+
+#[test]
+fn matches_rust_api() {
+    let m: Vec<u8> = (0..20000).map(|j| (j % 251) as u8).collect();
+    let mut output = [0u8; 64];
+
+    unsafe {
+        let state = k12_new();
+        assert_eq!(k12_update(state, m.as_ptr(), 10000), 0);
+        assert_eq!(k12_update(state, m[10000..].as_ptr(), 10000), 0);
+        assert_eq!(
+            k12_finalize(state, b"c".as_ptr(), 1, output.as_mut_ptr(), 64),
+            0
+        );
+        assert_eq!(output[..], k12::hash(&m, b"c", 64).unwrap()[..]);
+
+        // Finalizing leaves the state unchanged
+        assert_eq!(k12_update(state, b"abc".as_ptr(), 3), 0);
+        assert_eq!(
+            k12_finalize(state, ptr::null(), 0, output.as_mut_ptr(), 64),
+            0
+        );
+        assert_eq!(
+            output[..],
+            k12::hash(&[&m[..], b"abc"].concat(), b"", 64).unwrap()[..]
+        );
+        k12_free(state);
+    }
+}
+
+#[test]
+fn null_pointers() {
+    let mut output = [0u8; 32];
+
+    unsafe {
+        let state = k12_new();
+        assert_eq!(k12_update(ptr::null_mut(), b"abc".as_ptr(), 3), -1);
+        assert_eq!(k12_update(state, ptr::null(), 3), -1);
+        assert_eq!(k12_update(state, ptr::null(), 0), 0);
+
+        assert_eq!(
+            k12_finalize(ptr::null(), ptr::null(), 0, output.as_mut_ptr(), 32),
+            -1
+        );
+        assert_eq!(
+            k12_finalize(state, ptr::null(), 1, output.as_mut_ptr(), 32),
+            -1
+        );
+        assert_eq!(k12_finalize(state, ptr::null(), 0, ptr::null_mut(), 32), -1);
+        assert_eq!(k12_finalize(state, ptr::null(), 0, ptr::null_mut(), 0), 0);
+
+        assert_eq!(
+            k12_finalize(state, ptr::null(), 0, output.as_mut_ptr(), 32),
+            0
+        );
+        assert_eq!(output[..], k12::hash(b"", b"", 32).unwrap()[..]);
+
+        k12_free(state);
+        k12_free(ptr::null_mut());
+    }
+}
@@ -0,0 +1,97 @@
+//! Key derivation test vectors.
+//!
+//! The inputs are those of the test cases of RFC 5869. The expected outputs
+//! were computed with the Python reference implementation of KangarooTwelve
+//! in `tests/reference/K12.py`.
+#![cfg(feature = "alloc")]
+
+use hex_literal::hex;
+use k12::{k12_expand, k12_extract, Error};
+
+#[test]
+fn rfc5869_inputs() {
+    // Test case 1: basic test case
+    let ikm = [0x0b; 22];
+    let salt: Vec<u8> = (0x00..=0x0c).collect();
+    let info: Vec<u8> = (0xf0..=0xf9).collect();
+    let prk = k12_extract(&salt, &ikm);
+    assert_eq!(
+        prk[..],
+        hex!("4283AAEBC5557B34DEFF2CC88CED821C5DB93265A6C1D0487760E7B77601FDCA")
+    );
+    assert_eq!(
+        k12_expand(&prk, &info, 42).unwrap()[..],
+        hex!(
+            "B360B6C0DC252502EA0599FD1830A42653B63C448E327C7825A3B5107E02FAD87852EC1C660D44457F87"
+        )
+    );
+
+    // Test case 2: longer inputs and outputs
+    let ikm: Vec<u8> = (0x00..=0x4f).collect();
+    let salt: Vec<u8> = (0x60..=0xaf).collect();
+    let info: Vec<u8> = (0xb0..=0xff).collect();
+    let prk = k12_extract(&salt, &ikm);
+    assert_eq!(
+        prk[..],
+        hex!("4C0B26F73F453681BFA000B6E089E4DDDCFC46404CEB292FA3B2BFC34E671BF4")
+    );
+    assert_eq!(
+        k12_expand(&prk, &info, 82).unwrap()[..],
+        hex!("F6DD90B02004EF7D679F9ACD6A4016766AAB081B28CAA4E3FF36765AA80914C123264B035D53E7FA08B39F2BC6D798C5D9491ED5BC8DD9064BF59251E3BFB5A2130BF3C618E2978512F049CC151EF597081A")
+    );
+
+    // Test case 3: empty salt and info
+    let prk = k12_extract(&[], &[0x0b; 22]);
+    assert_eq!(
+        prk[..],
+        hex!("D5828E71748D2456595DB0F9D0AF8B5F2AFE2F367A9B7A591BD9B672A919DEBA")
+    );
+    assert_eq!(
+        k12_expand(&prk, &[], 42).unwrap()[..],
+        hex!(
+            "A19018EEF9F1D504FABB390F7202C687F81AFE71CC7BBB4311DB584C41D80E7C52FDAD6C6DCCBFEBD65E"
+        )
+    );
+}
+
+#[test]
+fn separation() {
+    let prk = k12_extract(b"salt", b"ikm");
+
+    // The salt is length-prefixed, and each phase has its own customization
+    assert_ne!(prk, k12_extract(b"sal", b"tikm"));
+    assert_ne!(
+        prk[..],
+        k12::hash(b"ikm", b"K12-KDF-Extract", 32).unwrap()[..]
+    );
+    assert_ne!(k12_expand(&prk, b"a", 32), k12_expand(&prk, b"b", 32));
+    assert_ne!(
+        k12_expand(&prk[..31], &prk[31..], 32),
+        k12_expand(&prk, b"", 32)
+    );
+
+    // Shorter outputs are prefixes of longer ones, as with HKDF-Expand
+    let long = k12_expand(&prk, b"info", 100).unwrap();
+    assert_eq!(k12_expand(&prk, b"info", 10).unwrap()[..], long[..10]);
+    assert_eq!(k12_expand(&prk, b"info", 0), Err(Error::ZeroOutputLength));
+}
+
+#[test]
+fn uniform_output() {
+    let prk = k12_extract(b"salt", b"input keying material");
+    let okm = k12_expand(&prk, b"info", 256 * 1024).unwrap();
+
+    let mut counts = [0u64; 256];
+    for &b in &okm {
+        counts[b as usize] += 1;
+    }
+    let expected = okm.len() as f64 / 256.0;
+    let chi_squared: f64 = counts
+        .iter()
+        .map(|&n| (n as f64 - expected).powi(2) / expected)
+        .sum();
+
+    // Critical value of the chi-squared distribution with 255 degrees of
+    // freedom at a significance level of 0.001
+    assert!(chi_squared < 330.5, "chi-squared = {}", chi_squared);
+}
@@ -0,0 +1,252 @@
+//! Known-answer tests of the Keccak-p[1600] and Keccak-p[800, 12]
+//! permutations, computed with the Python reference implementation of the
+//! Keccak team.
+
+use hex_literal::hex;
+use k12::{keccak_p800_12, keccak_p_1600, keccak_p_1600_12};
+
+#[test]
+fn all_zeros() {
+    let mut state = [0u8; 200];
+    keccak_p_1600_12(&mut state);
+    assert_eq!(
+        state[..],
+        hex!(
+            "1786A7B938545E8E1ED059F2506ACDD9351FA952C6E7B887C5E0E4CD67E09310
+            455AD9F290AB33B0451ADDA8722FA7E09C2F6714AA8037C51D075100F547DD3E
+            CC8A170C311DA3B3A0AA5792A586B5799BF9B1B33D7C4ABC93678AE663408768
+            66250E2E33036C5CDA30F0B90212AA9C9F7ACF2B789A3B5F2379AE61E0C136E5
+            EC873CB718B6E96DC28A9170F1D1BE2AB724EDDA53BDAB6A5AE12E2C6A41C1BF
+            AF5209B936E0CFC6D76070DC17365045E47A9FC2B21156627A64302CDB7136D4
+            1CA02C22760DFDCF"
+        )[..]
+    );
+}
+
+#[test]
+fn all_ones() {
+    let mut state = [0xFFu8; 200];
+    keccak_p_1600_12(&mut state);
+    assert_eq!(
+        state[..],
+        hex!(
+            "198D776E847A2915A243B6534EDC97FDF28A7BFF44B59A9079D20C97B63B6AAF
+            A5B413A6F2D104EBDE2FC2F079B692E26B635E16D72590BAF77C875664A027D6
+            FFF4408D5FC195A63C8C440B91DE79ECB65B13DA4A5DB6722DE38970D0B8504A
+            B6625DD0134F49C521E1D74B0E8B599F34447A1B05ECA05E72A926C4AE409899
+            D8ED979AEA9C70B131BE47D10F11DB60A973D66678843C56ECF3E15DC15AB923
+            58E8C978F693AFE53A0B91B30402F1CE18079D63B3A783CFCADDB728B4B8400F
+            2FC847348489B6C5"
+        )[..]
+    );
+}
+
+#[test]
+fn pattern() {
+    let mut state = [0u8; 200];
+    for (j, byte) in state.iter_mut().enumerate() {
+        *byte = (j % 251) as u8;
+    }
+    keccak_p_1600_12(&mut state);
+    assert_eq!(
+        state[..],
+        hex!(
+            "F4EAED43DC81909F5E96DC7D9659986E5AC8142009D0FE6172B4DFD56D0FEE1B
+            94432F44C97CA32E7D87FE8271F8D6540B4BD6DEA08A6C59F8ECC5AB20D4522B
+            D841350464AE68A676CFF2DCE4F67A57BA25D0CC57DBCBDD7C295858D751E3D6
+            6751A761B3B17B5DBF8E8779F9F09C1CC17688B3A33F4D11020001D21052409C
+            0681489199DEA9A7D3F8B8E6BE1AD1741490D984DB95D5ACC900D0293EDB14A5
+            C805CD6C1DC18E2B457ED791C2EC94661E442480C1773F8D74F994744DE70221
+            2894129B7044178F"
+        )[..]
+    );
+}
+
+#[test]
+fn round_counts() {
+    let mut state = [0u8; 200];
+    keccak_p_1600(&mut state, 0);
+    assert_eq!(state, [0u8; 200]);
+
+    // Only the round constant of the last round remains
+    keccak_p_1600(&mut state, 1);
+    assert_eq!(state[..8], hex!("0880008000000080"));
+    assert!(state[8..].iter().all(|&b| b == 0));
+
+    let mut state = [0u8; 200];
+    keccak_p_1600(&mut state, 14);
+    assert_eq!(
+        state[..],
+        hex!(
+            "F439AE25605C0593A5F37267C177BAFFEA515A55D561ED51CCF0E55C83D05853
+            3EFB72DF77AC01AE509A12AC857F76E064F0D09C50020BCECA7FF5F64BCECFF7
+            E1168390F1B181AC53055989A3F0EB4D033B18FAE82C0986ADC2D9A44416594E
+            DDA01C2669A3B02A9645A81A108C19D3CE102C584A470161390DE93A62481686
+            D67A050932E465E432E51A1981AAB63BE2B7A642555E54E9BC783CA572AE3142
+            9480818D642686A76ECDFC0CF69455418828C211A398B0E0E8AE31E185D2176F
+            50119099E1D0F843"
+        )[..]
+    );
+
+    // Keccak-f[1600]
+    let mut state = [0u8; 200];
+    keccak_p_1600(&mut state, 24);
+    assert_eq!(
+        state[..],
+        hex!(
+            "E7DDE140798F25F18A47C033F9CCD584EEA95AA61E2698D54D49806F304715BD
+            57D05362054E288BD46F8E7F2DA497FFC44746A4A0E5FE90762E19D60CDA5B8C
+            9C05191BF7A630AD64FC8FD0B75A933035D617233FA95AEB0321710D26E6A6A9
+            5F55CFDB167CA58126C84703CD31B8439F56A5111A2FF20161AED9215A63E505
+            F270C98CF2FEBE641166C47B95703661CB0ED04F555A7CB8C832CF1C8AE83E8C
+            14263AAE22790C94E409C5A224F94118C26504E72635F5163BA1307FE944F675
+            49A2EC5C7BFFF1EA"
+        )[..]
+    );
+
+    let mut state = [0u8; 200];
+    for (j, byte) in state.iter_mut().enumerate() {
+        *byte = (j % 251) as u8;
+    }
+    keccak_p_1600(&mut state, 24);
+    assert_eq!(
+        state[..],
+        hex!(
+            "FA7CD5DAF5912812212976DCA7E5F8B85EB775028C0FAC8F354531749603EE47
+            2C968CCB6DA8D417B03C44B52AA77F0E3E28316BD1B6AFEC0951BC08349203CC
+            3B02E51D94DA62F8089CC4F26E9DB6950617CE9EB7AC23551ADE78FC246E0024
+            B2DA19B0063E0B29B4D12FEB2E41B8E354B6C72C41AAAD31E4B7444BA9BAE521
+            9D035C958E81DC79435D3151BDC41CE4C240FDE4FCA03E7CEA6178360D35DF0D
+            2AF32CF3A30BCA92DDCC77C5026789A3DEA9BCDAE5C2C76F59410FF65684A10F
+            16AE0FE3D4810807"
+        )[..]
+    );
+}
+
+#[test]
+#[should_panic]
+fn too_many_rounds() {
+    keccak_p_1600(&mut [0u8; 200], 25);
+}
+
+#[test]
+fn p800_all_zeros() {
+    let mut state = [0u8; 100];
+    keccak_p800_12(&mut state);
+    assert_eq!(
+        state[..],
+        hex!(
+            "0B3E6E25CB9AEBD24D7F25C1669636EDA9CF4EF7C9EA4DD58C308E1793EA1968
+            AD9F8D11C206FE0191E28D4492422BA45AF67A62C6F049978FC1F2C59A3AB148
+            C73381D02BB9F603E2A081EECAE2B83814BA14E9B8F23D2D2E537A35AC918049
+            3A826FDD"
+        )[..]
+    );
+}
+
+#[test]
+fn p800_pattern() {
+    let mut state = [0u8; 100];
+    for (j, byte) in state.iter_mut().enumerate() {
+        *byte = j as u8;
+    }
+    keccak_p800_12(&mut state);
+    assert_eq!(
+        state[..],
+        hex!(
+            "3765D4757348C5173DE2C85F54AB6137AED4488C50BC6C17FE679108DDD4726A
+            1CCC9B98A38ABF6630A1655AB06FD3777EF1D6D5AA131B590DFE9EC4DDE3BFE9
+            95A545844D555A9B2CECCBE84BAD748A1C772A80869080F06B4681A4780AE1F0
+            73888416"
+        )[..]
+    );
+}
+
+#[test]
+#[cfg(feature = "debug_permutation")]
+fn debug_rounds() {
+    let mut pattern = [0u8; 200];
+    for (j, byte) in pattern.iter_mut().enumerate() {
+        *byte = (j % 251) as u8;
+    }
+
+    for &rounds in &[0, 1, 12, 14, 24] {
+        let mut states = Vec::new();
+        let mut state = pattern;
+        k12::keccak_p_debug(&mut state, rounds, &mut |i, s| states.push((i, *s)));
+        let indices: Vec<_> = states.iter().map(|&(i, _)| i).collect();
+        assert_eq!(indices, (24 - rounds..24).collect::<Vec<_>>());
+
+        let mut expected = pattern;
+        keccak_p_1600(&mut expected, rounds);
+        assert_eq!(state[..], expected[..], "{} rounds", rounds);
+        if let Some((_, last)) = states.last() {
+            assert_eq!(last[..], expected[..], "{} rounds", rounds);
+        }
+    }
+
+    // State after round 0 of Keccak-f[1600]
+    let mut first = None;
+    k12::keccak_p_debug(&mut pattern, 24, &mut |i, s| {
+        if i == 0 {
+            first = Some(*s);
+        }
+    });
+    assert_eq!(
+        first.unwrap()[..],
+        hex!(
+            "41D2C4F6C81A0C7E6ACAAA8BEB0B2B4A90A07282D3E330C09D5F1DDC945414D7
+            C260C567C062C7652A4A4A8AAACA8A4A09F948B8C87889390595A43444D4E474
+            FE5E7F1F7F5F7E9E8828C82809A9C9A9B4BDB5BCA4ADA5ACD950D255D352D05F
+            15112D3925291D01CFC5DBD1C7CDC3C9F2F9C1C8C0CBF3FACBEB9BDBABABFB9B
+            29305F668D94FBC258606E7078444A50EFDCC9F2E3D0C5FE4E480A644640422C
+            1C9A1B991A98199F111B1F15111F1F11353A2320111E171445514D4955415D59
+            E2EBF3FFC6CDD5DB"
+        )[..]
+    );
+}
+
+#[test]
+#[cfg(feature = "masking")]
+fn masked() {
+    use k12::{keccak_p_1600_masked, K12Rng};
+
+    fn to_bytes(lanes: &[u64; 25]) -> [u8; 200] {
+        let mut bytes = [0u8; 200];
+        for (chunk, lane) in bytes.chunks_exact_mut(8).zip(lanes) {
+            chunk.copy_from_slice(&lane.to_le_bytes());
+        }
+        bytes
+    }
+
+    let mut rng = K12Rng::new([7; 32]);
+    for &rounds in &[0, 1, 12, 14, 24] {
+        for _ in 0..20 {
+            let (mut state, mut mask) = ([0u64; 25], [0u64; 25]);
+            state.iter_mut().for_each(|lane| *lane = rng.next_u64());
+            mask.iter_mut().for_each(|lane| *lane = rng.next_u64());
+            let mut share = [0u64; 25];
+            for i in 0..25 {
+                share[i] = state[i] ^ mask[i];
+            }
+
+            keccak_p_1600_masked(&mut share, &mut mask, rounds, &mut rng);
+            for i in 0..25 {
+                share[i] ^= mask[i];
+            }
+            let mut expected = to_bytes(&state);
+            keccak_p_1600(&mut expected, rounds);
+            assert_eq!(to_bytes(&share)[..], expected[..], "{} rounds", rounds);
+        }
+    }
+
+    // The shares of the permuted state are fresh
+    let (mut a, mut b) = ([0u64; 25], [0u64; 25]);
+    let (mut c, mut d) = ([0u64; 25], [0u64; 25]);
+    keccak_p_1600_masked(&mut a, &mut b, 12, &mut rng);
+    keccak_p_1600_masked(&mut c, &mut d, 12, &mut rng);
+    assert_ne!(a, c);
+    let unmasked =
+        |x: [u64; 25], y: [u64; 25]| -> Vec<u64> { x.iter().zip(&y).map(|(x, y)| x ^ y).collect() };
+    assert_eq!(unmasked(a, b), unmasked(c, d));
+}
@@ -0,0 +1,145 @@
+//! KMAC12 test vectors.
+//!
+//! The inputs are those of the NIST KMAC128 samples. The expected tags were
+//! computed with an independent implementation of the construction on top of
+//! the KangarooTwelve of `tiny-keccak`.
+#![cfg(feature = "alloc")]
+
+use hex_literal::hex;
+use k12::{KangarooTwelve, Kmac12};
+
+fn key() -> Vec<u8> {
+    (0x40..0x60).collect()
+}
+
+fn data() -> Vec<u8> {
+    (0..200).collect()
+}
+
+#[test]
+fn samples() {
+    let expected = [
+        (
+            4,
+            &b""[..],
+            hex!("6DB1423757A43FDE8E9EA31C54F8047856191F996B350F996C06E23282D38D8C"),
+        ),
+        (
+            4,
+            &b"My Tagged Application"[..],
+            hex!("C3ED5D67C5E403C65BEF3854DF802AE642D8EF29F85C238E0B90CED50A064486"),
+        ),
+        (
+            200,
+            &b"My Tagged Application"[..],
+            hex!("4A2AE16C5467605F4ADC45A511A9D07DE3478B7CD6D4A506B0077E979132C862"),
+        ),
+    ];
+    for &(len, customization, tag) in &expected {
+        let mac = Kmac12::new_with_customization(key(), customization).chain(&data()[..len]);
+        assert_eq!(mac.tag(32).unwrap(), tag);
+    }
+}
+
+#[test]
+fn multi_chunk() {
+    let m: Vec<u8> = (0..20000).map(|j| (j % 251) as u8).collect();
+    let mac = Kmac12::new_with_customization(key(), b"My Tagged Application").chain(&m);
+    assert_eq!(
+        mac.tag(64).unwrap()[..],
+        hex!(
+            "7560BB65611C6E322B5F52DFC82A7718619815B76B2C4271732FA4BA3E96E029
+            498219DA4B35E50368F5796C080A6157F04BA6B52ACE078144124A52C6B8EDD9"
+        )[..]
+    );
+}
+
+#[test]
+fn differs_from_k12() {
+    let tag = Kmac12::new(b"").chain(b"abc").tag(32).unwrap();
+    assert_ne!(tag, k12::hash(b"abc", b"", 32).unwrap());
+    assert_ne!(
+        tag[..],
+        KangarooTwelve::new_with_customization(b"KMAC")
+            .chain(b"abc")
+            .finalize_fixed::<32>()[..]
+    );
+
+    // The tag length is part of the input
+    let long = Kmac12::new(b"").chain(b"abc").tag(64).unwrap();
+    assert_ne!(tag[..], long[..32]);
+}
+
+#[test]
+fn verify() {
+    let mac = Kmac12::new(key()).chain(data());
+    let tag = mac.clone().tag(32).unwrap();
+    assert!(mac.clone().verify(&tag));
+    assert!(mac.clone().verify(&mac.clone().tag(16).unwrap()));
+
+    let mut forged = tag.clone();
+    forged[31] ^= 1;
+    assert!(!mac.clone().verify(&forged));
+    assert!(!mac.clone().verify(&tag[..31]));
+    assert!(!mac.verify(&[]));
+}
+
+#[test]
+fn reset() {
+    let mut mac = Kmac12::new_with_customization(key(), b"c");
+    mac.update(data());
+    mac.reset();
+    mac.update(b"abc");
+    assert_eq!(
+        mac.tag(32).unwrap(),
+        Kmac12::new_with_customization(key(), b"c")
+            .chain(b"abc")
+            .tag(32)
+            .unwrap()
+    );
+}
+
+#[test]
+#[cfg(feature = "digest")]
+fn mac_traits() {
+    use k12::crypto_mac::{generic_array::GenericArray, Mac, NewMac};
+
+    let mut mac = <Kmac12 as NewMac>::new_varkey(&key()).unwrap();
+    Mac::update(&mut mac, b"abc");
+    let tag = mac.finalize_reset().into_bytes();
+    assert_eq!(
+        tag[..],
+        Kmac12::new(key()).chain(b"abc").tag(32).unwrap()[..]
+    );
+
+    Mac::update(&mut mac, b"abc");
+    assert!(Mac::verify(mac, &tag).is_ok());
+
+    let mac = <Kmac12 as NewMac>::new(GenericArray::from_slice(&key()));
+    assert!(Mac::verify(mac, &tag).is_err());
+}
+
+#[test]
+#[cfg(feature = "digest")]
+fn mac_key_lengths() {
+    use k12::crypto_mac::{Mac, MacError, NewMac};
+
+    let key: Vec<u8> = (0..64).collect();
+    let tags: Vec<_> = [0, 16, 32, 64]
+        .iter()
+        .map(|&len| {
+            let mut mac = <Kmac12 as NewMac>::new_varkey(&key[..len]).unwrap();
+            Mac::update(&mut mac, b"abc");
+            mac.finalize().into_bytes()
+        })
+        .collect();
+    for (i, a) in tags.iter().enumerate() {
+        for b in &tags[i + 1..] {
+            assert_ne!(a, b);
+        }
+    }
+
+    let mac = <Kmac12 as NewMac>::new_varkey(&key[..16]).unwrap();
+    assert_eq!(Mac::verify(mac.clone().chain(b"abc"), &tags[1]), Ok(()));
+    assert_eq!(Mac::verify(mac.chain(b"abd"), &tags[1]), Err(MacError));
+}
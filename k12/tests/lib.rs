@@ -1,10 +1,10 @@
-use core::iter;
-use k12::{
-    digest::{ExtendableOutput, Update},
-    KangarooTwelve,
-};
+#[cfg(all(feature = "alloc", feature = "digest"))]
+use k12::digest::ExtendableOutput;
+#[cfg(feature = "alloc")]
+use k12::KangarooTwelve;
+use k12::{Error, StackKangarooTwelve};
 
-fn read_bytes<T: AsRef<[u8]>>(s: T) -> Box<[u8]> {
+fn read_bytes<T: AsRef<[u8]>>(s: T) -> Vec<u8> {
     fn b(c: u8) -> u8 {
         match c {
             b'0'..=b'9' => c - b'0',
@@ -29,14 +29,15 @@ fn read_bytes<T: AsRef<[u8]>>(s: T) -> Box<[u8]> {
         i += 2;
     }
 
-    v.into_boxed_slice()
+    v
 }
 
 #[test]
+#[cfg(feature = "alloc")]
 fn empty() {
     // Source: reference paper
     assert_eq!(
-        KangarooTwelve::new().chain(b"").finalize_boxed(32),
+        KangarooTwelve::new().chain(b"").try_finalize(32).unwrap(),
         read_bytes(
             "1a c2 d4 50 fc 3b 42 05 d1 9d a7 bf ca
                 1b 37 51 3c 08 03 57 7a c7 16 7f 06 fe 2c e1 f0 ef 39 e5"
@@ -44,7 +45,7 @@ fn empty() {
     );
 
     assert_eq!(
-        KangarooTwelve::new().chain(b"").finalize_boxed(64),
+        KangarooTwelve::new().chain(b"").try_finalize(64).unwrap(),
         read_bytes(
             "1a c2 d4 50 fc 3b 42 05 d1 9d a7 bf ca
                 1b 37 51 3c 08 03 57 7a c7 16 7f 06 fe 2c e1 f0 ef 39 e5 42 69 c0 56 b8 c8 2e
@@ -53,7 +54,10 @@ fn empty() {
     );
 
     assert_eq!(
-        KangarooTwelve::new().chain(b"").finalize_boxed(10032)[10000..],
+        KangarooTwelve::new()
+            .chain(b"")
+            .try_finalize(10032)
+            .unwrap()[10000..],
         read_bytes(
             "e8 dc 56 36 42 f7 22 8c 84
                 68 4c 89 84 05 d3 a8 34 79 91 58 c0 79 b1 28 80 27 7a 1d 28 e2 ff 6d"
@@ -61,53 +65,1302 @@ fn empty() {
     );
 }
 
+const PAT_M: [&str; 7] = [
+    "2b da 92 45 0e 8b 14 7f 8a 7c b6 29 e7 84 a0 58 ef ca 7c f7
+            d8 21 8e 02 d3 45 df aa 65 24 4a 1f",
+    "6b f7 5f a2 23 91 98 db 47 72 e3 64 78 f8 e1 9b 0f 37 12 05
+            f6 a9 a9 3a 27 3f 51 df 37 12 28 88",
+    "0c 31 5e bc de db f6 14 26 de 7d cf 8f b7 25 d1 e7 46 75 d7
+            f5 32 7a 50 67 f3 67 b1 08 ec b6 7c",
+    "cb 55 2e 2e c7 7d 99 10 70 1d 57 8b 45 7d df 77 2c 12 e3 22
+            e4 ee 7f e4 17 f9 2c 75 8f 0d 59 d0",
+    "87 01 04 5e 22 20 53 45 ff 4d da 05 55 5c bb 5c 3a f1 a7 71
+            c2 b8 9b ae f3 7d b4 3d 99 98 b9 fe",
+    "84 4d 61 09 33 b1 b9 96 3c bd eb 5a e3 b6 b0 5c c7 cb d6 7c
+            ee df 88 3e b6 78 a0 a8 e0 37 16 82",
+    "3c 39 07 82 a8 a4 e8 9f a6 36 7f 72 fe aa f1 32 55 c8 d9 58
+            78 48 1d 3c d8 ce 85 f5 8e 88 0a f8",
+];
+
 #[test]
+#[cfg(feature = "alloc")]
 fn pat_m() {
-    let expected = [
-        "2b da 92 45 0e 8b 14 7f 8a 7c b6 29 e7 84 a0 58 ef ca 7c f7
-                d8 21 8e 02 d3 45 df aa 65 24 4a 1f",
-        "6b f7 5f a2 23 91 98 db 47 72 e3 64 78 f8 e1 9b 0f 37 12 05
-                f6 a9 a9 3a 27 3f 51 df 37 12 28 88",
-        "0c 31 5e bc de db f6 14 26 de 7d cf 8f b7 25 d1 e7 46 75 d7
-                f5 32 7a 50 67 f3 67 b1 08 ec b6 7c",
-        "cb 55 2e 2e c7 7d 99 10 70 1d 57 8b 45 7d df 77 2c 12 e3 22
-                e4 ee 7f e4 17 f9 2c 75 8f 0d 59 d0",
-        "87 01 04 5e 22 20 53 45 ff 4d da 05 55 5c bb 5c 3a f1 a7 71
-                c2 b8 9b ae f3 7d b4 3d 99 98 b9 fe",
-        "84 4d 61 09 33 b1 b9 96 3c bd eb 5a e3 b6 b0 5c c7 cb d6 7c
-                ee df 88 3e b6 78 a0 a8 e0 37 16 82",
-        "3c 39 07 82 a8 a4 e8 9f a6 36 7f 72 fe aa f1 32 55 c8 d9 58
-                78 48 1d 3c d8 ce 85 f5 8e 88 0a f8",
-    ];
     for i in 0..5
     /*NOTE: can be up to 7 but is slow*/
     {
         let len = 17usize.pow(i);
         let m: Vec<u8> = (0..len).map(|j| (j % 251) as u8).collect();
-        let result = KangarooTwelve::new().chain(&m).finalize_boxed(32);
-        assert_eq!(result, read_bytes(expected[i as usize]));
+        let result = KangarooTwelve::new().chain(&m).try_finalize(32).unwrap();
+        assert_eq!(result, read_bytes(PAT_M[i as usize]));
     }
 }
 
+const PAT_C: [&str; 4] = [
+    "fa b6 58 db 63 e9 4a 24 61 88 bf 7a f6 9a 13 30 45 f4 6e e9
+            84 c5 6e 3c 33 28 ca af 1a a1 a5 83",
+    "d8 48 c5 06 8c ed 73 6f 44 62 15 9b 98 67 fd 4c 20 b8 08 ac
+            c3 d5 bc 48 e0 b0 6b a0 a3 76 2e c4",
+    "c3 89 e5 00 9a e5 71 20 85 4c 2e 8c 64 67 0a c0 13 58 cf 4c
+            1b af 89 44 7a 72 42 34 dc 7c ed 74",
+    "75 d2 f8 6a 2e 64 45 66 72 6b 4f bc fc 56 57 b9 db cf 07 0c
+            7b 0d ca 06 45 0a b2 91 d7 44 3b cf",
+];
+
 #[test]
+#[cfg(feature = "alloc")]
 fn pat_c() {
-    let expected = [
-        "fa b6 58 db 63 e9 4a 24 61 88 bf 7a f6 9a 13 30 45 f4 6e e9
-                84 c5 6e 3c 33 28 ca af 1a a1 a5 83",
-        "d8 48 c5 06 8c ed 73 6f 44 62 15 9b 98 67 fd 4c 20 b8 08 ac
-                c3 d5 bc 48 e0 b0 6b a0 a3 76 2e c4",
-        "c3 89 e5 00 9a e5 71 20 85 4c 2e 8c 64 67 0a c0 13 58 cf 4c
-                1b af 89 44 7a 72 42 34 dc 7c ed 74",
-        "75 d2 f8 6a 2e 64 45 66 72 6b 4f bc fc 56 57 b9 db cf 07 0c
-                7b 0d ca 06 45 0a b2 91 d7 44 3b cf",
-    ];
     for i in 0..4 {
-        let m: Vec<u8> = iter::repeat(0xFF).take(2usize.pow(i) - 1).collect();
+        let m = vec![0xFF; 2usize.pow(i) - 1];
         let len = 41usize.pow(i);
         let c: Vec<u8> = (0..len).map(|j| (j % 251) as u8).collect();
         let result = KangarooTwelve::new_with_customization(c)
             .chain(&m)
-            .finalize_boxed(32);
-        assert_eq!(result, read_bytes(expected[i as usize]));
+            .try_finalize(32)
+            .unwrap();
+        assert_eq!(result, read_bytes(PAT_C[i as usize]));
+    }
+}
+
+#[test]
+fn one_shot() {
+    for i in 0..4 {
+        let m: Vec<u8> = (0..17usize.pow(i)).map(|j| (j % 251) as u8).collect();
+        let expected = read_bytes(PAT_M[i as usize]);
+        assert_eq!(k12::hash_fixed::<32>(&m, b"")[..], expected[..]);
+
+        let mut output = [0u8; 32];
+        k12::hash_into(&m, b"", &mut output);
+        assert_eq!(output[..], expected[..]);
+    }
+
+    for i in 0..4 {
+        let m = vec![0xFF; 2usize.pow(i) - 1];
+        let c: Vec<u8> = (0..41usize.pow(i)).map(|j| (j % 251) as u8).collect();
+        let expected = read_bytes(PAT_C[i as usize]);
+        #[cfg(feature = "alloc")]
+        assert_eq!(k12::hash(&m, &c, 32).unwrap()[..], expected[..]);
+        assert_eq!(k12::hash_fixed::<32>(&m, &c)[..], expected[..]);
+    }
+}
+
+#[test]
+fn hash_const() {
+    // Evaluated at compile time
+    const EMPTY: [u8; 32] = k12::hash_const(b"", b"");
+    const ABC: [u8; 300] = k12::hash_const(b"abc", b"customization");
+    const PERMUTED: [u8; 200] = k12::keccak_p_1600_const([0xA5; 200], 12);
+
+    assert_eq!(
+        EMPTY[..],
+        read_bytes("1AC2D450FC3B4205D19DA7BFCA1B37513C0803577AC7167F06FE2CE1F0EF39E5")[..]
+    );
+    assert_eq!(ABC, *k12::hash_fixed::<300>(b"abc", b"customization"));
+    let mut state = [0xA5; 200];
+    k12::keccak_p_1600_12(&mut state);
+    assert_eq!(PERMUTED[..], state[..]);
+
+    // Up to the largest input that fits in the final node alone
+    let m: Vec<u8> = (0..8192).map(|j| (j % 251) as u8).collect();
+    for &(m_len, c_len) in &[(167, 0), (168, 0), (8191, 0), (8000, 190), (0, 8189)] {
+        let (m, c) = (&m[..m_len], &m[..c_len]);
+        let output: [u8; 64] = k12::hash_const(m, c);
+        assert_eq!(output, *k12::hash_fixed::<64>(m, c), "{} {}", m_len, c_len);
+    }
+    for &rounds in &[0, 1, 14, 24] {
+        let mut state = [0u8; 200];
+        for (j, byte) in state.iter_mut().enumerate() {
+            *byte = j as u8;
+        }
+        let permuted = k12::keccak_p_1600_const(state, rounds);
+        k12::keccak_p_1600(&mut state, rounds);
+        assert_eq!(permuted[..], state[..], "{} rounds", rounds);
+    }
+}
+
+#[test]
+#[should_panic(expected = "input too long for hash_const")]
+fn hash_const_too_long() {
+    let _: [u8; 32] = k12::hash_const(&[0; 8192], b"");
+}
+
+#[test]
+#[cfg(feature = "alloc")]
+fn finalize_into() {
+    for i in 0..4 {
+        let m: Vec<u8> = (0..17usize.pow(i)).map(|j| (j % 251) as u8).collect();
+        let mut output = [0u8; 32];
+        KangarooTwelve::new().chain(&m).finalize_into(&mut output);
+        assert_eq!(output[..], read_bytes(PAT_M[i as usize])[..]);
+        assert_eq!(
+            output,
+            KangarooTwelve::new()
+                .chain(&m)
+                .finalize_fixed::<32>()
+                .into_bytes()
+        );
+    }
+
+    for i in 0..4 {
+        let m = vec![0xFF; 2usize.pow(i) - 1];
+        let c: Vec<u8> = (0..41usize.pow(i)).map(|j| (j % 251) as u8).collect();
+        let mut output = vec![0u8; 32];
+        KangarooTwelve::new_with_customization(&c)
+            .chain(&m)
+            .finalize_into(&mut output);
+        assert_eq!(output[..], read_bytes(PAT_C[i as usize])[..]);
+    }
+
+    KangarooTwelve::new().chain(b"abc").finalize_into(&mut []);
+}
+
+#[test]
+#[cfg(feature = "alloc")]
+fn finalize_into_vec() {
+    let m: Vec<u8> = (0..20000).map(|j| (j % 251) as u8).collect();
+    let mut buf = Vec::new();
+
+    KangarooTwelve::new()
+        .chain(&m)
+        .finalize_into_vec(64, &mut buf);
+    assert_eq!(buf, k12::hash(&m, b"", 64).unwrap());
+    let capacity = buf.capacity();
+    let ptr = buf.as_ptr();
+
+    KangarooTwelve::new()
+        .chain(b"abc")
+        .finalize_into_vec(32, &mut buf);
+    assert_eq!(buf, k12::hash(b"abc", b"", 32).unwrap());
+    assert_eq!(buf.capacity(), capacity);
+    assert_eq!(buf.as_ptr(), ptr);
+
+    KangarooTwelve::new()
+        .chain(&m)
+        .finalize_into_vec(64, &mut buf);
+    assert_eq!(buf, k12::hash(&m, b"", 64).unwrap());
+    assert_eq!(buf.as_ptr(), ptr);
+}
+
+#[test]
+#[cfg(feature = "alloc")]
+fn zero_output_length() {
+    assert_eq!(
+        KangarooTwelve::new().try_finalize(0),
+        Err(Error::ZeroOutputLength)
+    );
+    assert_eq!(k12::hash(b"abc", b"", 0), Err(Error::ZeroOutputLength));
+    assert_eq!(
+        k12::hash_batch(&[b"abc"], b"", 0),
+        Err(Error::ZeroOutputLength)
+    );
+    assert_eq!(
+        KangarooTwelve::new().finalize_with_domain(k12::Domain::new("d"), 0),
+        Err(Error::ZeroOutputLength)
+    );
+    assert_eq!(
+        k12::Kmac12::new(b"key").tag(0),
+        Err(Error::ZeroOutputLength)
+    );
+}
+
+#[test]
+#[cfg(feature = "alloc")]
+fn hash_batch() {
+    let long: Vec<u8> = (0..20000).map(|j| (j % 251) as u8).collect();
+    let messages: Vec<&[u8]> = vec![b"", b"abc", &long, b"abc"];
+    let outputs = k12::hash_batch(&messages, b"c", 48).unwrap();
+    assert_eq!(outputs.len(), messages.len());
+    for (message, output) in messages.iter().zip(&outputs) {
+        assert_eq!(*output, k12::hash(message, b"c", 48).unwrap());
+    }
+
+    assert!(k12::hash_batch(&[], b"", 32).unwrap().is_empty());
+}
+
+#[test]
+#[cfg(feature = "alloc")]
+fn squeezer_partial_reads() {
+    let expected = KangarooTwelve::new()
+        .chain(b"abc")
+        .try_finalize(1000)
+        .unwrap();
+
+    for &step in &[1, 7, 167, 168, 169, 500] {
+        let mut reader = KangarooTwelve::new().chain(b"abc").finalize_xof();
+        let mut output = vec![0u8; 1000];
+        for chunk in output.chunks_mut(step) {
+            reader.read(chunk);
+        }
+        assert_eq!(output[..], expected[..], "step size {}", step);
+    }
+}
+
+#[test]
+#[cfg(feature = "alloc")]
+fn squeezer_clone() {
+    let mut reader = KangarooTwelve::new().chain(b"abc").finalize_xof();
+    let mut prefix = [0u8; 100];
+    reader.read(&mut prefix);
+
+    let mut fork = reader.clone();
+    let mut a = [0u8; 300];
+    let mut b = [0u8; 300];
+    reader.read(&mut a);
+    fork.read(&mut b);
+    assert_eq!(a[..], b[..]);
+
+    let expected = KangarooTwelve::new()
+        .chain(b"abc")
+        .try_finalize(400)
+        .unwrap();
+    assert_eq!(prefix[..], expected[..100]);
+    assert_eq!(a[..], expected[100..]);
+}
+
+#[test]
+#[cfg(feature = "alloc")]
+fn squeezer_fork() {
+    let reader = KangarooTwelve::new().chain(b"abc").finalize_xof();
+    let streams = reader.fork(257);
+    assert_eq!(streams.len(), 257);
+
+    let outputs: Vec<[u8; 32]> = streams
+        .into_iter()
+        .map(|mut stream| {
+            let mut output = [0u8; 32];
+            stream.read(&mut output);
+            output
+        })
+        .collect();
+    for &(i, expected) in &[
+        (
+            0,
+            "D71A71F583CA5BCFF5D4C5C78304B6E3510A599FE5B5BD31B4758064ABF3ED7E",
+        ),
+        (
+            1,
+            "72993D6132D0C562932C959CF7B224F2A8F578612225B8529F5D87FF52CC7FA9",
+        ),
+        (
+            256,
+            "726533802CB1EEBA399453725F7130DDAE71BF38A52009B5B570AF6E3A7B361B",
+        ),
+    ] {
+        assert_eq!(outputs[i][..], read_bytes(expected)[..], "stream {}", i);
+    }
+
+    // No stream repeats another or any block of the sequential output
+    let sequential = KangarooTwelve::new()
+        .chain(b"abc")
+        .try_finalize(168 * 4)
+        .unwrap();
+    for (i, output) in outputs.iter().enumerate() {
+        assert!(outputs[..i].iter().all(|other| other != output));
+        assert!(sequential
+            .chunks(168)
+            .all(|block| block[..32] != output[..]));
+    }
+
+    // Forking does not consume the output of the reader
+    let mut reader = reader;
+    let mut output = [0u8; 168 * 4];
+    reader.read(&mut output);
+    assert_eq!(output[..], sequential[..]);
+    assert!(reader.fork(0).is_empty());
+}
+
+/// `right_encode(x)` as specified for KangarooTwelve.
+#[cfg(feature = "alloc")]
+fn right_encode(x: usize) -> Vec<u8> {
+    let mut bytes: Vec<u8> = x
+        .to_be_bytes()
+        .iter()
+        .copied()
+        .skip_while(|&b| b == 0)
+        .collect();
+    bytes.push(bytes.len() as u8);
+    bytes
+}
+
+/// KangarooTwelve from the chaining values of `LeafHasher`s, assembling the
+/// final node with TurboSHAKE128.
+#[cfg(feature = "alloc")]
+fn k12_from_leaves(factory: k12::LeafHasherFactory, m: &[u8], c: &[u8]) -> Vec<u8> {
+    let mut s = m.to_vec();
+    s.extend_from_slice(c);
+    s.extend_from_slice(&right_encode(c.len()));
+
+    let mut final_node = k12::TurboShake128::new_with_domain(0x07).unwrap();
+    if s.len() > factory.block_size() {
+        let (first, rest) = s.split_at(factory.block_size());
+        final_node = k12::TurboShake128::new_with_domain(0x06)
+            .unwrap()
+            .chain(first)
+            .chain([3, 0, 0, 0, 0, 0, 0, 0]);
+        let chunks = rest.chunks(factory.block_size());
+        let n = chunks.len();
+        for chunk in chunks {
+            let mut leaf = factory.leaf_hasher();
+            for piece in chunk.chunks(1000) {
+                leaf.update(piece).unwrap();
+            }
+            assert_eq!(leaf.len(), chunk.len());
+            final_node.update(leaf.finalize());
+        }
+        final_node.update(right_encode(n));
+        final_node.update([0xFF, 0xFF]);
+    } else {
+        final_node.update(&s);
+    }
+
+    let mut output = vec![0u8; 64];
+    final_node.finalize_xof().read(&mut output);
+    output
+}
+
+#[test]
+#[cfg(feature = "alloc")]
+fn leaf_hasher() {
+    for &len in &[0, 8191, 8192, 16383, 16384, 100_000] {
+        let m: Vec<u8> = (0..len).map(|j| (j % 251) as u8).collect();
+        for &c in &[&b""[..], b"customization"] {
+            let factory = KangarooTwelve::new().leaf_hasher_factory();
+            let expected = k12::hash(&m, c, 64).unwrap();
+            assert_eq!(k12_from_leaves(factory, &m, c), expected, "{}", len);
+
+            let hasher = KangarooTwelve::with_block_size_and_customization(1000, c).unwrap();
+            let factory = hasher.leaf_hasher_factory();
+            assert_eq!(factory.block_size(), 1000);
+            let expected = hasher.chain(&m).try_finalize(64).unwrap();
+            assert_eq!(k12_from_leaves(factory, &m, c), expected, "{}", len);
+        }
+    }
+
+    let factory = k12::LeafHasherFactory::with_block_size(200).unwrap();
+    let mut leaf = factory.leaf_hasher();
+    assert!(leaf.is_empty());
+    leaf.update([0u8; 150]).unwrap();
+    assert_eq!(leaf.update([0u8; 51]), Err(Error::CapacityExceeded));
+    assert_eq!(leaf.len(), 150);
+    assert_eq!(
+        k12::LeafHasherFactory::with_block_size(167).unwrap_err(),
+        Error::InvalidBlockSize
+    );
+    assert_eq!(k12::LeafHasherFactory::new().block_size(), 8192);
+}
+
+#[test]
+#[cfg(feature = "alloc")]
+fn update_with_progress() {
+    let m: Vec<u8> = (0..102_400).map(|j| (j % 251) as u8).collect();
+    let expected = k12::hash(&m, b"", 32).unwrap();
+
+    let mut reports = Vec::new();
+    let mut hasher = KangarooTwelve::new();
+    hasher.update_with_progress(&m, |n| reports.push(n));
+    let mut expected_reports: Vec<u64> = (1..=12).map(|k| k * 8192).collect();
+    expected_reports.push(102_400);
+    assert_eq!(reports, expected_reports);
+    assert_eq!(hasher.try_finalize(32).unwrap(), expected);
+
+    // Chunks completed across calls are reported by the call completing them
+    let mut reports = Vec::new();
+    let mut hasher = KangarooTwelve::new();
+    hasher.update_with_progress(&m[..5000], |n| reports.push(n));
+    hasher.update_with_progress(&m[5000..16384], |n| reports.push(n));
+    hasher.update_with_progress([], |n| reports.push(n));
+    hasher.update_with_progress(&m[16384..], |n| reports.push(n));
+    assert_eq!(reports[..3], [5000, 8192, 16384]);
+    assert_eq!(reports[3..], expected_reports[2..]);
+    assert_eq!(hasher.try_finalize(32).unwrap(), expected);
+}
+
+#[test]
+#[cfg(feature = "alloc")]
+fn incremental_updates() {
+    let m: Vec<u8> = (0..100_000).map(|j| (j % 251) as u8).collect();
+    let expected = k12::hash(&m, b"c", 32).unwrap();
+
+    for &step in &[1, 167, 168, 8191, 8192, 8193, 3 * 8192 + 5] {
+        let mut hasher = KangarooTwelve::new_with_customization(b"c");
+        for chunk in m.chunks(step) {
+            hasher.update(chunk);
+        }
+        let mut output = [0u8; 32];
+        hasher.finalize_xof().read(&mut output);
+        assert_eq!(output[..], expected[..], "step {}", step);
+    }
+}
+
+#[test]
+#[cfg(feature = "alloc")]
+fn fixed_output() {
+    use k12::{K12_256, K12_512};
+
+    // Source: reference paper
+    assert_eq!(
+        K12_256::new().finalize()[..],
+        read_bytes(
+            "1a c2 d4 50 fc 3b 42 05 d1 9d a7 bf ca
+                1b 37 51 3c 08 03 57 7a c7 16 7f 06 fe 2c e1 f0 ef 39 e5"
+        )[..]
+    );
+    assert_eq!(
+        K12_512::new().chain(b"").finalize()[..],
+        read_bytes(
+            "1a c2 d4 50 fc 3b 42 05 d1 9d a7 bf ca
+                1b 37 51 3c 08 03 57 7a c7 16 7f 06 fe 2c e1 f0 ef 39 e5 42 69 c0 56 b8 c8 2e
+                48 27 60 38 b6 d2 92 96 6c c0 7a 3d 46 45 27 2e 31 ff 38 50 81 39 eb 0a 71"
+        )[..]
+    );
+
+    let mut hasher = K12_256::default();
+    hasher.update([0u8, 1, 2]);
+    let fork = hasher.clone();
+    hasher.update([3u8, 4]);
+    assert_eq!(hasher.finalize(), fork.chain([3u8, 4]).finalize());
+
+    let m: Vec<u8> = (0..17).map(|j| (j % 251) as u8).collect();
+    assert_eq!(
+        K12_256::new().chain(&m).finalize()[..],
+        read_bytes(PAT_M[1])[..]
+    );
+
+    assert_eq!(
+        format!("{:x}", K12_256::new().finalize()),
+        "1ac2d450fc3b4205d19da7bfca1b37513c0803577ac7167f06fe2ce1f0ef39e5"
+    );
+    assert_eq!(
+        format!("{:X}", K12_256::new().finalize()),
+        "1AC2D450FC3B4205D19DA7BFCA1B37513C0803577AC7167F06FE2CE1F0EF39E5"
+    );
+    let hash = K12_512::new().finalize();
+    assert_eq!(hash.to_string(), format!("{:x}", hash));
+    assert_eq!(
+        hash.to_string(),
+        "1ac2d450fc3b4205d19da7bfca1b37513c0803577ac7167f06fe2ce1f0ef39e5\
+         4269c056b8c82e48276038b6d292966cc07a3d4645272e31ff38508139eb0a71"
+    );
+}
+
+#[test]
+#[cfg(feature = "alloc")]
+fn hasher_clone() {
+    let prefix: Vec<u8> = (0..20000).map(|j| (j % 251) as u8).collect();
+    let hasher = KangarooTwelve::new_with_customization(b"c").chain(&prefix);
+    let fork = hasher.clone();
+
+    let a = hasher.chain(b"a").finalize_fixed::<32>();
+    let b = fork.chain(b"b").finalize_fixed::<32>();
+    assert_ne!(a, b);
+
+    let fresh = |suffix: &[u8]| {
+        KangarooTwelve::new_with_customization(b"c")
+            .chain(&prefix)
+            .chain(suffix)
+            .finalize_fixed::<32>()
+    };
+    assert_eq!(a, fresh(b"a"));
+    assert_eq!(b, fresh(b"b"));
+}
+
+#[test]
+#[cfg(feature = "serde")]
+fn serde_round_trip() {
+    let m: Vec<u8> = (0..20000).map(|j| (j % 251) as u8).collect();
+    let hasher = KangarooTwelve::new_with_customization(b"c").chain(&m);
+    let json = serde_json::to_string(&hasher).unwrap();
+    let restored: KangarooTwelve = serde_json::from_str(&json).unwrap();
+    let expected = hasher.chain(b"def").finalize_fixed::<32>();
+    assert_eq!(restored.chain(b"def").finalize_fixed::<32>(), expected);
+
+    // Tampering with the Keccak state results in a different output
+    let mut value: serde_json::Value = serde_json::from_str(&json).unwrap();
+    value["leaf"][0] = (value["leaf"][0].as_u64().unwrap() ^ 1).into();
+    let restored: KangarooTwelve = serde_json::from_value(value).unwrap();
+    assert_ne!(restored.chain(b"def").finalize_fixed::<32>(), expected);
+
+    // Chunk indices are not limited to the range of a 32-bit `usize`
+    let mut value: serde_json::Value = serde_json::from_str(&json).unwrap();
+    value["chunk"] = (1u64 << 40).into();
+    let restored: KangarooTwelve = serde_json::from_value(value).unwrap();
+    assert_ne!(restored.chain(b"def").finalize_fixed::<32>(), expected);
+
+    // The parallelism is kept, and states without it are unbounded
+    let limited = KangarooTwelve::new_with_customization(b"c")
+        .with_parallelism(1)
+        .chain(&m);
+    let checkpoint = serde_json::to_string(&limited).unwrap();
+    let restored: KangarooTwelve = serde_json::from_str(&checkpoint).unwrap();
+    assert_eq!(serde_json::to_string(&restored).unwrap(), checkpoint);
+    let mut value: serde_json::Value = serde_json::from_str(&json).unwrap();
+    assert!(value["parallelism"].is_null());
+    value.as_object_mut().unwrap().remove("parallelism");
+    let restored: KangarooTwelve = serde_json::from_value(value).unwrap();
+    assert_eq!(serde_json::to_string(&restored).unwrap(), json);
+
+    let hasher = KangarooTwelve::with_block_size(1000).unwrap().chain(&m);
+    let checkpoint = serde_json::to_string(&hasher).unwrap();
+    let restored: KangarooTwelve = serde_json::from_str(&checkpoint).unwrap();
+    assert_eq!(
+        restored.chain(b"def").finalize_fixed::<32>(),
+        hasher.chain(b"def").finalize_fixed::<32>()
+    );
+
+    let tampered = |key: &str, v: serde_json::Value| {
+        let mut value: serde_json::Value = serde_json::from_str(&json).unwrap();
+        value[key] = v;
+        serde_json::from_value::<KangarooTwelve>(value).is_err()
+    };
+    assert!(tampered("version", 2.into()));
+    assert!(tampered("block_size", 167.into()));
+    assert!(tampered("leaf_pos", 169.into()));
+    assert!(tampered("chunk_len", 8193.into()));
+    assert!(tampered("final_node", vec![0; 199].into()));
+    assert!(tampered("customization", vec![256].into()));
+}
+
+#[test]
+#[cfg(feature = "bytes")]
+fn update_buf() {
+    use bytes::{Buf, BufMut, BytesMut};
+
+    let m: Vec<u8> = (0..20000).map(|j| (j % 251) as u8).collect();
+    let mut buf = BytesMut::new();
+    buf.put_slice(&m[..9000]);
+    let first = buf.split().freeze();
+    buf.put_slice(&m[9000..]);
+    let second = buf.freeze();
+
+    let mut hasher = KangarooTwelve::new();
+    let mut chain = first.chain(second);
+    hasher.update_buf(&mut chain);
+    assert!(!chain.has_remaining());
+    assert_eq!(
+        hasher.finalize_fixed::<32>(),
+        KangarooTwelve::new().chain(&m).finalize_fixed::<32>()
+    );
+}
+
+#[test]
+#[cfg(feature = "bytes")]
+fn read_into_buf() {
+    use bytes::BufMut;
+
+    let mut expected = [0u8; 1000];
+    KangarooTwelve::new()
+        .chain(b"abc")
+        .finalize_xof()
+        .read(&mut expected);
+
+    let mut squeezer = KangarooTwelve::new().chain(b"abc").finalize_xof();
+    let mut buf = bytes::BytesMut::new();
+    buf.put_u8(0xFF);
+    squeezer.read_into_buf(&mut buf, 100);
+    squeezer.read_into_buf(&mut buf, 0);
+    squeezer.read_into_buf(&mut buf, 900);
+    assert_eq!(buf[0], 0xFF);
+    assert_eq!(buf[1..], expected[..]);
+}
+
+#[test]
+#[cfg(feature = "std")]
+fn io_write() {
+    let m: Vec<u8> = (0..100_000).map(|j| (j % 251) as u8).collect();
+    let mut hasher = KangarooTwelve::new_with_customization(b"c");
+    let copied = std::io::copy(&mut std::io::Cursor::new(&m), &mut hasher).unwrap();
+    assert_eq!(copied, m.len() as u64);
+
+    let mut output = [0u8; 32];
+    hasher.finalize_xof().read(&mut output);
+    assert_eq!(output[..], k12::hash(&m, b"c", 32).unwrap()[..]);
+}
+
+#[tokio::test]
+#[cfg(feature = "tokio")]
+async fn async_write() {
+    use tokio::io::AsyncWriteExt;
+
+    let m: Vec<u8> = (0..100_000).map(|j| (j % 251) as u8).collect();
+    let (mut writer, mut reader) = tokio::io::duplex(4096);
+    let input = m.clone();
+    let feed = async move {
+        writer.write_all(&input).await.unwrap();
+        writer.shutdown().await.unwrap();
+    };
+    let mut absorber = k12::AsyncK12Absorber::new(KangarooTwelve::new_with_customization(b"c"));
+    let absorb = tokio::io::copy(&mut reader, &mut absorber);
+    let (_, copied) = tokio::join!(feed, absorb);
+    assert_eq!(copied.unwrap(), m.len() as u64);
+
+    let mut output = [0u8; 32];
+    absorber.into_inner().finalize_xof().read(&mut output);
+    assert_eq!(output[..], k12::hash(&m, b"c", 32).unwrap()[..]);
+}
+
+#[tokio::test]
+#[cfg(feature = "tokio")]
+async fn async_read() {
+    use tokio::io::{AsyncReadExt, AsyncWriteExt};
+
+    let mut absorber = k12::AsyncK12Absorber::new(KangarooTwelve::new());
+    absorber.write_all(b"abc").await.unwrap();
+    let squeezer = absorber.into_inner().finalize_xof();
+    let mut output = Vec::new();
+    tokio::io::copy(&mut squeezer.take(1000), &mut output)
+        .await
+        .unwrap();
+    assert_eq!(output, k12::hash(b"abc", b"", 1000).unwrap());
+}
+
+#[test]
+#[cfg(feature = "std")]
+fn io_read() {
+    use std::io::Read;
+
+    let reader = KangarooTwelve::new().chain(b"abc").finalize_xof();
+    let mut output = Vec::new();
+    std::io::copy(&mut reader.take(1000), &mut output).unwrap();
+    assert_eq!(output, k12::hash(b"abc", b"", 1000).unwrap());
+}
+
+#[test]
+#[cfg(feature = "std")]
+fn output_into() {
+    let mut expected = [0u8; 1000];
+    KangarooTwelve::new()
+        .chain(b"abc")
+        .finalize_xof()
+        .read(&mut expected);
+
+    let mut squeezer = KangarooTwelve::new().chain(b"abc").finalize_xof();
+    let mut output = vec![0xFF];
+    squeezer.output_into(&mut output, 100).unwrap();
+    squeezer.output_into(&mut output, 0).unwrap();
+    squeezer.output_into(&mut output, 900).unwrap();
+    assert_eq!(output[0], 0xFF);
+    assert_eq!(output[1..], expected[..]);
+
+    let mut full = [0u8; 100];
+    let err = squeezer.output_into(&mut &mut full[..], 200).unwrap_err();
+    assert_eq!(err.kind(), std::io::ErrorKind::WriteZero);
+}
+
+#[test]
+#[cfg(feature = "std")]
+fn hash_file() {
+    use std::io::ErrorKind;
+
+    let path = std::env::temp_dir().join(format!("k12-hash-file-{}", std::process::id()));
+    let m: Vec<u8> = (0..200_000).map(|j| (j % 251) as u8).collect();
+    std::fs::write(&path, &m).unwrap();
+    let output = k12::hash_file(&path, b"c", 32);
+    let zero = k12::hash_file(&path, b"c", 0);
+    std::fs::remove_file(&path).unwrap();
+
+    assert_eq!(output.unwrap(), k12::hash(&m, b"c", 32).unwrap());
+    assert_eq!(zero.unwrap_err().kind(), ErrorKind::InvalidInput);
+    assert_eq!(
+        k12::hash_file(&path, b"", 32).unwrap_err().kind(),
+        ErrorKind::NotFound
+    );
+}
+
+#[test]
+#[cfg(feature = "alloc")]
+fn reset() {
+    let mut hasher = KangarooTwelve::new();
+    hasher.update(vec![0xAB; 20000]);
+    hasher.reset();
+    hasher.update(b"abc");
+    assert_eq!(
+        hasher.finalize_fixed::<64>(),
+        KangarooTwelve::new().chain(b"abc").finalize_fixed::<64>()
+    );
+
+    let mut hasher = KangarooTwelve::new_with_customization(b"c");
+    hasher.update(b"xyz");
+    hasher.reset();
+    assert_eq!(
+        hasher.finalize_fixed::<64>(),
+        KangarooTwelve::new_with_customization(b"c").finalize_fixed::<64>()
+    );
+}
+
+#[test]
+#[cfg(feature = "alloc")]
+fn block_size() {
+    // Computed with an independent implementation of the tree hashing mode
+    let expected = [
+        (
+            168,
+            "83dbc07c7b1d86be16fb53d04e0df30bfe100a523454edad9a304bd32ae2a899",
+        ),
+        (
+            1000,
+            "02c1cb6b42e2af26b8d04da6b3e92b6ef42456121a6e45c94d332b8bd51fd5e7",
+        ),
+        (
+            4096,
+            "7fdc95cdcf3a6fa5d1ff125999cc2c53ad36d05d3ce26ce761d3cb2916900171",
+        ),
+    ];
+    let m: Vec<u8> = (0..20000).map(|j| (j % 251) as u8).collect();
+    let default = KangarooTwelve::new_with_customization(b"c")
+        .chain(&m)
+        .finalize_fixed::<32>();
+    for &(block_size, output) in &expected {
+        let hasher = KangarooTwelve::with_block_size_and_customization(block_size, b"c").unwrap();
+        let result = hasher.chain(&m).finalize_fixed::<32>();
+        assert_eq!(
+            result[..],
+            read_bytes(output)[..],
+            "block size {}",
+            block_size
+        );
+        assert_ne!(result, default);
+    }
+
+    let hasher = KangarooTwelve::with_block_size(8192).unwrap();
+    assert_eq!(
+        hasher.chain(&m).finalize_fixed::<32>(),
+        KangarooTwelve::new().chain(&m).finalize_fixed::<32>()
+    );
+
+    // Inputs which fit in a single block are not separated by the block size
+    let hasher = KangarooTwelve::with_block_size(168).unwrap();
+    assert_eq!(
+        hasher.chain(b"abc").finalize_fixed::<32>(),
+        KangarooTwelve::new().chain(b"abc").finalize_fixed::<32>()
+    );
+
+    assert_eq!(
+        KangarooTwelve::with_block_size(167).unwrap_err(),
+        Error::InvalidBlockSize
+    );
+}
+
+#[test]
+fn k12_output() {
+    use core::convert::TryFrom;
+    use k12::K12Output;
+
+    let output = k12::hash_fixed::<4>(b"", b"");
+    assert_eq!(output[..], read_bytes("1ac2d450")[..]);
+    assert_eq!(format!("{:x}", output), "1ac2d450");
+    assert_eq!(format!("{:X}", output), "1AC2D450");
+    assert_eq!(format!("{}", output), "1ac2d450");
+    assert_eq!(format!("{:?}", output), "K12Output(1ac2d450)");
+
+    assert_eq!(K12Output::<4>::try_from(&output[..]).unwrap(), output);
+    assert!(K12Output::<4>::try_from(&output[..3]).is_err());
+    assert_eq!(K12Output::from(output.clone().into_bytes()), output);
+}
+
+#[test]
+fn k12_output_ct_eq() {
+    let output = k12::hash_fixed::<32>(b"abc", b"");
+    assert!(output.ct_eq(&k12::hash_fixed(b"abc", b"")));
+    assert!(!output.ct_eq(&k12::hash_fixed(b"abd", b"")));
+    assert!(!output.ct_eq(&k12::hash_fixed(b"abc", b"d")));
+
+    // Only the last byte differs
+    let mut bytes = output.clone().into_bytes();
+    bytes[31] ^= 1;
+    assert!(!output.ct_eq(&bytes.into()));
+
+    assert!(k12::ct_eq_slice(&output, &output.clone().into_bytes()));
+    assert!(!k12::ct_eq_slice(&output, &bytes));
+    assert!(!k12::ct_eq_slice(&output, &output[..31]));
+    assert!(k12::ct_eq_slice(&[], &[]));
+}
+
+#[test]
+fn k12_output_from_hex() {
+    use k12::K12Output;
+
+    let output = k12::hash_fixed::<32>(b"", b"");
+    for hex in &[
+        "1ac2d450fc3b4205d19da7bfca1b37513c0803577ac7167f06fe2ce1f0ef39e5",
+        "1AC2D450FC3B4205D19DA7BFCA1B37513C0803577AC7167F06FE2CE1F0EF39E5",
+        "1a c2 d4 50 fc 3b 42 05 d1 9d a7 bf ca
+            1b 37 51 3c 08 03 57 7a c7 16 7f 06 fe 2c e1 f0 ef 39 e5",
+        "1a:c2:d4:50:fc:3b:42:05:d1:9d:a7:bf:ca:1b:37:51:3c:08:03:57:7a:c7:16:7f:06:fe:2c:e1:f0:ef:39:e5",
+    ] {
+        assert_eq!(K12Output::<32>::from_hex(hex), Ok(output.clone()));
+    }
+
+    // Formatting and parsing are inverses
+    for len in 0..64 {
+        let m: Vec<u8> = (0..len).collect();
+        let output = k12::hash_fixed::<32>(&m, b"");
+        assert_eq!(format!("{:x}", output).parse(), Ok(output.clone()));
+        assert_eq!(K12Output::from_hex(&format!("{:X}", output)), Ok(output));
+    }
+
+    assert_eq!(K12Output::<0>::from_hex(" "), Ok(K12Output::from([])));
+    assert_eq!(
+        K12Output::<2>::from_hex("1ag2"),
+        Err(Error::InvalidHexCharacter)
+    );
+    assert_eq!(
+        K12Output::<2>::from_hex("1a-c2"),
+        Err(Error::InvalidHexCharacter)
+    );
+    for hex in &["1ac", "1ac2d4", "1ac2d", ""] {
+        assert_eq!(
+            K12Output::<2>::from_hex(hex),
+            Err(Error::InvalidOutputLength)
+        );
+    }
+}
+
+#[test]
+#[cfg(feature = "alloc")]
+fn domain() {
+    use k12::Domain;
+
+    const A: Domain = Domain::new("MyApp v1 KDF");
+    const B: Domain = Domain::new("MyApp v1 MAC");
+
+    let m: Vec<u8> = (0..20000).map(|j| (j % 251) as u8).collect();
+    let a = KangarooTwelve::new()
+        .chain(&m)
+        .finalize_with_domain(A, 32)
+        .unwrap();
+    let b = KangarooTwelve::new()
+        .chain(&m)
+        .finalize_with_domain(B, 32)
+        .unwrap();
+    assert_ne!(a, b);
+
+    // Domains take the place of the customization string
+    assert_eq!(a, k12::hash(&m, b"MyApp v1 KDF", 32).unwrap());
+    let hasher = KangarooTwelve::new_with_customization(b"ignored").chain(&m);
+    assert_eq!(hasher.finalize_with_domain(A, 32).unwrap(), a);
+    assert_eq!(A.as_str(), "MyApp v1 KDF");
+
+    // `finalize_with` takes domains and plain customization strings alike
+    let hasher = KangarooTwelve::new().chain(&m);
+    assert_eq!(hasher.clone().finalize_with(A, 32).unwrap(), a);
+    assert_eq!(hasher.clone().finalize_with(B, 32).unwrap(), b);
+    assert_eq!(
+        hasher.clone().finalize_with(b"MyApp v1 KDF", 32).unwrap(),
+        a
+    );
+    assert_eq!(hasher.clone().finalize_with("MyApp v1 KDF", 32).unwrap(), a);
+    let bytes: &[u8] = A.as_bytes();
+    assert_eq!(hasher.clone().finalize_with(bytes, 32).unwrap(), a);
+    assert_eq!(
+        hasher.finalize_with(&b""[..], 32).unwrap(),
+        k12::hash(&m, b"", 32).unwrap()
+    );
+}
+
+#[test]
+#[cfg(feature = "alloc")]
+fn update_encoded() {
+    let hash_parts = |parts: &[&[u8]]| {
+        let mut hasher = KangarooTwelve::new();
+        for part in parts {
+            hasher.update_encoded(part);
+        }
+        hasher.try_finalize(32).unwrap()
+    };
+
+    // `encode_string("a") || encode_string("b")`
+    let ab = hash_parts(&[b"a", b"b"]);
+    assert_eq!(ab, k12::hash(b"\x01\x08a\x01\x08b", b"", 32).unwrap());
+    assert_ne!(ab, hash_parts(&[b"ab"]));
+    assert_ne!(ab, hash_parts(&[b"ab", b""]));
+    assert_ne!(hash_parts(&[b"a", b"bc"]), hash_parts(&[b"ab", b"c"]));
+
+    let long = [0xA5; 300];
+    let mut expected = vec![0x02, 0x09, 0x60];
+    expected.extend_from_slice(&long);
+    assert_eq!(hash_parts(&[&long]), k12::hash(&expected, b"", 32).unwrap());
+
+    // Fields with different tags differ even if their contents are equal
+    let field = |tag: u8, data: &[u8]| {
+        let mut hasher = KangarooTwelve::new();
+        hasher.update_field(tag, data);
+        hasher.try_finalize(32).unwrap()
+    };
+    assert_eq!(
+        field(7, b"a"),
+        k12::hash(b"\x07\x01\x08a", b"", 32).unwrap()
+    );
+    assert_ne!(field(1, b"a"), field(2, b"a"));
+}
+
+#[test]
+#[cfg(feature = "alloc")]
+fn finalize_multi() {
+    let m: Vec<u8> = (0..20000).map(|j| (j % 251) as u8).collect();
+    let read = |mut stream: k12::Squeezer| {
+        let mut output = vec![0u8; 4096];
+        stream.read(&mut output);
+        output
+    };
+    let outputs: Vec<_> = KangarooTwelve::new_with_customization(b"ignored")
+        .chain(&m)
+        .finalize_multi(b"transcript", 4)
+        .into_iter()
+        .map(read)
+        .collect();
+    assert_eq!(outputs.len(), 4);
+
+    // The streams are the forks of the output for the customization
+    let forks = KangarooTwelve::new_with_customization(b"transcript")
+        .chain(&m)
+        .finalize_xof()
+        .fork(4);
+    for (output, fork) in outputs.iter().zip(forks) {
+        assert_eq!(*output, read(fork));
+    }
+
+    // About half of the bits of any two streams, or of a stream and the
+    // output itself, agree
+    let output = k12::hash(&m, b"transcript", 4096).unwrap();
+    for (i, a) in outputs.iter().enumerate() {
+        for b in outputs[i + 1..].iter().chain([&output]) {
+            let differing: u32 = a.iter().zip(b).map(|(x, y)| (x ^ y).count_ones()).sum();
+            let fraction = f64::from(differing) / (8.0 * 4096.0);
+            assert!((fraction - 0.5).abs() < 0.02, "{}", fraction);
+        }
+    }
+}
+
+#[test]
+#[cfg(feature = "alloc")]
+fn update_iter() {
+    let m: Vec<u8> = (0..20000).map(|j| (j % 251) as u8).collect();
+    let slices: Vec<&[u8]> = vec![&m[..1], &m[1..8192], &m[8192..8193], &[], &m[8193..]];
+    let expected = k12::hash(&m, b"c", 32).unwrap();
+
+    let mut hasher = KangarooTwelve::new_with_customization(b"c");
+    hasher.update_iter(slices.iter());
+    assert_eq!(hasher.finalize_fixed::<32>()[..], expected[..]);
+
+    let hasher = KangarooTwelve::new_with_customization(b"c").chain_iter(m.chunks(1000));
+    assert_eq!(hasher.finalize_fixed::<32>()[..], expected[..]);
+
+    let hasher =
+        KangarooTwelve::new_with_customization(b"c").chain_iter(core::iter::empty::<&[u8]>());
+    assert_eq!(
+        hasher.finalize_fixed::<32>()[..],
+        k12::hash(b"", b"c", 32).unwrap()[..]
+    );
+}
+
+#[test]
+#[cfg(feature = "alloc")]
+fn parallelism() {
+    let m: Vec<u8> = (0..100_000).map(|j| (j % 251) as u8).collect();
+    let expected = k12::hash(&m, b"c", 32).unwrap();
+
+    for &parallelism in &[0, 1, 2, 4, usize::MAX] {
+        let hasher = KangarooTwelve::new_with_customization(b"c").with_parallelism(parallelism);
+        assert_eq!(hasher.chain(&m).finalize_fixed::<32>()[..], expected[..]);
+
+        let mut hasher = KangarooTwelve::new_with_customization(b"c");
+        hasher.set_parallelism(parallelism);
+        for chunk in m.chunks(30_000) {
+            hasher.update(chunk);
+        }
+        assert_eq!(hasher.finalize_fixed::<32>()[..], expected[..]);
+    }
+}
+
+#[test]
+#[cfg(feature = "alloc")]
+fn builder() {
+    use k12::KangarooTwelveBuilder;
+
+    let m: Vec<u8> = (0..100_000).map(|j| (j % 251) as u8).collect();
+    let a = KangarooTwelveBuilder::new().customization(b"c");
+    let b = KangarooTwelveBuilder::new()
+        .block_size(1000)
+        .unwrap()
+        .customization(b"c");
+
+    // Building twice gives equivalent instances
+    let output = a.build().chain(&m).finalize_fixed::<32>();
+    assert_eq!(a.build().chain(&m).finalize_fixed::<32>(), output);
+    assert_eq!(output[..], k12::hash(&m, b"c", 32).unwrap()[..]);
+
+    let other = b.build().chain(&m).finalize_fixed::<32>();
+    assert_eq!(b.build().chain(&m).finalize_fixed::<32>(), other);
+    assert_ne!(other, output);
+    assert_eq!(
+        other,
+        KangarooTwelve::with_block_size_and_customization(1000, b"c")
+            .unwrap()
+            .chain(&m)
+            .finalize_fixed::<32>()
+    );
+
+    // Parallelism does not affect the output
+    for &parallelism in &[0, 1, 2, 3] {
+        let hasher = a.clone().parallelism(parallelism).build();
+        assert_eq!(hasher.chain(&m).finalize_fixed::<32>(), output);
+    }
+
+    assert_eq!(
+        KangarooTwelveBuilder::new().block_size(100).unwrap_err(),
+        Error::InvalidBlockSize
+    );
+}
+
+fn stack_k12<const CAP: usize>(m: &[u8], c: &[u8], output_len: usize) -> Vec<u8> {
+    let mut output = vec![0u8; output_len];
+    StackKangarooTwelve::<CAP>::new_with_customization(c)
+        .unwrap()
+        .chain(m)
+        .unwrap()
+        .finalize_xof()
+        .read(&mut output);
+    output
+}
+
+#[test]
+fn stack_empty() {
+    assert_eq!(
+        stack_k12::<0>(b"", b"", 32)[..],
+        read_bytes(
+            "1a c2 d4 50 fc 3b 42 05 d1 9d a7 bf ca
+                1b 37 51 3c 08 03 57 7a c7 16 7f 06 fe 2c e1 f0 ef 39 e5"
+        )[..]
+    );
+}
+
+#[test]
+fn stack_pat_m() {
+    for i in 0..5 {
+        let len = 17usize.pow(i);
+        let m: Vec<u8> = (0..len).map(|j| (j % 251) as u8).collect();
+        let result = stack_k12::<{ 1 << 17 }>(&m, b"", 32);
+        assert_eq!(result[..], read_bytes(PAT_M[i as usize])[..]);
+    }
+}
+
+#[test]
+fn stack_pat_c() {
+    for i in 0..4 {
+        let m = vec![0xFF; 2usize.pow(i) - 1];
+        let len = 41usize.pow(i);
+        let c: Vec<u8> = (0..len).map(|j| (j % 251) as u8).collect();
+        let result = stack_k12::<{ 1 << 17 }>(&m, &c, 32);
+        assert_eq!(result[..], read_bytes(PAT_C[i as usize])[..]);
+    }
+}
+
+#[test]
+fn stack_capacity() {
+    assert_eq!(
+        StackKangarooTwelve::<4>::new_with_customization(b"abcde").unwrap_err(),
+        Error::CapacityExceeded
+    );
+
+    let mut k12 = StackKangarooTwelve::<4>::new_with_customization(b"ab").unwrap();
+    k12.update(b"c").unwrap();
+    assert_eq!(k12.update(b"de"), Err(Error::CapacityExceeded));
+    k12.update(b"d").unwrap();
+    assert_eq!(k12.update(b"e"), Err(Error::CapacityExceeded));
+}
+
+#[test]
+#[cfg(all(feature = "alloc", feature = "digest"))]
+fn digest_traits() {
+    use k12::{
+        digest::{FixedOutput, Reset, Update, XofReader},
+        K12_256, K12_512,
+    };
+
+    fn xof<D: Update + ExtendableOutput>(hasher: D, input: &[u8]) -> [u8; 32] {
+        let mut output = [0u8; 32];
+        hasher.chain(input).finalize_xof().read(&mut output);
+        output
+    }
+
+    let expected = KangarooTwelve::new_with_customization(b"c")
+        .chain(b"abc")
+        .finalize_boxed(64);
+
+    let hasher = KangarooTwelve::new_with_customization(b"c");
+    assert_eq!(xof(hasher, b"abc")[..], expected[..32]);
+
+    let mut hasher = KangarooTwelve::new_with_customization(b"c");
+
+    Update::update(&mut hasher, b"xyz");
+    Reset::reset(&mut hasher);
+    Update::update(&mut hasher, b"abc");
+    assert_eq!(hasher.finalize_boxed_reset(64), expected);
+    Update::update(&mut hasher, b"abc");
+    assert_eq!(hasher.finalize_boxed(64), expected);
+
+    let empty = KangarooTwelve::new().finalize_boxed(64);
+    assert_eq!(K12_256::new().chain(b"").finalize_fixed()[..], empty[..32]);
+    assert_eq!(K12_512::new().chain(b"").finalize_fixed()[..], empty[..]);
+}
+
+#[test]
+#[cfg(all(feature = "alloc", feature = "digest"))]
+fn digest_fixed_output() {
+    use k12::{
+        digest::{generic_array::typenum::Unsigned, BlockInput, Digest, FixedOutput},
+        K12_256, K12_512,
+    };
+
+    // Bounds required by HMAC
+    fn digest<D: Digest + BlockInput + Clone>(m: &[u8]) -> Vec<u8> {
+        assert_eq!(D::BlockSize::USIZE, 168);
+        D::digest(m).to_vec()
+    }
+
+    for i in 0..4 {
+        let m: Vec<u8> = (0..17usize.pow(i)).map(|j| (j % 251) as u8).collect();
+        assert_eq!(digest::<K12_256>(&m), read_bytes(PAT_M[i as usize]));
+        assert_eq!(
+            digest::<K12_512>(&m),
+            KangarooTwelve::new().chain(&m).try_finalize(64).unwrap()
+        );
+    }
+
+    let mut output = Default::default();
+    FixedOutput::finalize_into(K12_256::new(), &mut output);
+    assert_eq!(output[..], K12_256::new().finalize()[..]);
+}
+
+#[test]
+#[cfg(all(feature = "alloc", feature = "digest"))]
+fn xof_reader() {
+    use k12::{digest::XofReader, MarsupilamiFourteen, TurboShake128, TurboShake256};
+
+    fn kdf<R: XofReader + ?Sized>(reader: &mut R, keys: &mut [[u8; 32]]) {
+        for key in keys {
+            reader.read(key);
+        }
+    }
+
+    // All of the XOFs share the same reader type
+    let mut readers: Vec<Box<dyn XofReader>> = vec![
+        Box::new(KangarooTwelve::new().chain(b"abc").finalize_xof()),
+        Box::new(MarsupilamiFourteen::new().chain(b"abc").finalize_xof()),
+        Box::new(TurboShake128::new().chain(b"abc").finalize_xof()),
+        Box::new(TurboShake256::new().chain(b"abc").finalize_xof()),
+    ];
+    let mut outputs = Vec::new();
+    for reader in readers.iter_mut() {
+        let mut keys = [[0u8; 32]; 3];
+        kdf(reader.as_mut(), &mut keys);
+        outputs.push(keys.concat());
+    }
+
+    let mut reader = KangarooTwelve::new().chain(b"abc").finalize_xof();
+    let mut keys = [[0u8; 32]; 3];
+    kdf(&mut reader, &mut keys);
+    assert_eq!(outputs[0], keys.concat());
+    assert_eq!(outputs[0], k12::hash(b"abc", b"", 96).unwrap());
+    for (i, a) in outputs.iter().enumerate() {
+        for b in &outputs[i + 1..] {
+            assert_ne!(a, b);
+        }
+    }
+}
+
+#[test]
+#[cfg(all(feature = "alloc", feature = "digest"))]
+fn digest_reset() {
+    use k12::{
+        digest::{ExtendableOutputDirty, Reset, Update, XofReader},
+        MarsupilamiFourteen, TurboShake128, TurboShake256,
+    };
+
+    fn output<D: ExtendableOutputDirty>(hasher: &mut D) -> [u8; 64] {
+        let mut output = [0u8; 64];
+        hasher.finalize_xof_dirty().read(&mut output);
+        output
+    }
+
+    // Hash two messages through the same hasher, resetting it in between
+    fn reused<D: Default + Update + Reset + ExtendableOutputDirty>() {
+        let long: Vec<u8> = (0..20000).map(|j| (j % 251) as u8).collect();
+        let fresh = |m: &[u8]| output(&mut D::default().chain(m));
+
+        let mut hasher = D::default();
+        hasher.update(&long);
+        assert_eq!(output(&mut hasher), fresh(&long));
+        hasher.reset();
+        hasher.update(b"abc");
+        assert_eq!(output(&mut hasher), fresh(b"abc"));
+
+        // Reset a partially absorbed state
+        hasher.reset();
+        hasher.update(&long[..9000]);
+        hasher.reset();
+        assert_eq!(output(&mut hasher), fresh(b""));
+    }
+
+    reused::<KangarooTwelve>();
+    reused::<MarsupilamiFourteen>();
+    reused::<TurboShake128>();
+    reused::<TurboShake256>();
+}
+
+#[test]
+#[cfg(feature = "alloc")]
+fn multi_chunk() {
+    // Leaves are hashed in parallel when the `rayon` feature is enabled
+    let expected = [
+        (
+            8193,
+            "bb66fe72eaea5179418d5295ee1344854d8ad7f3fa17efcb467ec152341284cf",
+        ),
+        (
+            16384,
+            "82778f7f7234c83352e76837b721fbdbb5270b88010d84fa5ab0b61ec8ce0956",
+        ),
+        (
+            65536,
+            "26fbd1ca564b32ec52f0b4d9a562039aa75eaa1de7498b07d3b9c6d9a3614706",
+        ),
+        (
+            1 << 20,
+            "93070bfd10b8028f3c0ebe9304dd7f10f2c8ae403371ae695591f4710928f8dd",
+        ),
+    ];
+    for &(len, output) in &expected {
+        let m: Vec<u8> = (0..len).map(|j| (j % 251) as u8).collect();
+        let result = KangarooTwelve::new().chain(&m).try_finalize(32).unwrap();
+        assert_eq!(result, read_bytes(output), "length {}", len);
+    }
+}
+
+#[test]
+#[cfg(feature = "zeroize")]
+fn zeroize_on_drop() {
+    fn assert_zeroize_on_drop<T: zeroize::ZeroizeOnDrop>() {}
+
+    #[cfg(feature = "alloc")]
+    {
+        assert_zeroize_on_drop::<KangarooTwelve>();
+        assert_zeroize_on_drop::<k12::MarsupilamiFourteen>();
+        assert_zeroize_on_drop::<k12::K12_256>();
+        assert_zeroize_on_drop::<k12::Kmac12>();
     }
+    assert_zeroize_on_drop::<StackKangarooTwelve<64>>();
+    assert_zeroize_on_drop::<k12::Squeezer>();
+    assert_zeroize_on_drop::<k12::LeafHasher>();
+    assert_zeroize_on_drop::<k12::K12Rng>();
+    assert_zeroize_on_drop::<k12::KangarooTwelveRt<'static>>();
+    assert_zeroize_on_drop::<k12::TurboShake128>();
+    assert_zeroize_on_drop::<k12::TurboShake256>();
+    assert_zeroize_on_drop::<k12::Shake128>();
+    assert_zeroize_on_drop::<k12::Sha3_256>();
+    assert_zeroize_on_drop::<k12::Keccak256>();
+    assert_zeroize_on_drop::<k12::Duplex>();
+    assert_zeroize_on_drop::<k12::Sponge800>();
+    assert_zeroize_on_drop::<k12::XooSponge>();
+    assert_zeroize_on_drop::<k12::Sponge<k12::Xoodoo, 16, 32>>();
+    assert_zeroize_on_drop::<k12::SpongeReader<k12::KeccakP800<12>, 68>>();
 }
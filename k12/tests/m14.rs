@@ -0,0 +1,96 @@
+//! MarsupilamiFourteen test vectors, using the same input patterns as the
+//! KangarooTwelve vectors. The expected outputs were computed with an
+//! independent implementation of the tree hashing mode on top of
+//! Keccak-p[1600, 14].
+#![cfg(feature = "alloc")]
+
+mod common;
+
+use common::ptn;
+use hex_literal::hex;
+use k12::MarsupilamiFourteen;
+
+fn m14(m: &[u8], c: &[u8], output_len: usize) -> Vec<u8> {
+    let mut output = vec![0u8; output_len];
+    MarsupilamiFourteen::new_with_customization(c)
+        .chain(m)
+        .finalize_xof()
+        .read(&mut output);
+    output
+}
+
+#[test]
+fn empty() {
+    assert_eq!(
+        m14(b"", b"", 32)[..],
+        hex!("6F66EF1474EB53807AA329257C768BB88893D9F086E51DA2F5C80D17CA0FC57D")[..]
+    );
+    assert_eq!(
+        m14(b"", b"", 64)[..],
+        hex!(
+            "6F66EF1474EB53807AA329257C768BB88893D9F086E51DA2F5C80D17CA0FC57D
+            5A24FAC879014F8B30A3FDF5AC56EBAFA219EB891D4BBBAB7E1DF3B27205B459"
+        )[..]
+    );
+    assert_eq!(
+        m14(b"", b"", 10032)[10000..],
+        hex!("C09322DE1513D0CD604728F36D11ADFF58B93F776381095A071921EAFB30E1E3")[..]
+    );
+}
+
+#[test]
+fn pat_m() {
+    let expected = [
+        hex!(
+            "CC05EBC928156C7A03540085355C47C6AEA1D07DC811CDDED0E4C367F8D99368
+            A531825D996413A9BC0E1E572FF5DF4F98CA65F4FB4900EE2355F59599E2F648"
+        ),
+        hex!(
+            "AA764FD8B38F19976A305CB007F19384B210A5C7B0FC4499D6F83C6227BFF850
+            270B880CFF3F17325B843E972AE0B99A25FA0E0050CC748F37C4CFC2592FD172"
+        ),
+        hex!(
+            "F18A6E250B1CC83DEA89FFBB4DE56A8E70041C71FC5B17A2AAAB05C606AA6BF2
+            7C3955C946E8E215F0B1E2C93CB9E7A736C339C06F34E587DF3BCC5847CF25F6"
+        ),
+        hex!(
+            "0AC89B11A06F46B2F6FEEFF046C97E90DC02910AE509B8739CFEA5DF1DF90B82
+            895A5FAD67AD2FA41259090756C0D988440FA3267A48380ADA5DF9C7F0290757"
+        ),
+        hex!(
+            "35AF0A5FC6C4D111FBC68F879D05506AAFD300B5AB136986D7AED8A9F1BE331E
+            8664381864672E81BA32D828B2C05192A5886846F6C7570E7EBAEB97B59BD73E"
+        ),
+    ];
+    for (i, expected) in expected.iter().enumerate() {
+        let m = ptn(17usize.pow(i as u32));
+        assert_eq!(m14(&m, b"", 64)[..], expected[..]);
+    }
+}
+
+#[test]
+fn pat_c() {
+    let expected = [
+        hex!(
+            "E6C23CEEAB2089D14DC3B088FDFE6D4418BF8A6F330FB3EDCC300CD81E1BEF2F
+            0CAB479B196E53BE8FA287854D484FDFD084AF3AE1FFAC9B04C2E9EA2B5A1C7B"
+        ),
+        hex!(
+            "2BAB75B31B8C3049ABEB7674774771B64F59225BE20E930EBDBF8E37C24FAD69
+            BEF47A412DB62094D5CC95DE8E4FC2C0AE65FD0F4D03BB56E6292BE084FCC8E3"
+        ),
+        hex!(
+            "732A60C308BEBF5F7B3D3E8F0D26E324C04BAB4197CA0A608B0BEFAA25EA5976
+            0718509C01FE503DE2B970963F31E359E31F6AD5F6A591E83BC641D4CD6411DD"
+        ),
+        hex!(
+            "61583CDFAA64AB60E77B8C8BDD0AD088F9D760B2944F7D64C5DD81CE7E92D96B
+            FF67843A1EED51F301DB51FF54FDCD4462FD051425D4C2EDBA74AC2B1532EC14"
+        ),
+    ];
+    for (i, expected) in expected.iter().enumerate() {
+        let m = vec![0xFF; 2usize.pow(i as u32) - 1];
+        let c = ptn(41usize.pow(i as u32));
+        assert_eq!(m14(&m, &c, 64)[..], expected[..]);
+    }
+}
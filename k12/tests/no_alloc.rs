@@ -0,0 +1,60 @@
+//! `KangarooTwelveRt` does not allocate after construction.
+
+use k12::KangarooTwelveRt;
+use std::{
+    alloc::{GlobalAlloc, Layout, System},
+    cell::Cell,
+};
+
+/// Allocator counting the allocations of the current thread.
+struct CountingAllocator;
+
+thread_local! {
+    static ALLOCATIONS: Cell<usize> = const { Cell::new(0) };
+}
+
+unsafe impl GlobalAlloc for CountingAllocator {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        // Ignore allocations while the thread is being torn down
+        let _ = ALLOCATIONS.try_with(|n| n.set(n.get() + 1));
+        System.alloc(layout)
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        System.dealloc(ptr, layout)
+    }
+}
+
+#[global_allocator]
+static ALLOCATOR: CountingAllocator = CountingAllocator;
+
+/// Number of allocations made by `f` on the current thread.
+fn allocations(f: impl FnOnce()) -> usize {
+    let before = ALLOCATIONS.with(Cell::get);
+    f();
+    ALLOCATIONS.with(Cell::get) - before
+}
+
+#[test]
+fn no_allocations() {
+    let m: Vec<u8> = (0..100_000).map(|j| (j % 251) as u8).collect();
+    let mut expected = [0u8; 64];
+    k12::hash_into(&m, b"customization", &mut expected);
+
+    let mut output = [0u8; 64];
+    let mut hasher = KangarooTwelveRt::new(b"customization", &mut output);
+    let n = allocations(|| {
+        for _ in 0..2 {
+            for chunk in m.chunks(10_000) {
+                hasher.update(chunk);
+            }
+            assert_eq!(hasher.finalize(), &expected[..]);
+        }
+        hasher.update(&m);
+    });
+    assert_eq!(n, 0);
+
+    // The counter does see allocations
+    #[cfg(feature = "alloc")]
+    assert!(allocations(|| drop(k12::KangarooTwelve::new_with_customization(b"c"))) > 0);
+}
@@ -0,0 +1,26 @@
+# Answers requests of the differential tests in `tests/differential.rs` with
+# the reference implementation in `K12.py`.
+#
+# Each line of input is a function name followed by its arguments separated
+# by single spaces, with byte strings in hexadecimal (possibly empty):
+#
+#     k12 <message> <customization> <output length>
+#     turboshake128 <message> <domain byte> <output length>
+#     turboshake256 <message> <domain byte> <output length>
+#
+# and is answered by a line with the output in hexadecimal.
+
+import sys
+
+from K12 import KangarooTwelve, TurboSHAKE128, TurboSHAKE256
+
+FUNCTIONS = {
+    "k12": lambda m, c, n: KangarooTwelve(m, c, n),
+    "turboshake128": lambda m, d, n: TurboSHAKE128(m, d[0], n),
+    "turboshake256": lambda m, d, n: TurboSHAKE256(m, d[0], n),
+}
+
+for line in sys.stdin:
+    name, a, b, n = line.rstrip("\n").split(" ")
+    output = FUNCTIONS[name](bytes.fromhex(a), bytes.fromhex(b), int(n))
+    print(output.hex(), flush=True)
@@ -0,0 +1,72 @@
+//! Tests of the `K12Rng` generator.
+
+use k12::K12Rng;
+
+fn seed(byte: u8) -> [u8; 32] {
+    [byte; 32]
+}
+
+#[test]
+#[cfg(feature = "alloc")]
+fn output_stream() {
+    // The output is the KangarooTwelve output for the seed
+    let mut rng = K12Rng::new(seed(1));
+    let mut output = [0u8; 1000];
+    rng.fill_bytes(&mut output[..3]);
+    rng.fill_bytes(&mut output[3..]);
+    assert_eq!(output[..], k12::hash(&seed(1), b"", 1000).unwrap()[..]);
+
+    let mut rng = K12Rng::new_with_personalization(seed(1), b"MyApp nonces");
+    let expected = k12::hash(&seed(1), b"MyApp nonces", 12).unwrap();
+    assert_eq!(rng.next_u32().to_le_bytes()[..], expected[..4]);
+    assert_eq!(rng.next_u64().to_le_bytes()[..], expected[4..]);
+}
+
+#[test]
+fn seeds() {
+    let (mut a, mut b) = (K12Rng::new(seed(1)), K12Rng::new(seed(1)));
+    let (mut x, mut y) = ([0u8; 256], [0u8; 256]);
+    a.fill_bytes(&mut x);
+    b.fill_bytes(&mut y);
+    assert_eq!(x, y);
+
+    // About one byte in 256 agrees by chance
+    for mut other in [
+        K12Rng::new(seed(2)),
+        K12Rng::new_with_personalization(seed(1), b"p"),
+    ] {
+        other.fill_bytes(&mut y);
+        assert!(x.iter().zip(&y).filter(|(x, y)| x == y).count() < 8);
+    }
+}
+
+#[test]
+fn monobit_frequency() {
+    // Frequency (monobit) test of NIST SP 800-22, Section 2.1, on 1 MiB
+    let mut rng = K12Rng::new(seed(3));
+    let mut output = vec![0u8; 1 << 20];
+    rng.fill_bytes(&mut output);
+
+    let n = 8.0 * output.len() as f64;
+    let ones: u32 = output.iter().map(|b| b.count_ones()).sum();
+    let s_obs = (2.0 * f64::from(ones) - n).abs() / n.sqrt();
+
+    // `erfc(s_obs / sqrt(2)) >= 0.01`, i.e. the sequence passes at the
+    // significance level recommended by SP 800-22
+    assert!(s_obs < 2.5758, "s_obs = {}", s_obs);
+}
+
+#[test]
+#[cfg(feature = "rand_core")]
+fn rand_core_traits() {
+    use k12::rand_core::{RngCore, SeedableRng};
+
+    let mut rng = K12Rng::from_seed(seed(1));
+    let mut expected = K12Rng::new(seed(1));
+    assert_eq!(RngCore::next_u32(&mut rng), expected.next_u32());
+    assert_eq!(RngCore::next_u64(&mut rng), expected.next_u64());
+    let (mut x, mut y) = ([0u8; 100], [0u8; 100]);
+    rng.try_fill_bytes(&mut x).unwrap();
+    expected.fill_bytes(&mut y);
+    assert_eq!(x, y);
+}
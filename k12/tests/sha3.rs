@@ -0,0 +1,159 @@
+//! Test vectors from the FIPS 202 examples and from Ethereum, and comparisons
+//! with the `sha3` crate.
+
+#[cfg(feature = "digest")]
+mod common;
+
+#[cfg(feature = "digest")]
+use common::ptn;
+#[cfg(feature = "digest")]
+use digest::Digest;
+use hex_literal::hex;
+use k12::{Keccak256, Sha3_256};
+
+#[test]
+fn sha3_256_vectors() {
+    let vectors: &[(&[u8], [u8; 32])] = &[
+        (
+            b"",
+            hex!("A7FFC6F8BF1ED76651C14756A061D662F580FF4DE43B49FA82D80A4B80F8434A"),
+        ),
+        (
+            b"abc",
+            hex!("3A985DA74FE225B2045C172D6BD390BD855F086E3E9D525B46BFE24511431532"),
+        ),
+        (
+            b"abcdbcdecdefdefgefghfghighijhijkijkljklmklmnlmnomnopnopq",
+            hex!("41C0DBA2A9D6240849100376A8235E2C82E1B9998A999E21DB32DD97496D3376"),
+        ),
+        (
+            b"abcdefghbcdefghicdefghijdefghijkefghijklfghijklmghijklmnhijklmnoijklmnopjklmnopqklmnopqrlmnopqrsmnopqrstnopqrstu",
+            hex!("916F6061FE879741CA6469B43971DFDB28B1A32DC36CB3254E812BE27AAD1D18"),
+        ),
+        (
+            &[0xA3; 200],
+            hex!("79F38ADEC5C20307A98EF76E8324AFBFD46CFD81B22E3973C65FA1BD9DE31787"),
+        ),
+    ];
+
+    for (message, expected) in vectors {
+        assert_eq!(Sha3_256::new().chain(message).finalize(), *expected);
+    }
+}
+
+#[test]
+fn sha3_256_million_a() {
+    let mut hasher = Sha3_256::new();
+    for _ in 0..1000 {
+        hasher.update([b'a'; 1000]);
+    }
+    assert_eq!(
+        hasher.finalize(),
+        hex!("5C8875AE474A3634BA4FD55EC85BFFD661F32ACA75C6D699D0CDCB6C115891C1")
+    );
+}
+
+#[cfg(feature = "digest")]
+#[test]
+fn sha3_256_matches_sha3() {
+    let data = ptn(3 * 136 + 1);
+    for len in 0..data.len() {
+        let expected = sha3::Sha3_256::digest(&data[..len]);
+        assert_eq!(
+            Sha3_256::new().chain(&data[..len]).finalize()[..],
+            expected[..],
+            "length {}",
+            len
+        );
+    }
+}
+
+#[cfg(feature = "digest")]
+#[test]
+fn digest_trait() {
+    fn digest<D: Digest>(parts: &[&[u8]]) -> Vec<u8> {
+        let mut hasher = D::new();
+        for part in parts {
+            hasher.update(part);
+        }
+        let output = hasher.finalize_reset().to_vec();
+        assert_eq!(hasher.finalize()[..], D::digest(b"")[..]);
+        output
+    }
+
+    assert_eq!(
+        digest::<Sha3_256>(&[b"ab", b"c"]),
+        hex!("3A985DA74FE225B2045C172D6BD390BD855F086E3E9D525B46BFE24511431532")
+    );
+    assert_eq!(
+        digest::<Keccak256>(&[b"ab", b"c"]),
+        hex!("4E03657AEA45A94FC7D47BA826C8D667C0D1E6E33A64A036EC44F58FA12D6C45")
+    );
+}
+
+#[test]
+fn keccak256_vectors() {
+    let vectors: &[(&[u8], [u8; 32])] = &[
+        (
+            b"",
+            hex!("C5D2460186F7233C927E7DB2DCC703C0E500B653CA82273B7BFAD8045D85A470"),
+        ),
+        (
+            b"abc",
+            hex!("4E03657AEA45A94FC7D47BA826C8D667C0D1E6E33A64A036EC44F58FA12D6C45"),
+        ),
+        // Hash of the empty list of ommers in block headers
+        (
+            &hex!("C0"),
+            hex!("1DCC4DE8DEC75D7AAB85B567B6CCD41AD312451B948A7413F0A142FD40D49347"),
+        ),
+        // Root of the empty trie
+        (
+            &hex!("80"),
+            hex!("56E81F171BCC55A6FF8345E692C0F86E5B48E01B996CADC001622FB5E363B421"),
+        ),
+        // Topic of the ERC-20 `Transfer` event
+        (
+            b"Transfer(address,address,uint256)",
+            hex!("DDF252AD1BE2C89B69C2B068FC378DAA952BA7F163C4A11628F55A4DF523B3EF"),
+        ),
+        // Signing hash of the example transaction of EIP-155
+        (
+            &hex!(
+                "EC098504A817C800825208943535353535353535353535353535353535353535
+                880DE0B6B3A764000080018080"
+            ),
+            hex!("DAF5A779AE972F972197303D7B574746C7EF83EADAC0F2791AD23DB92E4C8E53"),
+        ),
+    ];
+
+    for (message, expected) in vectors {
+        assert_eq!(Keccak256::new().chain(message).finalize(), *expected);
+    }
+
+    // The selector of the ERC-20 `transfer` function
+    assert_eq!(
+        Keccak256::new()
+            .chain("transfer(address,uint256)")
+            .finalize()[..4],
+        hex!("A9059CBB")
+    );
+
+    // Different from SHA3-256 even for the empty message
+    assert_ne!(Keccak256::new().finalize(), Sha3_256::new().finalize());
+}
+
+#[cfg(feature = "digest")]
+#[test]
+fn keccak256_matches_sha3() {
+    let data = ptn(3 * 136 + 1);
+    for len in 0..data.len() {
+        let expected = sha3::Keccak256::digest(&data[..len]);
+        assert_eq!(
+            Keccak256::new().chain(&data[..len]).finalize()[..],
+            expected[..],
+            "length {}",
+            len
+        );
+    }
+}
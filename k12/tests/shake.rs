@@ -0,0 +1,150 @@
+//! Test vectors from the FIPS 202 examples, and comparisons with the `sha3`
+//! crate.
+
+mod common;
+
+use common::ptn;
+#[cfg(feature = "digest")]
+use digest::{ExtendableOutput, Reset, Update, XofReader};
+use hex_literal::hex;
+use k12::{Shake128, Shake256};
+
+fn shake128(m: &[u8], output_len: usize) -> Vec<u8> {
+    let mut output = vec![0u8; output_len];
+    Shake128::new().chain(m).finalize_xof().read(&mut output);
+    output
+}
+
+fn shake256(m: &[u8], output_len: usize) -> Vec<u8> {
+    let mut output = vec![0u8; output_len];
+    Shake256::new().chain(m).finalize_xof().read(&mut output);
+    output
+}
+
+#[test]
+fn shake128_vectors() {
+    assert_eq!(
+        shake128(b"", 32),
+        hex!("7F9C2BA4E88F827D616045507605853ED73B8093F6EFBC88EB1A6EACFA66EF26")
+    );
+    assert_eq!(
+        shake128(b"abc", 32),
+        hex!("5881092DD818BF5CF8A3DDB793FBCBA74097D5C526A6D35F97B83351940F2CC8")
+    );
+    assert_eq!(
+        shake128(&[0xA3; 200], 32),
+        hex!("131AB8D2B594946B9C81333F9BB6E0CE75C3B93104FA3469D3917457385DA037")
+    );
+    assert_eq!(
+        shake128(&ptn(1000), 32),
+        hex!("A72440F7F5AA7C14C8E0187420611DA7E2BA62F5BB2E88A91B9C9448CAC30078")
+    );
+    assert_eq!(
+        shake128(b"", 512)[480..],
+        hex!("43E41B45A653F2A5C4492C1ADD544512DDA2529833462B71A41A45BE97290B6F")
+    );
+}
+
+#[test]
+fn shake256_vectors() {
+    assert_eq!(
+        shake256(b"", 64),
+        hex!(
+            "46B9DD2B0BA88D13233B3FEB743EEB243FCD52EA62B81B82B50C27646ED5762F
+            D75DC4DDD8C0F200CB05019D67B592F6FC821C49479AB48640292EACB3B7C4BE"
+        )
+    );
+    assert_eq!(
+        shake256(b"abc", 64),
+        hex!(
+            "483366601360A8771C6863080CC4114D8DB44530F8F1E1EE4F94EA37E78B5739
+            D5A15BEF186A5386C75744C0527E1FAA9F8726E462A12A4FEB06BD8801E751E4"
+        )
+    );
+    assert_eq!(
+        shake256(&[0xA3; 200], 64),
+        hex!(
+            "CD8A920ED141AA0407A22D59288652E9D9F1A7EE0C1E7C1CA699424DA84A904D
+            2D700CAAE7396ECE96604440577DA4F3AA22AEB8857F961C4CD8E06F0AE6610B"
+        )
+    );
+    assert_eq!(
+        shake256(&ptn(1000), 64),
+        hex!(
+            "34833F03ED88BB5F083CE590C7AE5AF93EDE33E11F53C70E47916C7044746ACB
+            DCA19A73FF13905E91F8DC25CE6E41AE59FE75441BD548DDA9114ACA1DA71802"
+        )
+    );
+    assert_eq!(
+        shake256(b"", 512)[480..],
+        hex!("AB0BAE316339894304E35877B0C28A9B1FD166C796B9CC258A064A8F57E27F2A")
+    );
+}
+
+#[cfg(feature = "digest")]
+#[test]
+fn shake_matches_sha3() {
+    let data = ptn(3 * 168 + 1);
+    for len in 0..data.len() {
+        let mut expected = [0u8; 200];
+        sha3::Shake128::default()
+            .chain(&data[..len])
+            .finalize_xof()
+            .read(&mut expected);
+        assert_eq!(shake128(&data[..len], 200), expected[..], "length {}", len);
+
+        sha3::Shake256::default()
+            .chain(&data[..len])
+            .finalize_xof()
+            .read(&mut expected);
+        assert_eq!(shake256(&data[..len], 200), expected[..], "length {}", len);
+    }
+}
+
+#[cfg(feature = "digest")]
+#[test]
+fn shake128_digest() {
+    let mut hasher = Shake128::default();
+    Update::update(&mut hasher, b"ab");
+    Update::update(&mut hasher, b"c");
+    let mut output = [0u8; 32];
+    hasher.clone().finalize_xof().read(&mut output);
+    assert_eq!(
+        output,
+        hex!("5881092DD818BF5CF8A3DDB793FBCBA74097D5C526A6D35F97B83351940F2CC8")
+    );
+
+    Reset::reset(&mut hasher);
+    ExtendableOutput::finalize_xof(hasher).read(&mut output);
+    assert_eq!(
+        output,
+        hex!("7F9C2BA4E88F827D616045507605853ED73B8093F6EFBC88EB1A6EACFA66EF26")
+    );
+}
+
+#[cfg(feature = "digest")]
+#[test]
+fn shake256_digest() {
+    let mut hasher = Shake256::default();
+    Update::update(&mut hasher, b"ab");
+    Update::update(&mut hasher, b"c");
+    let mut output = [0u8; 32];
+    hasher.clone().finalize_xof().read(&mut output);
+    assert_eq!(
+        output,
+        hex!("483366601360A8771C6863080CC4114D8DB44530F8F1E1EE4F94EA37E78B5739")
+    );
+
+    Reset::reset(&mut hasher);
+    ExtendableOutput::finalize_xof(hasher).read(&mut output);
+    assert_eq!(
+        output,
+        hex!("46B9DD2B0BA88D13233B3FEB743EEB243FCD52EA62B81B82B50C27646ED5762F")
+    );
+}
+
+#[test]
+fn debug() {
+    assert_eq!(format!("{:?}", Shake128::new()), "Shake128 { ... }");
+    assert_eq!(format!("{:?}", Shake256::new()), "Shake256 { ... }");
+}
@@ -0,0 +1,131 @@
+//! The generic `Sponge` over the permutations of the crate.
+
+mod common;
+
+use common::ptn;
+use k12::{
+    Keccak1600x12, Keccak1600x24, KeccakP800, Permutation, Sponge, Sponge800, TurboShake128, Xoodoo,
+};
+use std::convert::TryInto;
+
+#[test]
+fn keccak_p1600() {
+    let data = ptn(400);
+    for &len in &[0, 1, 167, 168, 169, 400] {
+        let mut sponge = Sponge::<Keccak1600x12, 168, 32>::new();
+        sponge.absorb(&data[..len]);
+        let mut output = [0u8; 400];
+        sponge.finalize_xof(0x1F).read(&mut output);
+
+        let mut expected = [0u8; 400];
+        TurboShake128::new()
+            .chain(&data[..len])
+            .finalize_xof()
+            .read(&mut expected);
+        assert_eq!(output[..], expected[..], "length {}", len);
+    }
+
+    let mut output = [0u8; 32];
+    Sponge::<Keccak1600x24, 168, 32>::new()
+        .finalize_xof(0x1F)
+        .read(&mut output);
+    let mut expected = [0u8; 32];
+    k12::Shake128::new().finalize_xof().read(&mut expected);
+    assert_eq!(output, expected);
+}
+
+/// Keccak-p[1600, 12] on top of the public byte-oriented permutation, as a
+/// permutation defined outside of the crate.
+struct BytewiseKeccak;
+
+impl Permutation for BytewiseKeccak {
+    type State = [u64; 25];
+
+    const WIDTH: usize = 200;
+
+    fn permute(state: &mut [u64; 25]) {
+        let mut bytes = [0u8; 200];
+        Self::squeeze_bytes(state, 0, &mut bytes);
+        k12::keccak_p_1600_12(&mut bytes);
+        for (lane, chunk) in state.iter_mut().zip(bytes.chunks_exact(8)) {
+            *lane = u64::from_le_bytes(chunk.try_into().unwrap());
+        }
+    }
+
+    fn absorb_bytes(state: &mut [u64; 25], offset: usize, input: &[u8]) {
+        for (i, byte) in input.iter().enumerate() {
+            let j = offset + i;
+            state[j / 8] ^= u64::from(*byte) << (8 * (j % 8));
+        }
+    }
+
+    fn squeeze_bytes(state: &[u64; 25], offset: usize, output: &mut [u8]) {
+        for (i, byte) in output.iter_mut().enumerate() {
+            let j = offset + i;
+            *byte = (state[j / 8] >> (8 * (j % 8))) as u8;
+        }
+    }
+}
+
+#[test]
+fn external_permutation() {
+    let data = ptn(400);
+    for &len in &[0, 1, 168, 400] {
+        let mut output = [0u8; 200];
+        let mut sponge = Sponge::<BytewiseKeccak, 168, 32>::new();
+        sponge.absorb(&data[..len]);
+        sponge.finalize_xof(0x1F).read(&mut output);
+
+        let mut expected = [0u8; 200];
+        let mut sponge = Sponge::<Keccak1600x12, 168, 32>::new();
+        sponge.absorb(&data[..len]);
+        sponge.finalize_xof(0x1F).read(&mut expected);
+        assert_eq!(output[..], expected[..], "length {}", len);
+    }
+}
+
+#[test]
+fn keccak_p800() {
+    let data = ptn(200);
+    let mut sponge = Sponge::<KeccakP800<12>, 68, 32>::new();
+    sponge.absorb(&data);
+    let mut output = [0u8; 100];
+    sponge.finalize_xof(0x1F).read(&mut output);
+
+    let mut expected = [0u8; 100];
+    Sponge800::new().chain(&data).finalize_into(&mut expected);
+    assert_eq!(output[..], expected[..]);
+}
+
+#[test]
+fn xoodoo() {
+    let mut sponge = Sponge::<Xoodoo, 16, 32>::new();
+    sponge.absorb(b"abc");
+    let mut reader = sponge.finalize_xof(0x1F);
+
+    // Cloning the reader forks the output stream
+    let mut first = [0u8; 40];
+    reader.clone().read(&mut first);
+    let mut second = [0u8; 40];
+    reader.read(&mut second[..7]);
+    reader.read(&mut second[7..]);
+    assert_eq!(first, second);
+    assert_ne!(first[..16], first[16..32]);
+}
+
+#[cfg(feature = "digest")]
+#[test]
+fn xof_reader() {
+    use digest::XofReader;
+
+    fn read(reader: &mut impl XofReader) -> [u8; 32] {
+        let mut output = [0u8; 32];
+        reader.read(&mut output);
+        output
+    }
+
+    let mut reader = Sponge::<Keccak1600x12, 168, 32>::new().finalize_xof(0x1F);
+    let mut expected = TurboShake128::new().finalize_xof();
+    assert_eq!(read(&mut reader), read(&mut expected));
+    assert_eq!(read(&mut reader), read(&mut expected));
+}
@@ -0,0 +1,89 @@
+//! Known-answer tests of `Sponge800`, computed with the Python reference
+//! implementation of the Keccak team.
+
+mod common;
+
+use common::ptn;
+use hex_literal::hex;
+use k12::{Error, Sponge800};
+
+#[test]
+fn sponge800() {
+    let vectors: &[(usize, [u8; 32])] = &[
+        (
+            0,
+            hex!("7ED3E0D84C0D4E92C8B32E4ECC9CD7B09A24AA5AEAEEDE7AEBE1AACF9A278574"),
+        ),
+        (
+            1,
+            hex!("C14C06D9AB557F944F723715148006956C4991F537D9518FF514EEAEBED6D6DF"),
+        ),
+        (
+            67,
+            hex!("3453AAF52B6729A530141BB78B28B93A1DF65435DAF45A75EC2B1A5DFFE4E3A2"),
+        ),
+        (
+            68,
+            hex!("0275CAB7EA1992030F41A9D5EB34028A80611D9200F33F6A8CA51D510FC9EC47"),
+        ),
+        (
+            69,
+            hex!("04A2DBA777C28AC60A5F3CD7BFBA9A80E1BE1BB772E454F92C1FFAFB59CA5EAA"),
+        ),
+        (
+            200,
+            hex!("D52BC5118753E0FED65BA22D0505FAABB296FBDF922D17677DB7B1C786BCF05D"),
+        ),
+    ];
+
+    for (len, expected) in vectors {
+        let message = ptn(*len);
+        let mut output = [0u8; 32];
+        Sponge800::new().chain(&message).finalize_into(&mut output);
+        assert_eq!(output, *expected, "length {}", len);
+
+        let mut sponge = Sponge800::new();
+        for part in message.chunks(17) {
+            sponge.update(part);
+        }
+        let mut output = [0u8; 32];
+        sponge.finalize_into(&mut output);
+        assert_eq!(output, *expected, "length {} in chunks", len);
+    }
+}
+
+#[test]
+fn long_output() {
+    let mut output = [0u8; 150];
+    Sponge800::new().chain(b"abc").finalize_into(&mut output);
+    assert_eq!(
+        output[..],
+        hex!(
+            "6604101E77719F41206C822879C7B29B1C0F88EDD6565D73922CB43C94E407C2
+            B93540280540A098F1AE2D5D7DCFBE72D9B201187E6735277CA5F10FFD1A1B66
+            077B92F737BBAA7EDD1949D15F15C3B12947FCD0ADDAF58023AFCC6301F59535
+            7C70FEE4CD0BB1721935420BCED08CD47A7D2114572CE61CD032E07B433070C9
+            A72612A09B8F3689614C48D1B9060ABC8445E7EE4FA5"
+        )[..]
+    );
+}
+
+#[test]
+fn domain() {
+    let mut output = [0u8; 32];
+    Sponge800::new_with_domain(0x07)
+        .unwrap()
+        .chain(b"abc")
+        .finalize_into(&mut output);
+    assert_eq!(
+        output,
+        hex!("8DE98DC79A54A78E6A45923DE3CE1F4DA0607A4A0056186F91BB780896F41148")
+    );
+
+    for &domain in &[0x00, 0x80, 0xFF] {
+        assert_eq!(
+            Sponge800::new_with_domain(domain).unwrap_err(),
+            Error::InvalidDomainSeparator
+        );
+    }
+}
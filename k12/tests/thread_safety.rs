@@ -0,0 +1,44 @@
+//! Every public type can be sent to and shared between threads.
+
+fn assert_send_sync<T: Send + Sync>() {}
+
+#[test]
+fn send_sync() {
+    #[cfg(feature = "alloc")]
+    {
+        assert_send_sync::<k12::KangarooTwelve>();
+        assert_send_sync::<k12::KangarooTwelveBuilder>();
+        assert_send_sync::<k12::MarsupilamiFourteen>();
+        assert_send_sync::<k12::K12_256>();
+        assert_send_sync::<k12::K12_512>();
+        assert_send_sync::<k12::Kmac12>();
+    }
+    #[cfg(feature = "tokio")]
+    assert_send_sync::<k12::AsyncK12Absorber>();
+    #[cfg(feature = "ffi")]
+    assert_send_sync::<k12::ffi::K12State>();
+
+    assert_send_sync::<k12::StackKangarooTwelve<64>>();
+    assert_send_sync::<k12::LeafHasherFactory>();
+    assert_send_sync::<k12::LeafHasher>();
+    assert_send_sync::<k12::K12Rng>();
+    assert_send_sync::<k12::KangarooTwelveRt<'static>>();
+    assert_send_sync::<k12::Squeezer>();
+    assert_send_sync::<k12::K12Output<32>>();
+    assert_send_sync::<k12::Domain>();
+    assert_send_sync::<k12::Error>();
+    assert_send_sync::<k12::TurboShake128>();
+    assert_send_sync::<k12::TurboShake256>();
+    assert_send_sync::<k12::Shake128>();
+    assert_send_sync::<k12::Shake256>();
+    assert_send_sync::<k12::Sha3_256>();
+    assert_send_sync::<k12::Keccak256>();
+    assert_send_sync::<k12::Duplex>();
+    assert_send_sync::<k12::Sponge800>();
+    assert_send_sync::<k12::XooSponge>();
+    assert_send_sync::<k12::XoodooState>();
+    assert_send_sync::<k12::Sponge<k12::Keccak1600x12, 168, 32>>();
+    assert_send_sync::<k12::Sponge<k12::KeccakP800<12>, 68, 32>>();
+    assert_send_sync::<k12::Sponge<k12::Xoodoo, 16, 32>>();
+    assert_send_sync::<k12::SpongeReader<k12::Keccak1600x12, 168>>();
+}
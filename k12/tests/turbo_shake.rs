@@ -0,0 +1,242 @@
+//! Test vectors from RFC 9861, Section 5.
+
+mod common;
+
+use common::ptn;
+use hex_literal::hex;
+use k12::{Error, TurboShake128, TurboShake256};
+
+fn turbo_shake128(m: &[u8], domain: u8, output_len: usize) -> Vec<u8> {
+    let mut output = vec![0u8; output_len];
+    TurboShake128::new_with_domain(domain)
+        .unwrap()
+        .chain(m)
+        .finalize_xof()
+        .read(&mut output);
+    output
+}
+
+fn turbo_shake256(m: &[u8], domain: u8, output_len: usize) -> Vec<u8> {
+    let mut output = vec![0u8; output_len];
+    TurboShake256::new_with_domain(domain)
+        .unwrap()
+        .chain(m)
+        .finalize_xof()
+        .read(&mut output);
+    output
+}
+
+#[test]
+fn turbo_shake128_empty() {
+    assert_eq!(
+        turbo_shake128(b"", 0x1F, 32),
+        hex!("1E415F1C5983AFF2169217277D17BB538CD945A397DDEC541F1CE41AF2C1B74C")
+    );
+    assert_eq!(
+        turbo_shake128(b"", 0x1F, 64)[..],
+        hex!(
+            "1E415F1C5983AFF2169217277D17BB538CD945A397DDEC541F1CE41AF2C1B74C
+            3E8CCAE2A4DAE56C84A04C2385C03C15E8193BDF58737363321691C05462C8DF"
+        )[..]
+    );
+    assert_eq!(
+        turbo_shake128(b"", 0x1F, 10032)[10000..],
+        hex!("A3B9B0385900CE761F22AED548E754DA10A5242D62E8C658E3F3A923A7555607")
+    );
+}
+
+#[test]
+fn turbo_shake128_pat_m() {
+    let expected = [
+        hex!("55CEDD6F60AF7BB29A4042AE832EF3F58DB7299F893EBB9247247D856958DAA9"),
+        hex!("9C97D036A3BAC819DB70EDE0CA554EC6E4C2A1A4FFBFD9EC269CA6A111161233"),
+        hex!("96C77C279E0126F7FC07C9B07F5CDAE1E0BE60BDBE10620040E75D7223A624D2"),
+        hex!("D4976EB56BCF118520582B709F73E1D6853E001FDAF80E1B13E0D0599D5FB372"),
+        hex!("DA67C7039E98BF530CF7A37830C6664E14CBAB7F540F58403B1B82951318EE5C"),
+        hex!("B97A906FBF83EF7C812517ABF3B2D0AEA0C4F60318CE11CF103925127F59EECD"),
+        hex!("35CD494ADEDED2F25239AF09A7B8EF0C4D1CA4FE2D1AC370FA63216FE7B4C2B1"),
+    ];
+    for (i, expected) in expected.iter().enumerate() {
+        let m = ptn(17usize.pow(i as u32));
+        assert_eq!(turbo_shake128(&m, 0x1F, 32)[..], expected[..]);
+    }
+}
+
+#[test]
+fn turbo_shake128_domains() {
+    let expected = [
+        (
+            3,
+            0x01,
+            hex!("BF323F940494E88EE1C540FE660BE8A0C93F43D15EC006998462FA994EED5DAB"),
+        ),
+        (
+            1,
+            0x06,
+            hex!("8EC9C66465ED0D4A6C35D13506718D687A25CB05C74CCA1E42501ABD83874A67"),
+        ),
+        (
+            3,
+            0x07,
+            hex!("B658576001CAD9B1E5F399A9F77723BBA05458042D68206F7252682DBA3663ED"),
+        ),
+        (
+            7,
+            0x0B,
+            hex!("8DEEAA1AEC47CCEE569F659C21DFA8E112DB3CEE37B18178B2ACD805B799CC37"),
+        ),
+        (
+            1,
+            0x30,
+            hex!("553122E2135E363C3292BED2C6421FA232BAB03DAA07C7D6636603286506325B"),
+        ),
+        (
+            3,
+            0x7F,
+            hex!("16274CC656D44CEFD422395D0F9053BDA6D28E122ABA15C765E5AD0E6EAF26F9"),
+        ),
+    ];
+    for &(len, domain, output) in &expected {
+        assert_eq!(turbo_shake128(&vec![0xFF; len], domain, 32)[..], output[..]);
+    }
+}
+
+#[test]
+fn turbo_shake256_empty() {
+    assert_eq!(
+        turbo_shake256(b"", 0x1F, 64)[..],
+        hex!(
+            "367A329DAFEA871C7802EC67F905AE13C57695DC2C6663C61035F59A18F8E7DB
+            11EDC0E12E91EA60EB6B32DF06DD7F002FBAFABB6E13EC1CC20D995547600DB0"
+        )[..]
+    );
+    assert_eq!(
+        turbo_shake256(b"", 0x1F, 10032)[10000..],
+        hex!("ABEFA11630C661269249742685EC082F207265DCCF2F43534E9C61BA0C9D1D75")
+    );
+}
+
+#[test]
+fn turbo_shake256_pat_m() {
+    let expected = [
+        hex!(
+            "3E1712F928F8EAF1054632B2AA0A246ED8B0C378728F60BC970410155C28820E
+            90CC90D8A3006AA2372C5C5EA176B0682BF22BAE7467AC94F74D43D39B0482E2"
+        ),
+        hex!(
+            "B3BAB0300E6A191FBE6137939835923578794EA54843F5011090FA2F3780A9E5
+            CB22C59D78B40A0FBFF9E672C0FBE0970BD2C845091C6044D687054DA5D8E9C7"
+        ),
+        hex!(
+            "66B810DB8E90780424C0847372FDC95710882FDE31C6DF75BEB9D4CD9305CFCA
+            E35E7B83E8B7E6EB4B78605880116316FE2C078A09B94AD7B8213C0A738B65C0"
+        ),
+        hex!(
+            "C74EBC919A5B3B0DD1228185BA02D29EF442D69D3D4276A93EFE0BF9A16A7DC0
+            CD4EABADAB8CD7A5EDD96695F5D360ABE09E2C6511A3EC397DA3B76B9E1674FB"
+        ),
+        hex!(
+            "02CC3A8897E6F4F6CCB6FD46631B1F5207B66C6DE9C7B55B2D1A23134A170AFD
+            AC234EABA9A77CFF88C1F020B73724618C5687B362C430B248CD38647F848A1D"
+        ),
+        hex!(
+            "ADD53B06543E584B5823F626996AEE50FE45ED15F20243A7165485ACB4AA76B4
+            FFDA75CEDF6D8CDC95C332BD56F4B986B58BB17D1778BFC1B1A97545CDF4EC9F"
+        ),
+        hex!(
+            "9E11BC59C24E73993C1484EC66358EF71DB74AEFD84E123F7800BA9C4853E02C
+            FE701D9E6BB765A304F0DC34A4EE3BA82C410F0DA70E86BFBD90EA877C2D6104"
+        ),
+    ];
+    for (i, expected) in expected.iter().enumerate() {
+        let m = ptn(17usize.pow(i as u32));
+        assert_eq!(turbo_shake256(&m, 0x1F, 64)[..], expected[..]);
+    }
+}
+
+#[test]
+fn turbo_shake256_domains() {
+    let expected = [
+        (
+            3,
+            0x01,
+            hex!(
+                "D21C6FBBF587FA2282F29AEA620175FB0257413AF78A0B1B2A87419CE031D933
+                AE7A4D383327A8A17641A34F8A1D1003AD7DA6B72DBA84BB62FEF28F62F12424"
+            ),
+        ),
+        (
+            1,
+            0x06,
+            hex!(
+                "738D7B4E37D18B7F22AD1B5313E357E3DD7D07056A26A303C433FA3533455280
+                F4F5A7D4F700EFB437FE6D281405E07BE32A0A972E22E63ADC1B090DAEFE004B"
+            ),
+        ),
+        (
+            3,
+            0x07,
+            hex!(
+                "18B3B5B7061C2E67C1753A00E6AD7ED7BA1C906CF93EFB7092EAF27FBEEBB755
+                AE6E292493C110E48D260028492B8E09B5500612B8F2578985DED5357D00EC67"
+            ),
+        ),
+        (
+            7,
+            0x0B,
+            hex!(
+                "BB36764951EC97E9D85F7EE9A67A7718FC005CF42556BE79CE12C0BDE50E5736
+                D6632B0D0DFB202D1BBB8FFE3DD74CB00834FA756CB03471BAB13A1E2C16B3C0"
+            ),
+        ),
+        (
+            1,
+            0x30,
+            hex!(
+                "F3FE12873D34BCBB2E608779D6B70E7F86BEC7E90BF113CBD4FDD0C4E2F4625E
+                148DD7EE1A52776CF77F240514D9CCFC3B5DDAB8EE255E39EE389072962C111A"
+            ),
+        ),
+        (
+            3,
+            0x7F,
+            hex!(
+                "ABE569C1F77EC340F02705E7D37C9AB7E155516E4A6A150021D70B6FAC0BB40C
+                069F9A9828A0D575CD99F9BAE435AB1ACF7ED9110BA97CE0388D074BAC768776"
+            ),
+        ),
+    ];
+    for &(len, domain, output) in &expected {
+        assert_eq!(turbo_shake256(&vec![0xFF; len], domain, 64), output[..]);
+    }
+}
+
+#[test]
+fn invalid_domain() {
+    for &domain in &[0x00, 0x80, 0xFF] {
+        assert_eq!(
+            TurboShake128::new_with_domain(domain).unwrap_err(),
+            Error::InvalidDomainSeparator
+        );
+        assert_eq!(
+            TurboShake256::new_with_domain(domain).unwrap_err(),
+            Error::InvalidDomainSeparator
+        );
+    }
+}
+
+#[test]
+#[cfg(feature = "digest")]
+fn turbo_shake128_digest() {
+    use k12::digest::{ExtendableOutput, Update};
+
+    let mut hasher = TurboShake128::default();
+    Update::update(&mut hasher, b"abc");
+    let output = hasher.finalize_boxed_reset(32);
+    assert_ne!(output[..], turbo_shake128(b"", 0x1F, 32)[..]);
+
+    assert_eq!(
+        hasher.finalize_boxed(32)[..],
+        turbo_shake128(b"", 0x1F, 32)[..]
+    );
+}
@@ -0,0 +1,111 @@
+//! Known-answer tests of Xoodoo[12] and of the hash mode of Xoodyak from the
+//! NIST lightweight cryptography competition, with messages `00 01 02 ...`.
+
+use hex_literal::hex;
+use k12::{XooSponge, XoodooState};
+
+fn pattern(len: usize) -> Vec<u8> {
+    (0..len).map(|j| j as u8).collect()
+}
+
+#[test]
+fn permute() {
+    let mut state = XoodooState::default();
+    state.permute();
+    assert_eq!(
+        state.to_bytes()[..],
+        hex!(
+            "8DD8D589BFFC63A9192D231B14A0A5FF0681B136FEC1C7AF
+            BE7CE5AEBD4075A770E8862EC9B7F5FEF2AD4F8B62404F5E"
+        )[..]
+    );
+
+    let mut bytes = [0u8; 48];
+    bytes.copy_from_slice(&pattern(48));
+    let mut state = XoodooState::from_bytes(&bytes);
+    assert_eq!(state.0[0], 0x0302_0100);
+    state.permute();
+    assert_eq!(
+        state.to_bytes()[..],
+        hex!(
+            "7633AEB55DCCBF60D4A6DFD7506D06BFB2AC97AE970D8AD3
+            1385117BB775A741B3B1540BB53BE96F3B2B8FAFA676A3B6"
+        )[..]
+    );
+}
+
+#[test]
+fn hash() {
+    let vectors: &[(usize, [u8; 32])] = &[
+        (
+            0,
+            hex!("EA152F2B47BCE24EFB66C479D4ADF17BD324D806E85FF75EE369EE50DC8F8BD1"),
+        ),
+        (
+            1,
+            hex!("27921F8DDF392894460B70B3ED6C091E6421B7D2147DCD6031D7EFEBAD3030CC"),
+        ),
+        (
+            15,
+            hex!("DB4C9CFE9D385D8CA329E27AEB495A0816C1AB051A57C231A134082661D71BED"),
+        ),
+        (
+            16,
+            hex!("9EA695347CDDDFF9BC63ECE30FE231441D581768FE223DD6BD7367094FD216B3"),
+        ),
+        (
+            17,
+            hex!("20593B39BB6D595019331601244411323F713085BB1A30218C972B96D9B7B7B3"),
+        ),
+        (
+            32,
+            hex!("CEBE4AFF9EAC2218017DDA5F8207BA830E989187256539BD7D31AE5E94FF0C6E"),
+        ),
+        (
+            33,
+            hex!("249CFCCD50D66E722E80E79002CE3B302B4CA067483AB9CDEB474DBF555B7633"),
+        ),
+        (
+            1024,
+            hex!("FCC4D63932D98C30CAB597E60B7CCA475BD9FBF984838C5CB5615C949F814615"),
+        ),
+    ];
+
+    for (len, expected) in vectors {
+        let message = pattern(*len);
+        let mut output = [0u8; 32];
+        XooSponge::new().chain(&message).finalize_into(&mut output);
+        assert_eq!(output, *expected, "length {}", len);
+
+        // The input is not delimited at the boundaries of `update` calls
+        for &chunk in &[1, 15, 16, 17] {
+            let mut sponge = XooSponge::new();
+            for part in message.chunks(chunk) {
+                sponge.update(part);
+            }
+            let mut output = [0u8; 32];
+            sponge.finalize_into(&mut output);
+            assert_eq!(output, *expected, "length {} in chunks of {}", len, chunk);
+        }
+    }
+}
+
+#[test]
+fn long_output() {
+    let mut output = [0u8; 100];
+    XooSponge::new().chain(b"abc").finalize_into(&mut output);
+    assert_eq!(
+        output[..],
+        hex!(
+            "661F71B331A0C1214441C4B4A811697E9109BC0B3C4E1E647C4D1127B18E2A1E
+            3D08222EDEC482770BBC33357C30669F1515B9587478470811E21D5E5B8B5F18
+            D810C39C33C990C7D13E213E33C10C35777BDA3D08B096097C57927ACA8CB537
+            A63B7266"
+        )[..]
+    );
+
+    // A prefix of the output does not depend on the output length
+    let mut short = [0u8; 17];
+    XooSponge::new().chain(b"abc").finalize_into(&mut short);
+    assert_eq!(short[..], output[..17]);
+}